@@ -0,0 +1,94 @@
+use std::error;
+use std::fmt;
+
+/// An error that can occur when processing an SDMX-CSV message.
+#[derive(Debug)]
+pub enum Error {
+	/// An error from the underlying CSV reader or writer.
+	Csv(csv::Error),
+	/// A column required by the SDMX-CSV format (or by the
+	/// configured options) is missing from the header row.
+	MissingColumn(&'static str),
+	/// A field could not be interpreted. Both the line and
+	/// the column are 1-based.
+	InvalidField {
+		line: u64,
+		column: usize,
+		kind: FieldErrorKind,
+	},
+}
+
+/// The kind of field which could not be interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldErrorKind {
+	Structure,
+	StructureId,
+	StructureName,
+	Action,
+	MetadataSetId,
+}
+
+/// An error that can occur when parsing a structure type,
+/// a structure identifier, or a structure name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseStructureError {
+	kind: FieldErrorKind,
+}
+
+impl ParseStructureError {
+	pub(crate) const fn new(kind: FieldErrorKind) -> Self {
+		Self { kind }
+	}
+
+	/// The kind of field which could not be parsed.
+	pub const fn kind(&self) -> FieldErrorKind {
+		self.kind
+	}
+}
+
+impl error::Error for ParseStructureError {}
+
+impl fmt::Display for ParseStructureError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid {}", self.kind)
+	}
+}
+
+impl error::Error for Error {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			Self::Csv(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Csv(e) => write!(f, "CSV error: {}", e),
+			Self::MissingColumn(name) => write!(f, "missing column: {}", name),
+			Self::InvalidField { line, column, kind } => {
+				write!(f, "invalid {} at line {}, column {}", kind, line, column)
+			}
+		}
+	}
+}
+
+impl fmt::Display for FieldErrorKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Structure => f.write_str("structure type"),
+			Self::StructureId => f.write_str("structure identifier"),
+			Self::StructureName => f.write_str("structure name"),
+			Self::Action => f.write_str("action"),
+			Self::MetadataSetId => f.write_str("metadata set identifier"),
+		}
+	}
+}
+
+impl From<csv::Error> for Error {
+	fn from(value: csv::Error) -> Self {
+		Self::Csv(value)
+	}
+}
@@ -0,0 +1,503 @@
+use super::{literal, structure_reference, ConvertError};
+use crate::{Action, CellValue, DataRecord, DataWriter, Label, Labels, Structure, StructureId};
+use sdmx_json::data::{
+	Component, ComponentValue, DataMessage, DataSet, Structure as JsonStructure,
+};
+use sdmx_json::primitives::SdmxValue;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io;
+
+/// Converts the datasets of an SDMX-JSON Data Message into the rows
+/// of an SDMX-CSV Data Message.
+///
+/// Each dataset is resolved against the structure it refers to, and each
+/// of its observations is written as a row: its dimensions are decoded
+/// from the series and observation keys, and its attributes are taken
+/// from the dataset, dimension group, series, and observation levels.
+/// A series without any observations is written as a single row, where
+/// the dimensions at the observation level are left empty.
+///
+/// The columns are the union of the components of all structures in the
+/// message, starting with the dimensions (in order of their key position),
+/// followed by the measures, then the attributes. The structure identifier
+/// of each row comes from the URN of the first link to a dataflow, a data
+/// structure, or a provision agreement, either from the dataset itself or
+/// otherwise from its structure.
+///
+/// # Example
+/// ```no_run
+/// use sdmx_csv::json::DataMessageExporter;
+/// use sdmx_csv::{DataOptions, DataWriter};
+/// use sdmx_json::data::DataMessage;
+/// use std::str::FromStr;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+///     let file = std::fs::read_to_string("sdmx-data.json")?;
+///     let message = DataMessage::from_str(&file)?;
+///
+///     let exporter = DataMessageExporter::new(&message);
+///     let mut writer = DataWriter::new(std::io::stdout(), DataOptions::default(), exporter.headers())?;
+///     exporter.write(&mut writer)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DataMessageExporter<'m> {
+	structures: &'m [JsonStructure],
+	data_sets: &'m [DataSet],
+	headers: Vec<Label<'m>>,
+	columns: HashMap<&'m str, usize>,
+}
+
+impl<'m> DataMessageExporter<'m> {
+	pub fn new(message: &'m DataMessage) -> Self {
+		let data = message.data.as_ref();
+		let structures = data
+			.and_then(|data| data.structures.as_deref())
+			.unwrap_or_default();
+		let data_sets = data
+			.and_then(|data| data.data_sets.as_deref())
+			.unwrap_or_default();
+
+		let mut headers = Vec::new();
+		let mut columns = HashMap::new();
+		for structure in structures {
+			for component in Layout::new(structure).components() {
+				columns.entry(component.id.as_str()).or_insert_with(|| {
+					headers.push(Label {
+						id: &component.id,
+						localized: component.name.as_deref(),
+					});
+					headers.len() - 1
+				});
+			}
+		}
+
+		Self {
+			structures,
+			data_sets,
+			headers,
+			columns,
+		}
+	}
+
+	/// The component columns, to create a [`DataWriter`] with.
+	pub fn headers(&self) -> impl Iterator<Item = (usize, Label<'m>)> + '_ {
+		self.headers.iter().copied().enumerate()
+	}
+
+	/// Writes all observations of all datasets as rows. The writer must
+	/// have been created with the [`headers`][Self::headers()] of this
+	/// exporter. Coded values are written according to the labels
+	/// the writer was configured with.
+	pub fn write<W: io::Write>(&self, writer: &mut DataWriter<W>) -> Result<(), ConvertError> {
+		for data_set in self.data_sets {
+			self.write_data_set(writer, data_set)?;
+		}
+		Ok(())
+	}
+
+	fn write_data_set<W: io::Write>(
+		&self,
+		writer: &mut DataWriter<W>,
+		data_set: &'m DataSet,
+	) -> Result<(), ConvertError> {
+		let index = data_set.structure.unwrap_or_default();
+		let structure = usize::try_from(index)
+			.ok()
+			.and_then(|index| self.structures.get(index))
+			.ok_or(ConvertError::MissingStructure(index))?;
+		let (structure_kind, structure_id) = data_set
+			.links
+			.as_deref()
+			.and_then(structure_reference)
+			.or_else(|| structure.links.as_deref().and_then(structure_reference))
+			.ok_or(ConvertError::MissingStructureReference)?;
+
+		let rows = Rows {
+			layout: Layout::new(structure),
+			columns: &self.columns,
+			labels: writer.options().labels,
+			structure: structure_kind,
+			structure_id,
+			action: data_set.action.map(Action::from).unwrap_or_default(),
+			groups: group_keys(data_set)?,
+		};
+
+		// dimensions at the dataset level have a single value
+		let mut key = vec![Some(0); rows.layout.dimensions.len()];
+		let mut cells = Vec::new();
+		rows.attribute_cells(
+			&mut cells,
+			rows.layout.attributes[DATA_SET],
+			data_set.attributes.as_deref(),
+		)?;
+		let data_set_cells = cells.len();
+
+		for (indices, raw, series) in sorted_entries(data_set.series.as_ref())? {
+			cells.truncate(data_set_cells);
+			rows.assign(&mut key, &rows.layout.series_slots, &indices, raw)?;
+			for slot in &rows.layout.obs_slots {
+				key[*slot] = None;
+			}
+			rows.attribute_cells(
+				&mut cells,
+				rows.layout.attributes[SERIES],
+				series.attributes.as_deref(),
+			)?;
+			let series_cells = cells.len();
+
+			let observations = sorted_entries(series.observations.as_ref())?;
+			if observations.is_empty() {
+				rows.write_row(writer, &key, &cells)?;
+			}
+			for (indices, raw, values) in observations {
+				cells.truncate(series_cells);
+				rows.assign(&mut key, &rows.layout.obs_slots, &indices, raw)?;
+				rows.observation_cells(&mut cells, values)?;
+				rows.write_row(writer, &key, &cells)?;
+			}
+		}
+
+		let flat_slots: Vec<usize> = rows
+			.layout
+			.series_slots
+			.iter()
+			.chain(&rows.layout.obs_slots)
+			.copied()
+			.collect();
+		for (indices, raw, values) in sorted_entries(data_set.observations.as_ref())? {
+			cells.truncate(data_set_cells);
+			rows.assign(&mut key, &flat_slots, &indices, raw)?;
+			rows.observation_cells(&mut cells, values)?;
+			rows.write_row(writer, &key, &cells)?;
+		}
+
+		Ok(())
+	}
+}
+
+const DATA_SET: usize = 0;
+const DIMENSION_GROUP: usize = 1;
+const SERIES: usize = 2;
+const OBSERVATION: usize = 3;
+
+/// The components of a structure, arranged to decode the keys
+/// and values of its datasets.
+#[derive(Debug, Clone)]
+struct Layout<'m> {
+	/// All dimensions, in order of their key position.
+	dimensions: Vec<&'m Component>,
+	/// Whether each dimension is at the observation level.
+	at_observation: Vec<bool>,
+	/// The position in `dimensions` for each dimension of a series key.
+	series_slots: Vec<usize>,
+	/// The position in `dimensions` for each dimension of an observation key.
+	obs_slots: Vec<usize>,
+	measures: &'m [Component],
+	/// The attributes at the dataset, dimension group,
+	/// series, and observation levels.
+	attributes: [&'m [Component]; 4],
+}
+
+impl<'m> Layout<'m> {
+	fn new(structure: &'m JsonStructure) -> Self {
+		let dims = &structure.dimensions;
+		let levels = [
+			dims.data_set.as_deref().unwrap_or_default(),
+			dims.series.as_deref().unwrap_or_default(),
+			dims.observation.as_deref().unwrap_or_default(),
+		];
+
+		// dimensions without a key position are kept last, in order
+		let mut ordered: Vec<(usize, &'m Component)> = levels
+			.iter()
+			.enumerate()
+			.flat_map(|(level, components)| components.iter().map(move |c| (level, c)))
+			.collect();
+		ordered.sort_by_key(|(_, c)| c.key_position.unwrap_or(usize::MAX));
+
+		let slots = |level: usize| -> Vec<usize> {
+			levels[level]
+				.iter()
+				.map(|c| {
+					ordered
+						.iter()
+						.position(|(_, other)| std::ptr::eq(c, *other))
+						.unwrap_or_default()
+				})
+				.collect()
+		};
+		let series_slots = slots(1);
+		let obs_slots = slots(2);
+
+		let attributes = &structure.attributes;
+		Self {
+			at_observation: ordered.iter().map(|(level, _)| *level == 2).collect(),
+			dimensions: ordered.into_iter().map(|(_, c)| c).collect(),
+			series_slots,
+			obs_slots,
+			measures: structure
+				.measures
+				.as_ref()
+				.and_then(|m| m.observation.as_deref())
+				.unwrap_or_default(),
+			attributes: [
+				attributes.data_set.as_deref().unwrap_or_default(),
+				attributes.dimension_group.as_deref().unwrap_or_default(),
+				attributes.series.as_deref().unwrap_or_default(),
+				attributes.observation.as_deref().unwrap_or_default(),
+			],
+		}
+	}
+
+	fn components(&self) -> impl Iterator<Item = &'m Component> + '_ {
+		self.dimensions
+			.iter()
+			.copied()
+			.chain(self.measures)
+			.chain(self.attributes.iter().flat_map(|a| a.iter()))
+	}
+}
+
+/// The context for writing the rows of a single dataset.
+struct Rows<'m, 'e> {
+	layout: Layout<'m>,
+	columns: &'e HashMap<&'m str, usize>,
+	labels: Labels,
+	structure: Structure,
+	structure_id: StructureId<'m>,
+	action: Action,
+	/// The keys of dimension groups (with a wildcard for each dimension
+	/// not in the group), and their attribute values.
+	groups: Vec<Group<'m>>,
+}
+
+impl<'m> Rows<'m, '_> {
+	/// Assigns the indices of a series or observation key
+	/// to the positions of their dimensions.
+	fn assign(
+		&self,
+		key: &mut [Option<usize>],
+		slots: &[usize],
+		indices: &[usize],
+		raw: &str,
+	) -> Result<(), ConvertError> {
+		if slots.len() != indices.len() {
+			return Err(ConvertError::InvalidKey(raw.to_owned()));
+		}
+		for (slot, index) in slots.iter().zip(indices) {
+			key[*slot] = Some(*index);
+		}
+		Ok(())
+	}
+
+	fn attribute_cells(
+		&self,
+		cells: &mut Vec<Cell<'m>>,
+		components: &'m [Component],
+		values: Option<&'m [SdmxValue]>,
+	) -> Result<(), ConvertError> {
+		for (component, value) in components.iter().zip(values.unwrap_or_default()) {
+			if let Some((cell, name)) = self.value(component, value)? {
+				cells.push((self.columns[component.id.as_str()], cell, name));
+			}
+		}
+		Ok(())
+	}
+
+	/// The values of an observation are its measures,
+	/// followed by its attributes.
+	fn observation_cells(
+		&self,
+		cells: &mut Vec<Cell<'m>>,
+		values: &'m [SdmxValue],
+	) -> Result<(), ConvertError> {
+		let (measures, attributes) = values.split_at(self.layout.measures.len().min(values.len()));
+		self.attribute_cells(cells, self.layout.measures, Some(measures))?;
+		self.attribute_cells(cells, self.layout.attributes[OBSERVATION], Some(attributes))
+	}
+
+	/// Formats a value, which is an index into the values of
+	/// its component if the component is coded. A single coded value
+	/// is returned as its ID, along with its localized name (if any)
+	/// for the writer to label it.
+	fn value(
+		&self,
+		component: &'m Component,
+		value: &'m SdmxValue,
+	) -> Result<Option<Labelled<'m>>, ConvertError> {
+		let coded = component.values.as_ref().is_some_and(|v| !v.is_empty());
+		match value {
+			SdmxValue::Integer(index) if coded => Ok(code(component, *index)?
+				.map(|value| (Cow::Borrowed(value.id.as_str()), name(value)))),
+			SdmxValue::Array(indices) if coded => {
+				let mut values = Vec::with_capacity(indices.len());
+				for index in indices.iter() {
+					if let Some((id, localized)) = self.value(component, index)? {
+						values.push(Cow::Owned(
+							Label { id: &id, localized }
+								.format(self.labels)
+								.into_owned(),
+						));
+					}
+				}
+				Ok(Some((
+					Cow::Owned(CellValue::Array(values).to_string()),
+					None,
+				)))
+			}
+			_ => Ok(literal(value).map(|cell| (cell, None))),
+		}
+	}
+
+	fn write_row<W: io::Write>(
+		&self,
+		writer: &mut DataWriter<W>,
+		key: &[Option<usize>],
+		cells: &[Cell<'m>],
+	) -> Result<(), ConvertError> {
+		let mut ids = Vec::with_capacity(key.len());
+		let mut dimension_cells = Vec::with_capacity(key.len());
+		for (component, index) in self.layout.dimensions.iter().copied().zip(key) {
+			let Some(index) = *index else {
+				ids.push("");
+				continue;
+			};
+			let value = isize::try_from(index)
+				.ok()
+				.and_then(|index| code(component, index).transpose())
+				.unwrap_or_else(|| Err(invalid_index(component, index as isize)))?;
+			ids.push(value.id.as_str());
+			dimension_cells.push((
+				self.columns[component.id.as_str()],
+				Cow::Borrowed(value.id.as_str()),
+				name(value),
+			));
+		}
+
+		let mut group_cells = Vec::new();
+		for (group_key, values) in &self.groups {
+			let matches = group_key
+				.iter()
+				.zip(key)
+				.all(|(group, index)| group.map_or(true, |group| Some(group) == *index));
+			if matches {
+				self.attribute_cells(
+					&mut group_cells,
+					self.layout.attributes[DIMENSION_GROUP],
+					Some(values),
+				)?;
+			}
+		}
+
+		let series_key: Vec<&str> = ids
+			.iter()
+			.zip(&self.layout.at_observation)
+			.filter(|(_, at_observation)| !**at_observation)
+			.map(|(id, _)| *id)
+			.collect();
+		let series_key = series_key.join(".");
+		// a series without observations has no observation key
+		let obs_key = match key.contains(&None) {
+			true => None,
+			false => Some(ids.join(".")),
+		};
+
+		let all_cells = || dimension_cells.iter().chain(&group_cells).chain(cells);
+		let components = all_cells()
+			.map(|(column, cell, _)| (*column, cell.as_ref()))
+			.collect();
+		let names = all_cells()
+			.filter_map(|(column, _, name)| Some((*column, (*name)?)))
+			.collect();
+		let record = DataRecord {
+			structure: self.structure,
+			structure_id: self.structure_id,
+			structure_name: None,
+			action: self.action,
+			series_key: Some(&series_key),
+			obs_key: obs_key.as_deref(),
+			components,
+			other: HashMap::new(),
+		};
+		Ok(writer.write_labelled_record(&record, &names)?)
+	}
+}
+
+/// A formatted value, with the localized name of its coded value (if any).
+type Labelled<'m> = (Cow<'m, str>, Option<&'m str>);
+
+/// A cell of a row, with the localized name of its coded value (if any).
+type Cell<'m> = (usize, Cow<'m, str>, Option<&'m str>);
+
+/// The localized name of a coded value, except for a time period
+/// (a value with a start or an end), which is always written as-is.
+fn name(value: &ComponentValue) -> Option<&str> {
+	match value.start.is_some() || value.end.is_some() {
+		true => None,
+		false => value.name.as_deref(),
+	}
+}
+
+/// Looks up a coded value by its index, where a value may be
+/// `null` (e.g. when it is not used by the message).
+fn code(component: &Component, index: isize) -> Result<Option<&ComponentValue>, ConvertError> {
+	let values = component.values.as_deref().unwrap_or_default();
+	match usize::try_from(index).ok().and_then(|i| values.get(i)) {
+		Some(value) => Ok(value.as_ref()),
+		None => Err(invalid_index(component, index)),
+	}
+}
+
+fn invalid_index(component: &Component, index: isize) -> ConvertError {
+	ConvertError::InvalidValueIndex {
+		component: component.id.clone(),
+		index,
+	}
+}
+
+/// A parsed series or observation key, the key as-is, and its value.
+type Entry<'m, T> = (Vec<usize>, &'m str, &'m T);
+
+/// Parses the keys of a map of series or observations, and
+/// sorts the entries by their key.
+fn sorted_entries<T>(map: Option<&HashMap<String, T>>) -> Result<Vec<Entry<'_, T>>, ConvertError> {
+	let mut entries = Vec::new();
+	for (raw, value) in map.into_iter().flatten() {
+		let indices = match raw.as_str() {
+			"" => Vec::new(),
+			_ => raw
+				.split(':')
+				.map(|index| index.parse::<usize>())
+				.collect::<Result<_, _>>()
+				.map_err(|_| ConvertError::InvalidKey(raw.clone()))?,
+		};
+		entries.push((indices, raw.as_str(), value));
+	}
+	entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+	Ok(entries)
+}
+
+/// A dimension group key, and its attribute values.
+type Group<'m> = (Vec<Option<usize>>, &'m [SdmxValue]);
+
+/// Parses the keys of the dimension group attributes, where a dimension
+/// which is not part of a group is left empty (e.g. `0::1:`).
+fn group_keys(data_set: &DataSet) -> Result<Vec<Group<'_>>, ConvertError> {
+	let mut groups = Vec::new();
+	for (raw, values) in data_set.dimension_group_attributes.iter().flatten() {
+		let key: Vec<Option<usize>> = raw
+			.split(':')
+			.map(|index| match index {
+				"" => Ok(None),
+				index => index.parse::<usize>().map(Some),
+			})
+			.collect::<Result<_, _>>()
+			.map_err(|_| ConvertError::InvalidKey(raw.clone()))?;
+		groups.push((key, values.as_slice()));
+	}
+	groups.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+	Ok(groups)
+}
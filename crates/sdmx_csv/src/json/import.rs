@@ -0,0 +1,635 @@
+use super::ConvertError;
+use crate::{
+	Action, CellValue, DataRecord, InternedHeaders, Label, Labels, OwnedStructureId, Structure,
+};
+use sdmx_json::data::{
+	AttributeRelationship as JsonRelationship, Component, ComponentValue, Data, DataMessage,
+	DataSet, DimsMeasuresAttributes, Series, Structure as JsonStructure,
+};
+use sdmx_json::primitives::{Link, Location, SdmxValue};
+use sdmx_json::structure::{AttributeRelationship, DataStructure};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Builds an SDMX-JSON Data Message from the records of an SDMX-CSV
+/// Data Message, according to the data structure definition they use.
+///
+/// The dimensions of the data structure are split between the series
+/// level and the observation level (the time dimension, if any), and the
+/// series and observation keys are encoded as indices into the values of
+/// each dimension. The values of the dimensions and the coded attributes
+/// are those actually used by the records, in order of their first use.
+///
+/// Attributes are placed at the level dictated by their relationship:
+/// - a relationship to the dataflow is at the dataset level,
+/// - a relationship to the observation, or to dimensions which include
+///   the time dimension, is at the observation level,
+/// - a relationship to all the other dimensions is at the series level,
+/// - and a relationship to a group, or to only some dimensions,
+///   is at the dimension group level.
+///
+/// A dataset is created for each distinct combination of the structure,
+/// the structure identifier, and the action of the records.
+///
+/// # Example
+/// ```no_run
+/// use csv::StringRecord;
+/// use sdmx_csv::json::DataMessageImporter;
+/// use sdmx_csv::{DataOptions, DataReader};
+/// use sdmx_json::structure::DataStructure;
+///
+/// fn import(
+///     file: std::fs::File,
+///     structure: &DataStructure,
+/// ) -> Result<sdmx_json::data::DataMessage, Box<dyn std::error::Error + 'static>> {
+///     let options = DataOptions::default();
+///     let mut reader = DataReader::new(file, options)?;
+///     let mut importer = DataMessageImporter::new(structure, &reader.headers(), options.labels);
+///
+///     let mut buffer = StringRecord::new();
+///     while let Some(record) = reader.read_record(&mut buffer)? {
+///         importer.add_record(&record)?;
+///     }
+///     Ok(importer.finish())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DataMessageImporter {
+	labels: Labels,
+	structure_link: Option<Link>,
+	/// All dimensions, in order of their position. The time
+	/// dimension (if any) is always last.
+	dimensions: Vec<ImportedComponent>,
+	has_time_dimension: bool,
+	measures: Vec<ImportedComponent>,
+	attributes: Vec<ImportedComponent>,
+	data_sets: Vec<ImportedDataSet>,
+}
+
+/// The level of an attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Level {
+	DataSet,
+	/// The positions of the dimensions in the group.
+	DimensionGroup(Vec<usize>),
+	Series,
+	Observation,
+}
+
+#[derive(Debug, Clone)]
+struct ImportedComponent {
+	id: String,
+	column: Option<usize>,
+	coded: bool,
+	multilingual: bool,
+	/// Only used for attributes.
+	level: Level,
+	relationship: Option<JsonRelationship>,
+	/// The position of this attribute among the
+	/// other attributes at the same level.
+	offset: usize,
+	values: Vec<ComponentValue>,
+	indices: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone)]
+struct ImportedDataSet {
+	structure: Structure,
+	structure_id: OwnedStructureId,
+	action: Action,
+	attributes: Vec<SdmxValue>,
+	groups: BTreeMap<String, Vec<SdmxValue>>,
+	series: BTreeMap<String, ImportedSeries>,
+	observations: BTreeMap<String, Vec<SdmxValue>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ImportedSeries {
+	attributes: Vec<SdmxValue>,
+	observations: BTreeMap<String, Vec<SdmxValue>>,
+}
+
+impl DataMessageImporter {
+	/// Creates an importer for records read with the given headers.
+	/// When the labels are configured as [`Labels::Both`], coded values
+	/// are expected to be in the format of `<id>: <localized name>`.
+	/// Records read with [`Labels::Name`] cannot be imported, as their
+	/// headers and coded values are localized names rather than IDs.
+	pub fn new(structure: &DataStructure, headers: &InternedHeaders<'_>, labels: Labels) -> Self {
+		let components = structure.data_structure_components.as_ref();
+		let column = |id: &str| headers.position(id);
+
+		let mut dimensions: Vec<(usize, ImportedComponent)> = components
+			.and_then(|c| c.dimension_list.dimensions.as_ref())
+			.into_iter()
+			.flatten()
+			.map(|dimension| {
+				let id = dimension
+					.id
+					.clone()
+					.unwrap_or_else(|| dimension.concept_id().to_owned());
+				let component = ImportedComponent::new(id, column, true, false);
+				(dimension.position, component)
+			})
+			.collect();
+		dimensions.sort_by_key(|(position, _)| *position);
+		let mut dimensions: Vec<ImportedComponent> =
+			dimensions.into_iter().map(|(_, c)| c).collect();
+
+		let time = components.and_then(|c| c.dimension_list.time_dimensions.as_ref());
+		let has_time_dimension = time.is_some();
+		if let Some(time) = time {
+			let id = time
+				.id
+				.clone()
+				.unwrap_or_else(|| time.concept_id().to_owned());
+			dimensions.push(ImportedComponent::new(id, column, true, false));
+		}
+
+		let measures = components
+			.and_then(|c| c.measure_list.as_ref())
+			.and_then(|m| m.measures.as_ref())
+			.into_iter()
+			.flatten()
+			.map(|measure| {
+				let representation = &measure.local_representation;
+				ImportedComponent::new(
+					measure.id.clone(),
+					column,
+					representation.enumeration.is_some(),
+					false,
+				)
+			})
+			.collect();
+
+		let groups = components.and_then(|c| c.groups.as_ref());
+		let mut offsets = HashMap::new();
+		let attributes = components
+			.and_then(|c| c.attribute_list.as_ref())
+			.and_then(|a| a.attributes.as_ref())
+			.into_iter()
+			.flatten()
+			.map(|attribute| {
+				let representation = &attribute.local_representation;
+				let multilingual = representation
+					.format
+					.as_ref()
+					.is_some_and(|f| f.is_multilingual);
+				let mut component = ImportedComponent::new(
+					attribute.id.clone(),
+					column,
+					representation.enumeration.is_some(),
+					multilingual,
+				);
+
+				let (level, relationship) = level(
+					&attribute.attribute_relationship,
+					&dimensions,
+					has_time_dimension,
+					groups.map(Vec::as_slice).unwrap_or_default(),
+				);
+				let offset = offsets.entry(level_kind(&level)).or_insert(0);
+				component.offset = *offset;
+				*offset += 1;
+				component.level = level;
+				component.relationship = Some(relationship);
+				component
+			})
+			.collect();
+
+		let structure_link = structure_urn(
+			"datastructure.DataStructure",
+			structure.common.agency_id.as_deref(),
+			&structure.common.id,
+			structure.common.version.as_deref(),
+		)
+		.map(|urn| link(urn, "datastructure"));
+
+		Self {
+			labels,
+			structure_link,
+			dimensions,
+			has_time_dimension,
+			measures,
+			attributes,
+			data_sets: Vec::new(),
+		}
+	}
+
+	/// Adds the observation of a record to its dataset.
+	pub fn add_record(&mut self, record: &DataRecord<'_>) -> Result<(), ConvertError> {
+		let labels = self.labels;
+		let mut key = Vec::with_capacity(self.dimensions.len());
+		for dimension in &mut self.dimensions {
+			let cell = dimension
+				.cell(record)
+				.ok_or_else(|| ConvertError::MissingDimension(dimension.id.clone()))?;
+			key.push(dimension.intern(cell, labels));
+		}
+
+		let series_len = key.len() - usize::from(self.has_time_dimension);
+		let series_key = join_key(&key[..series_len]);
+		let obs_key = join_key(&key[series_len..]);
+
+		let mut values = Vec::with_capacity(self.measures.len());
+		for measure in &mut self.measures {
+			values.push(measure.value(record, labels));
+		}
+
+		let data_set = data_set(&mut self.data_sets, record);
+		for attribute in &mut self.attributes {
+			let value = attribute.value(record, labels);
+			if value == SdmxValue::Null {
+				continue;
+			}
+
+			let offset = attribute.offset;
+			match &attribute.level {
+				Level::DataSet => set(&mut data_set.attributes, offset, value),
+				Level::DimensionGroup(positions) => {
+					let group_key = key
+						.iter()
+						.enumerate()
+						.map(|(position, index)| match positions.contains(&position) {
+							true => index.to_string(),
+							false => String::new(),
+						})
+						.collect::<Vec<_>>()
+						.join(":");
+					let group = data_set.groups.entry(group_key).or_default();
+					set(group, offset, value);
+				}
+				Level::Series => {
+					let series = data_set.series.entry(series_key.clone()).or_default();
+					set(&mut series.attributes, offset, value);
+				}
+				Level::Observation => set(&mut values, self.measures.len() + offset, value),
+			}
+		}
+
+		if self.has_time_dimension {
+			let series = data_set.series.entry(series_key).or_default();
+			series.observations.insert(obs_key, values);
+		} else {
+			data_set.observations.insert(series_key, values);
+		}
+		Ok(())
+	}
+
+	/// Builds the data message from all the records added so far.
+	pub fn finish(self) -> DataMessage {
+		let mut dimensions: Vec<Component> = self
+			.dimensions
+			.iter()
+			.enumerate()
+			.map(|(position, dimension)| Component {
+				key_position: Some(position),
+				..dimension.to_component()
+			})
+			.collect();
+		let time = match self.has_time_dimension {
+			true => dimensions.pop().map(|time| vec![time]),
+			false => None,
+		};
+
+		let attributes_at = |kind: u8| -> Option<Vec<Component>> {
+			let attributes: Vec<Component> = self
+				.attributes
+				.iter()
+				.filter(|a| level_kind(&a.level) == kind)
+				.map(|a| Component {
+					relationship: a.relationship.clone(),
+					..a.to_component()
+				})
+				.collect();
+			(!attributes.is_empty()).then_some(attributes)
+		};
+
+		let structure = JsonStructure {
+			links: self.structure_link.clone().map(|link| vec![link]),
+			dimensions: DimsMeasuresAttributes {
+				series: (!dimensions.is_empty()).then_some(dimensions),
+				observation: time,
+				..Default::default()
+			},
+			measures: Some(DimsMeasuresAttributes {
+				observation: Some(self.measures.iter().map(|m| m.to_component()).collect()),
+				..Default::default()
+			}),
+			attributes: DimsMeasuresAttributes {
+				data_set: attributes_at(0),
+				dimension_group: attributes_at(1),
+				series: attributes_at(2),
+				observation: attributes_at(3),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		let data_sets = self
+			.data_sets
+			.into_iter()
+			.map(ImportedDataSet::into_data_set)
+			.collect();
+
+		DataMessage {
+			data: Some(Data {
+				structures: Some(vec![structure]),
+				data_sets: Some(data_sets),
+				other: None,
+			}),
+			..Default::default()
+		}
+	}
+}
+
+impl ImportedComponent {
+	fn new(
+		id: String,
+		column: impl Fn(&str) -> Option<usize>,
+		coded: bool,
+		multilingual: bool,
+	) -> Self {
+		Self {
+			column: column(&id),
+			id,
+			coded,
+			multilingual,
+			level: Level::Observation,
+			relationship: None,
+			offset: 0,
+			values: Vec::new(),
+			indices: HashMap::new(),
+		}
+	}
+
+	fn cell<'r>(&self, record: &DataRecord<'r>) -> Option<&'r str> {
+		record.components.get(&self.column?).copied()
+	}
+
+	/// Returns the index of a coded value, adding it to
+	/// the values of this component if it is not used yet.
+	fn intern(&mut self, cell: &str, labels: Labels) -> usize {
+		let label = match labels {
+			Labels::Both => Label::parse(cell),
+			_ => Label::from(cell),
+		};
+		if let Some(index) = self.indices.get(label.id) {
+			return *index;
+		}
+
+		let index = self.values.len();
+		self.values.push(ComponentValue {
+			id: label.id.to_owned(),
+			name: label.localized.map(str::to_owned),
+			..Default::default()
+		});
+		self.indices.insert(label.id.to_owned(), index);
+		index
+	}
+
+	fn value(&mut self, record: &DataRecord<'_>, labels: Labels) -> SdmxValue {
+		let Some(cell) = self.cell(record) else {
+			return SdmxValue::Null;
+		};
+
+		match CellValue::parse(cell) {
+			CellValue::Array(values) => {
+				let values = values.iter().map(|v| self.single_value(v, labels));
+				SdmxValue::Array(Box::new(values.collect()))
+			}
+			_ if self.multilingual => match CellValue::parse_localized(cell) {
+				CellValue::Localized(pairs) => SdmxValue::LocalizedText(
+					pairs
+						.into_iter()
+						.map(|(locale, text)| (locale.into_owned(), text.into_owned()))
+						.collect(),
+				),
+				_ => SdmxValue::String(cell.to_owned()),
+			},
+			CellValue::Single(value) => self.single_value(&value, labels),
+			CellValue::Localized(_) => SdmxValue::String(cell.to_owned()),
+		}
+	}
+
+	/// A coded value is encoded as its index, and an uncoded
+	/// value as a number if possible, otherwise as a string.
+	fn single_value(&mut self, value: &str, labels: Labels) -> SdmxValue {
+		if self.coded {
+			return SdmxValue::Integer(self.intern(value, labels) as isize);
+		}
+		if let Ok(integer) = value.parse::<isize>() {
+			return SdmxValue::Integer(integer);
+		}
+		match value.parse::<f64>() {
+			Ok(number) if number.is_finite() => SdmxValue::Number(number),
+			_ => SdmxValue::String(value.to_owned()),
+		}
+	}
+
+	fn to_component(&self) -> Component {
+		Component {
+			id: self.id.clone(),
+			values: (self.coded || !self.values.is_empty())
+				.then(|| self.values.iter().cloned().map(Some).collect()),
+			..Default::default()
+		}
+	}
+}
+
+impl ImportedDataSet {
+	fn into_data_set(self) -> DataSet {
+		let links = structure_urn(
+			match self.structure {
+				Structure::DataFlow => "datastructure.Dataflow",
+				Structure::DataProvision => "registry.ProvisionAgreement",
+				_ => "datastructure.DataStructure",
+			},
+			Some(self.structure_id.agency()),
+			self.structure_id.artefact_id(),
+			Some(self.structure_id.version()),
+		)
+		.map(|urn| {
+			let rel = match self.structure {
+				Structure::DataFlow => "dataflow",
+				Structure::DataProvision => "provisionagreement",
+				_ => "datastructure",
+			};
+			vec![link(urn, rel)]
+		});
+
+		let series: HashMap<String, Series> = self
+			.series
+			.into_iter()
+			.map(|(key, series)| {
+				let series = Series {
+					attributes: (!series.attributes.is_empty()).then_some(series.attributes),
+					observations: (!series.observations.is_empty())
+						.then(|| series.observations.into_iter().collect()),
+					..Default::default()
+				};
+				(key, series)
+			})
+			.collect();
+
+		DataSet {
+			structure: Some(0),
+			action: Some(self.action.into()),
+			links,
+			attributes: (!self.attributes.is_empty()).then_some(self.attributes),
+			dimension_group_attributes: (!self.groups.is_empty())
+				.then(|| self.groups.into_iter().collect()),
+			series: (!series.is_empty()).then_some(series),
+			observations: (!self.observations.is_empty())
+				.then(|| self.observations.into_iter().collect()),
+			..Default::default()
+		}
+	}
+}
+
+/// Finds the dataset for a record, or creates it.
+fn data_set<'d>(
+	data_sets: &'d mut Vec<ImportedDataSet>,
+	record: &DataRecord<'_>,
+) -> &'d mut ImportedDataSet {
+	let position = data_sets.iter().position(|d| {
+		d.structure == record.structure
+			&& d.structure_id.as_structure_id() == record.structure_id
+			&& d.action == record.action
+	});
+	match position {
+		Some(position) => &mut data_sets[position],
+		None => {
+			data_sets.push(ImportedDataSet {
+				structure: record.structure,
+				structure_id: record.structure_id.into(),
+				action: record.action,
+				attributes: Vec::new(),
+				groups: BTreeMap::new(),
+				series: BTreeMap::new(),
+				observations: BTreeMap::new(),
+			});
+			data_sets.last_mut().unwrap()
+		}
+	}
+}
+
+/// Determines the level of an attribute from its relationship, and
+/// converts the relationship to its SDMX-JSON Data Message equivalent.
+fn level(
+	relationship: &AttributeRelationship,
+	dimensions: &[ImportedComponent],
+	has_time_dimension: bool,
+	groups: &[sdmx_json::structure::Group],
+) -> (Level, JsonRelationship) {
+	let empty = || Value::Object(Map::new());
+	let positions = |ids: &[String]| -> Vec<usize> {
+		ids.iter()
+			.filter_map(|id| dimensions.iter().position(|d| &d.id == id))
+			.collect()
+	};
+	let at_dimensions = |ids: &[String]| {
+		let positions = positions(ids);
+		let series_len = dimensions.len() - usize::from(has_time_dimension);
+		let level = if positions.is_empty() {
+			Level::DataSet
+		} else if positions.iter().any(|p| *p >= series_len) {
+			Level::Observation
+		} else if positions.len() == series_len {
+			// without a time dimension, observations are not grouped
+			// into series, so an attribute of the whole key is one
+			// of an observation
+			match has_time_dimension {
+				true => Level::Series,
+				false => Level::Observation,
+			}
+		} else {
+			Level::DimensionGroup(positions)
+		};
+		let relationship = JsonRelationship {
+			dimensions: Some(ids.to_vec()),
+			..Default::default()
+		};
+		(level, relationship)
+	};
+
+	match relationship {
+		AttributeRelationship::DataFlow(_) => (
+			Level::DataSet,
+			JsonRelationship {
+				dataflow: Some(empty()),
+				..Default::default()
+			},
+		),
+		AttributeRelationship::Dimensions(d) => {
+			at_dimensions(d.dimensions.as_deref().unwrap_or_default())
+		}
+		AttributeRelationship::Groups(g) => {
+			let ids = groups
+				.iter()
+				.find(|group| group.id == g.group)
+				.and_then(|group| group.group_dimensions.as_deref())
+				.unwrap_or_default();
+			at_dimensions(ids)
+		}
+		AttributeRelationship::Observations(_) => (
+			Level::Observation,
+			JsonRelationship {
+				observation: Some(empty()),
+				..Default::default()
+			},
+		),
+	}
+}
+
+/// Identifies a level regardless of the dimensions in a group,
+/// in the order the levels appear in a data message.
+fn level_kind(level: &Level) -> u8 {
+	match level {
+		Level::DataSet => 0,
+		Level::DimensionGroup(_) => 1,
+		Level::Series => 2,
+		Level::Observation => 3,
+	}
+}
+
+/// Sets a value in a list of values, filling any gap with `null`.
+fn set(values: &mut Vec<SdmxValue>, position: usize, value: SdmxValue) {
+	if values.len() <= position {
+		values.resize(position + 1, SdmxValue::Null);
+	}
+	values[position] = value;
+}
+
+fn join_key(key: &[usize]) -> String {
+	key.iter()
+		.map(usize::to_string)
+		.collect::<Vec<_>>()
+		.join(":")
+}
+
+fn structure_urn(
+	class: &str,
+	agency: Option<&str>,
+	id: &str,
+	version: Option<&str>,
+) -> Option<String> {
+	Some(format!(
+		"urn:sdmx:org.sdmx.infomodel.{}={}:{}({})",
+		class, agency?, id, version?
+	))
+}
+
+fn link(urn: String, rel: &str) -> Link {
+	Link {
+		location: Location::Urn(urn),
+		rel: rel.to_owned(),
+		url: None,
+		uri: None,
+		title: None,
+		titles: None,
+		type_: None,
+		hreflang: None,
+		other: None,
+	}
+}
@@ -0,0 +1,146 @@
+//! Conversions between SDMX-JSON and SDMX-CSV data messages.
+
+mod export;
+mod import;
+
+pub use export::*;
+pub use import::*;
+
+use crate::{Action, CellValue, Error, Structure, StructureId};
+use sdmx_json::primitives::{Action as JsonAction, Link, Location, SdmxValue};
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+
+/// An error that can occur when converting between SDMX-JSON
+/// and SDMX-CSV messages.
+#[derive(Debug)]
+pub enum ConvertError {
+	/// An error from reading or writing the SDMX-CSV message.
+	Csv(Error),
+	/// A dataset refers to a structure which is not in the message.
+	MissingStructure(isize),
+	/// Neither a dataset nor its structure links to a
+	/// dataflow, a data structure, or a provision agreement
+	/// with a URN.
+	MissingStructureReference,
+	/// A series or observation key does not match
+	/// the dimensions of its structure.
+	InvalidKey(String),
+	/// A record has no value for a dimension of the data structure.
+	MissingDimension(String),
+	/// A value refers to an index which is out of range
+	/// for the values of its component.
+	InvalidValueIndex { component: String, index: isize },
+}
+
+impl error::Error for ConvertError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			Self::Csv(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for ConvertError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Csv(e) => e.fmt(f),
+			Self::MissingStructure(index) => write!(f, "missing structure at index {}", index),
+			Self::MissingStructureReference => f.write_str("missing structure reference"),
+			Self::InvalidKey(key) => write!(f, "invalid key: {}", key),
+			Self::MissingDimension(id) => write!(f, "missing value for dimension {}", id),
+			Self::InvalidValueIndex { component, index } => {
+				write!(
+					f,
+					"invalid value index {} for component {}",
+					index, component
+				)
+			}
+		}
+	}
+}
+
+impl From<Error> for ConvertError {
+	fn from(value: Error) -> Self {
+		Self::Csv(value)
+	}
+}
+
+impl From<JsonAction> for Action {
+	fn from(value: JsonAction) -> Self {
+		match value {
+			JsonAction::Append => Self::Append,
+			JsonAction::Replace => Self::Replace,
+			JsonAction::Delete => Self::Delete,
+			JsonAction::Information => Self::Information,
+		}
+	}
+}
+
+impl From<Action> for JsonAction {
+	fn from(value: Action) -> Self {
+		match value {
+			Action::Append => Self::Append,
+			Action::Replace => Self::Replace,
+			Action::Delete => Self::Delete,
+			Action::Information => Self::Information,
+		}
+	}
+}
+
+/// Finds the first link to a dataflow, a data structure, or a provision
+/// agreement (in that order of preference), and parses the structure
+/// identifier from the end of its URN.
+fn structure_reference(links: &[Link]) -> Option<(Structure, StructureId<'_>)> {
+	const RELATIONS: [(&str, Structure); 3] = [
+		("dataflow", Structure::DataFlow),
+		("datastructure", Structure::DataStructure),
+		("provisionagreement", Structure::DataProvision),
+	];
+
+	RELATIONS.iter().find_map(|(rel, structure)| {
+		links
+			.iter()
+			.filter(|link| link.rel.eq_ignore_ascii_case(rel))
+			.filter_map(link_urn)
+			.find_map(|urn| StructureId::parse(urn.rsplit_once('=')?.1).ok())
+			.map(|id| (*structure, id))
+	})
+}
+
+/// The URN of a link, which is kept in the other properties
+/// of the link if it also has a hyperlink reference.
+fn link_urn(link: &Link) -> Option<&str> {
+	match &link.location {
+		Location::Urn(urn) => Some(urn),
+		Location::Href(_) => link.other.as_ref()?.get("urn")?.as_str(),
+	}
+}
+
+/// Formats an uncoded value as the text of a cell. Multilingual texts
+/// and arrays are formatted as a [`CellValue`].
+fn literal(value: &SdmxValue) -> Option<Cow<'_, str>> {
+	match value {
+		SdmxValue::Null => None,
+		SdmxValue::String(s) => Some(Cow::Borrowed(s)),
+		SdmxValue::Integer(i) => Some(Cow::Owned(i.to_string())),
+		SdmxValue::Number(n) => Some(Cow::Owned(n.to_string())),
+		SdmxValue::Boolean(b) => Some(Cow::Owned(b.to_string())),
+		SdmxValue::LocalizedText(texts) => {
+			let mut pairs: Vec<_> = texts
+				.iter()
+				.map(|(locale, text)| {
+					(Cow::Borrowed(locale.as_str()), Cow::Borrowed(text.as_str()))
+				})
+				.collect();
+			pairs.sort_unstable();
+			Some(Cow::Owned(CellValue::Localized(pairs).to_string()))
+		}
+		SdmxValue::Array(values) => {
+			let values = values.iter().map(|v| literal(v).unwrap_or_default());
+			Some(Cow::Owned(CellValue::Array(values.collect()).to_string()))
+		}
+	}
+}
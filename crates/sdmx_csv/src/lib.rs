@@ -1,10 +1,102 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+//! A Rust implementation of SDMX-CSV (Statistical Data and Metadata eXchange).
+//!
+//! SDMX-CSV data messages can be read one row at a time with a
+//! [`DataReader`], which yields [`DataRecord`]s that borrow from
+//! a reusable [`StringRecord`][csv::StringRecord] buffer.
+//! Likewise, they can be written with a [`DataWriter`].
+//!
+//! SDMX-CSV metadata messages are handled the same way, with a
+//! [`MetadataReader`] and a [`MetadataWriter`] for [`MetadataRecord`]s.
+//!
+//! Records which need to outlive the reader's buffer (e.g. to be sent
+//! to another thread) can be converted with `into_owned()` into an
+//! [`OwnedDataRecord`] or an [`OwnedMetadataRecord`].
+//!
+//! Cells which hold multiple values (or multilingual texts) can be
+//! parsed and written as a [`CellValue`].
+//!
+//! Time periods can be converted to and from the normalized
+//! ISO 8601 format with [`normalize_time_period`] and
+//! [`original_time_period`].
+//!
+//! # Features
+//! - `json`: enables the `json` module, for converting between SDMX-CSV
+//!   and the SDMX-JSON data messages of the `sdmx_json` crate.
+
+mod error;
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub mod json;
+mod media_type;
+mod owned;
+mod read;
+mod time;
+mod value;
+mod write;
+
+pub use error::*;
+pub use media_type::*;
+pub use owned::*;
+pub use read::*;
+pub use time::*;
+pub use value::*;
+pub use write::*;
+
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+// Reserved column names defined by the SDMX-CSV format.
+pub(crate) const STRUCTURE: &str = "STRUCTURE";
+pub(crate) const STRUCTURE_ID: &str = "STRUCTURE_ID";
+pub(crate) const STRUCTURE_NAME: &str = "STRUCTURE_NAME";
+pub(crate) const ACTION: &str = "ACTION";
+pub(crate) const SERIES_KEY: &str = "SERIES_KEY";
+pub(crate) const OBS_KEY: &str = "OBS_KEY";
+pub(crate) const METADATASET_ID: &str = "METADATASET_ID";
+pub(crate) const METADATASET_NAME: &str = "METADATASET_NAME";
+pub(crate) const TARGET_TYPES: &str = "TARGET_TYPES";
+pub(crate) const TARGET_IDS: &str = "TARGET_IDS";
+pub(crate) const TARGET_NAMES: &str = "TARGET_NAMES";
+
+/// The ID of the time dimension, whose values are converted
+/// when the time format is [`TimeFormat::Normalized`].
+pub(crate) const TIME_PERIOD: &str = "TIME_PERIOD";
 
 /// An object for efficiently storing dynamic column
 /// names with indices.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct InternedHeaders<'a>(pub HashMap<usize, &'a str>);
 
+impl<'a> InternedHeaders<'a> {
+	/// Returns the name of the column at the given index.
+	pub fn get(&self, column: usize) -> Option<&'a str> {
+		self.0.get(&column).copied()
+	}
+
+	/// Returns the index of the column with the given name.
+	pub fn position(&self, name: &str) -> Option<usize> {
+		self.0
+			.iter()
+			.find_map(|(column, header)| (*header == name).then_some(*column))
+	}
+
+	/// An iterator over the columns and their names, in no particular order.
+	pub fn iter(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+		self.0.iter().map(|(column, header)| (*column, *header))
+	}
+
+	/// Returns the hierarchical path of the column at the given index.
+	/// In a metadata message, a nested metadata attribute is named by
+	/// the IDs of its ancestors separated by dots (e.g. `A.B.C`).
+	pub fn path(&self, column: usize) -> Option<std::str::Split<'a, char>> {
+		self.get(column).map(|header| header.split('.'))
+	}
+}
+
 /// A CSV record for an SDMX-CSV Data Message.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DataRecord<'a> {
@@ -47,9 +139,76 @@ pub enum Structure {
 	DataFlow,
 	DataStructure,
 	DataProvision,
+	MetadataFlow,
+	MetadataProvision,
 }
 
-/// A unique identifier for an SDMX artefact.
+impl TryFrom<&str> for Structure {
+	type Error = ();
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		match value {
+			"dataflow" => Ok(Self::DataFlow),
+			"datastructure" => Ok(Self::DataStructure),
+			"dataprovision" => Ok(Self::DataProvision),
+			"metadataflow" => Ok(Self::MetadataFlow),
+			"metadataprovision" => Ok(Self::MetadataProvision),
+			_ => Err(()),
+		}
+	}
+}
+
+impl FromStr for Structure {
+	type Err = ParseStructureError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::try_from(s).map_err(|_| ParseStructureError::new(FieldErrorKind::Structure))
+	}
+}
+
+impl fmt::Display for Structure {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl Structure {
+	/// The keyword used for this structure type in the `STRUCTURE` column.
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			Self::DataFlow => "dataflow",
+			Self::DataStructure => "datastructure",
+			Self::DataProvision => "dataprovision",
+			Self::MetadataFlow => "metadataflow",
+			Self::MetadataProvision => "metadataprovision",
+		}
+	}
+
+	/// Whether this structure type may be referenced by
+	/// a metadata message (rather than a data message).
+	pub const fn is_metadata(&self) -> bool {
+		matches!(self, Self::MetadataFlow | Self::MetadataProvision)
+	}
+}
+
+/// A unique identifier for an SDMX artefact, in the
+/// format of `AGENCY:ID(VERSION)`.
+///
+/// Since it borrows from the text it was parsed from, it is
+/// parsed with [`StructureId::parse`] (or `TryFrom<&str>`)
+/// rather than with `FromStr`, which cannot return a value
+/// borrowing from its input. Use [`OwnedStructureId`] (which
+/// implements `FromStr`) when a `str::parse()` is needed.
+///
+/// # Example
+/// ```
+/// use sdmx_csv::{OwnedStructureId, StructureId};
+///
+/// let id = StructureId::parse("ECB:EXR(1.0.0)").unwrap();
+/// assert_eq!(id, StructureId::new("ECB", "EXR", "1.0.0"));
+/// assert_eq!(id.to_string(), "ECB:EXR(1.0.0)");
+///
+/// let owned: OwnedStructureId = "ECB:EXR(1.0.0)".parse().unwrap();
+/// assert_eq!(owned.as_structure_id(), id);
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct StructureId<'a> {
 	agency: &'a str,
@@ -58,6 +217,29 @@ pub struct StructureId<'a> {
 }
 
 impl<'a> StructureId<'a> {
+	/// Creates an identifier from its agency, artefact ID, and version.
+	pub const fn new(agency: &'a str, artefact_id: &'a str, version: &'a str) -> Self {
+		Self {
+			agency,
+			artefact_id,
+			version,
+		}
+	}
+
+	/// Parses an identifier in the format of `AGENCY:ID(VERSION)`,
+	/// where none of the parts may be empty.
+	pub fn parse(s: &'a str) -> Result<Self, ParseStructureError> {
+		let parse = || {
+			let (agency, rest) = s.split_once(':')?;
+			let (artefact_id, version) = rest.strip_suffix(')')?.split_once('(')?;
+			if agency.is_empty() || artefact_id.is_empty() || version.is_empty() {
+				return None;
+			}
+			Some(Self::new(agency, artefact_id, version))
+		};
+		parse().ok_or(ParseStructureError::new(FieldErrorKind::StructureId))
+	}
+
 	pub const fn agency(&self) -> &'a str {
 		self.agency
 	}
@@ -71,13 +253,99 @@ impl<'a> StructureId<'a> {
 	}
 }
 
-/// A unique identifier and localized name for an SDMX artefact.
+impl fmt::Display for StructureId<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}({})", self.agency, self.artefact_id, self.version)
+	}
+}
+
+impl<'a> TryFrom<&'a str> for StructureId<'a> {
+	type Error = ParseStructureError;
+	fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+		Self::parse(value)
+	}
+}
+
+/// A unique identifier and localized name for an SDMX artefact,
+/// in the format of `AGENCY:ID(VERSION): Localized name` (as
+/// used in the `STRUCTURE_ID` column with [`Labels::Both`]).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct StructureName<'a> {
 	pub id: StructureId<'a>,
 	pub localized: &'a str,
 }
 
+impl<'a> StructureName<'a> {
+	/// Parses an identifier and a localized name, separated by the
+	/// first `": "`. The localized name is required.
+	pub fn parse(s: &'a str) -> Result<Self, ParseStructureError> {
+		let (id, localized) = s
+			.split_once(": ")
+			.ok_or(ParseStructureError::new(FieldErrorKind::StructureName))?;
+		Ok(Self {
+			id: StructureId::parse(id)?,
+			localized,
+		})
+	}
+}
+
+impl fmt::Display for StructureName<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}", self.id, self.localized)
+	}
+}
+
+impl<'a> TryFrom<&'a str> for StructureName<'a> {
+	type Error = ParseStructureError;
+	fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+		Self::parse(value)
+	}
+}
+
+/// A column header or cell value, which contains an ID and
+/// optionally a localized name (in the format of `<id>: <localized name>`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Label<'a> {
+	pub id: &'a str,
+	pub localized: Option<&'a str>,
+}
+
+impl<'a> Label<'a> {
+	/// Splits a label on the first `": "` separator. If there is
+	/// no separator, the whole label is assumed to be an ID.
+	pub fn parse(s: &'a str) -> Self {
+		match s.split_once(": ") {
+			Some((id, localized)) => Self {
+				id,
+				localized: Some(localized),
+			},
+			None => Self {
+				id: s,
+				localized: None,
+			},
+		}
+	}
+
+	/// Formats the label according to the given label configuration.
+	/// If there is no localized name, the ID is used instead.
+	pub fn format(&self, labels: Labels) -> Cow<'a, str> {
+		match (labels, self.localized) {
+			(Labels::Name, Some(localized)) => Cow::Borrowed(localized),
+			(Labels::Both, Some(localized)) => Cow::Owned(format!("{}: {}", self.id, localized)),
+			_ => Cow::Borrowed(self.id),
+		}
+	}
+}
+
+impl<'a> From<&'a str> for Label<'a> {
+	fn from(value: &'a str) -> Self {
+		Self {
+			id: value,
+			localized: None,
+		}
+	}
+}
+
 // TODO: Have some sort of sdmx_core/sdmx_im crate
 // for sharing common types between standard implementations.
 /// An action which describes how or why the data is being transmitted
@@ -104,6 +372,17 @@ impl TryFrom<char> for Action {
 	}
 }
 
+impl From<Action> for char {
+	fn from(value: Action) -> Self {
+		match value {
+			Action::Append => 'A',
+			Action::Replace => 'R',
+			Action::Delete => 'D',
+			Action::Information => 'I',
+		}
+	}
+}
+
 /// Options to configure an SDMX-CSV Data Message.
 /// These options may also be given from an HTTP Accept header.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -130,8 +409,35 @@ pub enum Labels {
 	Both,
 }
 
+impl TryFrom<&str> for Labels {
+	type Error = ();
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		match value {
+			"id" => Ok(Self::Id),
+			"name" => Ok(Self::Name),
+			"both" => Ok(Self::Both),
+			_ => Err(()),
+		}
+	}
+}
+
+impl Labels {
+	/// The value of this option as a media type parameter.
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			Self::Id => "id",
+			Self::Name => "name",
+			Self::Both => "both",
+		}
+	}
+}
+
 /// Configures if a time format should be stored as originally
 /// recorded, or as a normalized ISO 8601 format.
+///
+/// When normalized, the values of the `TIME_PERIOD` column are converted
+/// with [`normalize_time_period`] by a [`DataWriter`], and converted back
+/// with [`original_time_period`] by a [`DataReader`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum TimeFormat {
 	#[default]
@@ -139,6 +445,27 @@ pub enum TimeFormat {
 	Normalized,
 }
 
+impl TryFrom<&str> for TimeFormat {
+	type Error = ();
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		match value {
+			"original" => Ok(Self::Original),
+			"normalized" => Ok(Self::Normalized),
+			_ => Err(()),
+		}
+	}
+}
+
+impl TimeFormat {
+	/// The value of this option as a media type parameter.
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			Self::Original => "original",
+			Self::Normalized => "normalized",
+		}
+	}
+}
+
 /// Configures whether additional key-related columns
 /// may appear (no extra, an observation key, a series key,
 /// or both).
@@ -150,3 +477,28 @@ pub enum Keys {
 	Series,
 	Both,
 }
+
+impl TryFrom<&str> for Keys {
+	type Error = ();
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		match value {
+			"none" => Ok(Self::None),
+			"obs" => Ok(Self::Obs),
+			"series" => Ok(Self::Series),
+			"both" => Ok(Self::Both),
+			_ => Err(()),
+		}
+	}
+}
+
+impl Keys {
+	/// The value of this option as a media type parameter.
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			Self::None => "none",
+			Self::Obs => "obs",
+			Self::Series => "series",
+			Self::Both => "both",
+		}
+	}
+}
@@ -0,0 +1,172 @@
+use crate::{DataOptions, MetadataOptions};
+use std::error;
+use std::fmt;
+
+/// The media type of an SDMX-CSV Data Message.
+pub const DATA_MEDIA_TYPE: &str = "application/vnd.sdmx.data+csv";
+/// The media type of an SDMX-CSV Metadata Message.
+pub const METADATA_MEDIA_TYPE: &str = "application/vnd.sdmx.metadata+csv";
+/// The version of SDMX-CSV implemented by this crate.
+pub const VERSION: &str = "2.0.0";
+
+/// An error that can occur when parsing options from a media type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaTypeError {
+	/// The media type is not the expected SDMX-CSV media type.
+	UnexpectedType(String),
+	/// The `version` parameter is not a supported SDMX-CSV version.
+	UnsupportedVersion(String),
+	/// A parameter is not in the format of `name=value`.
+	MalformedParameter(String),
+	/// A known parameter has an unknown value.
+	InvalidParameterValue { name: String, value: String },
+}
+
+impl error::Error for MediaTypeError {}
+
+impl fmt::Display for MediaTypeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::UnexpectedType(essence) => write!(f, "unexpected media type: {}", essence),
+			Self::UnsupportedVersion(version) => write!(f, "unsupported version: {}", version),
+			Self::MalformedParameter(parameter) => {
+				write!(f, "malformed media type parameter: {}", parameter)
+			}
+			Self::InvalidParameterValue { name, value } => {
+				write!(f, "invalid value for parameter {}: {}", name, value)
+			}
+		}
+	}
+}
+
+impl DataOptions {
+	/// Parses the options from the parameters of an SDMX-CSV data
+	/// media type, such as one given in an HTTP Accept header.
+	///
+	/// Parameters which are not given take their default value,
+	/// and unknown parameters (e.g. `q` or `charset`) are ignored.
+	///
+	/// # Example
+	/// ```
+	/// use sdmx_csv::{DataOptions, Keys, Labels, TimeFormat};
+	///
+	/// let options = DataOptions::from_media_type(
+	///     "application/vnd.sdmx.data+csv;version=2.0.0;labels=both;timeFormat=normalized;keys=series",
+	/// ).unwrap();
+	/// assert_eq!(options.labels, Labels::Both);
+	/// assert_eq!(options.time_format, TimeFormat::Normalized);
+	/// assert_eq!(options.keys, Keys::Series);
+	/// ```
+	pub fn from_media_type(media_type: &str) -> Result<Self, MediaTypeError> {
+		let mut options = Self::default();
+		for (name, value) in parameters(media_type, DATA_MEDIA_TYPE)? {
+			if name.eq_ignore_ascii_case("labels") {
+				options.labels = parameter_value(name, value)?;
+			} else if name.eq_ignore_ascii_case("timeFormat") {
+				options.time_format = parameter_value(name, value)?;
+			} else if name.eq_ignore_ascii_case("keys") {
+				options.keys = parameter_value(name, value)?;
+			}
+		}
+		Ok(options)
+	}
+
+	/// Formats the options as the parameters of an SDMX-CSV data media type.
+	pub fn to_media_type(&self) -> String {
+		format!(
+			"{};version={};labels={};timeFormat={};keys={}",
+			DATA_MEDIA_TYPE,
+			VERSION,
+			self.labels.as_str(),
+			self.time_format.as_str(),
+			self.keys.as_str(),
+		)
+	}
+}
+
+impl MetadataOptions {
+	/// Parses the options from the parameters of an SDMX-CSV metadata
+	/// media type, such as one given in an HTTP Accept header.
+	///
+	/// Parameters which are not given take their default value,
+	/// and unknown parameters (e.g. `q` or `charset`) are ignored.
+	pub fn from_media_type(media_type: &str) -> Result<Self, MediaTypeError> {
+		let mut options = Self::default();
+		for (name, value) in parameters(media_type, METADATA_MEDIA_TYPE)? {
+			if name.eq_ignore_ascii_case("labels") {
+				options.labels = parameter_value(name, value)?;
+			}
+		}
+		Ok(options)
+	}
+
+	/// Formats the options as the parameters of an SDMX-CSV metadata media type.
+	pub fn to_media_type(&self) -> String {
+		format!(
+			"{};version={};labels={}",
+			METADATA_MEDIA_TYPE,
+			VERSION,
+			self.labels.as_str(),
+		)
+	}
+}
+
+/// Checks the essence of the media type, and the `version` parameter,
+/// then returns the remaining parameters as name-value pairs.
+fn parameters<'a>(
+	media_type: &'a str,
+	expected: &str,
+) -> Result<Vec<(&'a str, &'a str)>, MediaTypeError> {
+	let mut parts = media_type.split(';');
+	let essence = parts.next().unwrap_or_default().trim();
+	if !essence.eq_ignore_ascii_case(expected) {
+		return Err(MediaTypeError::UnexpectedType(essence.to_owned()));
+	}
+
+	let mut parameters = Vec::new();
+	for parameter in parts.map(str::trim).filter(|p| !p.is_empty()) {
+		let (name, value) = parameter
+			.split_once('=')
+			.ok_or_else(|| MediaTypeError::MalformedParameter(parameter.to_owned()))?;
+		let name = name.trim();
+		let value = value.trim();
+		let value = value
+			.strip_prefix('"')
+			.and_then(|v| v.strip_suffix('"'))
+			.unwrap_or(value);
+
+		if name.eq_ignore_ascii_case("version") {
+			if !is_supported_version(value) {
+				return Err(MediaTypeError::UnsupportedVersion(value.to_owned()));
+			}
+		} else {
+			parameters.push((name, value));
+		}
+	}
+	Ok(parameters)
+}
+
+/// Whether a version (e.g. `2`, `2.0`, or `2.0.1`) has the same major
+/// and minor parts as [`VERSION`], where each part is a number and only
+/// the major part is required.
+fn is_supported_version(version: &str) -> bool {
+	let parts: Vec<&str> = version.split('.').collect();
+	let is_number = |part: &&str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+	if parts.len() > 3 || !parts.iter().all(is_number) {
+		return false;
+	}
+	parts
+		.iter()
+		.zip(VERSION.split('.').take(2))
+		.all(|(part, supported)| part.parse::<u64>().ok() == supported.parse().ok())
+}
+
+fn parameter_value<T>(name: &str, value: &str) -> Result<T, MediaTypeError>
+where
+	T: for<'v> TryFrom<&'v str, Error = ()>,
+{
+	T::try_from(value).map_err(|_| MediaTypeError::InvalidParameterValue {
+		name: name.to_owned(),
+		value: value.to_owned(),
+	})
+}
@@ -0,0 +1,232 @@
+use crate::{
+	Action, DataRecord, MetadataRecord, ParseStructureError, Structure, StructureId, StructureName,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// An owned [`DataRecord`], which does not borrow from the reader's
+/// buffer, and so can be kept or sent to another thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedDataRecord {
+	pub structure: Structure,
+	pub structure_id: OwnedStructureId,
+	pub structure_name: Option<OwnedStructureName>,
+	pub action: Action,
+	pub series_key: Option<String>,
+	pub obs_key: Option<String>,
+	pub components: HashMap<usize, String>,
+	pub other: HashMap<usize, String>,
+}
+
+impl OwnedDataRecord {
+	/// Borrows this record as a [`DataRecord`] (e.g. for writing).
+	pub fn as_record(&self) -> DataRecord<'_> {
+		DataRecord {
+			structure: self.structure,
+			structure_id: self.structure_id.as_structure_id(),
+			structure_name: self.structure_name.as_ref().map(|n| n.as_structure_name()),
+			action: self.action,
+			series_key: self.series_key.as_deref(),
+			obs_key: self.obs_key.as_deref(),
+			components: borrow_cells(&self.components),
+			other: borrow_cells(&self.other),
+		}
+	}
+}
+
+impl DataRecord<'_> {
+	/// Copies the borrowed fields of this record into an [`OwnedDataRecord`].
+	pub fn into_owned(self) -> OwnedDataRecord {
+		OwnedDataRecord {
+			structure: self.structure,
+			structure_id: self.structure_id.into(),
+			structure_name: self.structure_name.map(Into::into),
+			action: self.action,
+			series_key: self.series_key.map(str::to_owned),
+			obs_key: self.obs_key.map(str::to_owned),
+			components: own_cells(self.components),
+			other: own_cells(self.other),
+		}
+	}
+}
+
+impl From<DataRecord<'_>> for OwnedDataRecord {
+	fn from(value: DataRecord<'_>) -> Self {
+		value.into_owned()
+	}
+}
+
+/// An owned [`MetadataRecord`], which does not borrow from the reader's
+/// buffer, and so can be kept or sent to another thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedMetadataRecord {
+	pub md_structure: Structure,
+	pub md_structure_id: OwnedStructureId,
+	pub md_structure_name: Option<OwnedStructureName>,
+	pub metadataset_id: String,
+	pub metadataset_name: Option<String>,
+	pub action: Action,
+	pub target_types: Vec<String>,
+	pub target_ids: Vec<String>,
+	pub target_names: Option<String>,
+	pub components: HashMap<usize, String>,
+	pub others: HashMap<usize, String>,
+}
+
+impl OwnedMetadataRecord {
+	/// Borrows this record as a [`MetadataRecord`] (e.g. for writing).
+	pub fn as_record(&self) -> MetadataRecord<'_> {
+		MetadataRecord {
+			md_structure: self.md_structure,
+			md_structure_id: self.md_structure_id.as_structure_id(),
+			md_structure_name: self
+				.md_structure_name
+				.as_ref()
+				.map(|n| n.as_structure_name()),
+			metadataset_id: &self.metadataset_id,
+			metadataset_name: self.metadataset_name.as_deref(),
+			action: self.action,
+			target_types: self.target_types.iter().map(String::as_str).collect(),
+			target_ids: self.target_ids.iter().map(String::as_str).collect(),
+			target_names: self.target_names.as_deref(),
+			components: borrow_cells(&self.components),
+			others: borrow_cells(&self.others),
+		}
+	}
+}
+
+impl MetadataRecord<'_> {
+	/// Copies the borrowed fields of this record into an [`OwnedMetadataRecord`].
+	pub fn into_owned(self) -> OwnedMetadataRecord {
+		OwnedMetadataRecord {
+			md_structure: self.md_structure,
+			md_structure_id: self.md_structure_id.into(),
+			md_structure_name: self.md_structure_name.map(Into::into),
+			metadataset_id: self.metadataset_id.to_owned(),
+			metadataset_name: self.metadataset_name.map(str::to_owned),
+			action: self.action,
+			target_types: self.target_types.into_iter().map(str::to_owned).collect(),
+			target_ids: self.target_ids.into_iter().map(str::to_owned).collect(),
+			target_names: self.target_names.map(str::to_owned),
+			components: own_cells(self.components),
+			others: own_cells(self.others),
+		}
+	}
+}
+
+impl From<MetadataRecord<'_>> for OwnedMetadataRecord {
+	fn from(value: MetadataRecord<'_>) -> Self {
+		value.into_owned()
+	}
+}
+
+/// An owned [`StructureId`]. Unlike its borrowed counterpart,
+/// it can be parsed with `FromStr`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct OwnedStructureId {
+	agency: String,
+	artefact_id: String,
+	version: String,
+}
+
+impl OwnedStructureId {
+	pub fn new(
+		agency: impl Into<String>,
+		artefact_id: impl Into<String>,
+		version: impl Into<String>,
+	) -> Self {
+		Self {
+			agency: agency.into(),
+			artefact_id: artefact_id.into(),
+			version: version.into(),
+		}
+	}
+
+	pub fn agency(&self) -> &str {
+		&self.agency
+	}
+
+	pub fn artefact_id(&self) -> &str {
+		&self.artefact_id
+	}
+
+	pub fn version(&self) -> &str {
+		&self.version
+	}
+
+	pub fn as_structure_id(&self) -> StructureId<'_> {
+		StructureId::new(&self.agency, &self.artefact_id, &self.version)
+	}
+}
+
+impl From<StructureId<'_>> for OwnedStructureId {
+	fn from(value: StructureId<'_>) -> Self {
+		Self::new(value.agency(), value.artefact_id(), value.version())
+	}
+}
+
+impl FromStr for OwnedStructureId {
+	type Err = ParseStructureError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		StructureId::parse(s).map(Self::from)
+	}
+}
+
+impl fmt::Display for OwnedStructureId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.as_structure_id().fmt(f)
+	}
+}
+
+/// An owned [`StructureName`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct OwnedStructureName {
+	pub id: OwnedStructureId,
+	pub localized: String,
+}
+
+impl OwnedStructureName {
+	pub fn as_structure_name(&self) -> StructureName<'_> {
+		StructureName {
+			id: self.id.as_structure_id(),
+			localized: &self.localized,
+		}
+	}
+}
+
+impl From<StructureName<'_>> for OwnedStructureName {
+	fn from(value: StructureName<'_>) -> Self {
+		Self {
+			id: value.id.into(),
+			localized: value.localized.to_owned(),
+		}
+	}
+}
+
+impl FromStr for OwnedStructureName {
+	type Err = ParseStructureError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		StructureName::parse(s).map(Self::from)
+	}
+}
+
+impl fmt::Display for OwnedStructureName {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.as_structure_name().fmt(f)
+	}
+}
+
+fn own_cells(cells: HashMap<usize, &str>) -> HashMap<usize, String> {
+	cells
+		.into_iter()
+		.map(|(column, cell)| (column, cell.to_owned()))
+		.collect()
+}
+
+fn borrow_cells(cells: &HashMap<usize, String>) -> HashMap<usize, &str> {
+	cells
+		.iter()
+		.map(|(column, cell)| (*column, cell.as_str()))
+		.collect()
+}
@@ -0,0 +1,533 @@
+use crate::{
+	original_time_period, Action, DataOptions, DataRecord, Error, FieldErrorKind, InternedHeaders,
+	Keys, Label, Labels, MetadataOptions, MetadataRecord, Structure, StructureId, StructureName,
+	TimeFormat, ACTION, METADATASET_ID, METADATASET_NAME, OBS_KEY, SERIES_KEY, STRUCTURE,
+	STRUCTURE_ID, STRUCTURE_NAME, TARGET_IDS, TARGET_NAMES, TARGET_TYPES, TIME_PERIOD,
+};
+use csv::{Reader, StringRecord};
+use std::collections::HashMap;
+use std::io;
+
+/// A streaming reader for an SDMX-CSV Data Message.
+///
+/// The header row is read when the reader is created. Each call to
+/// [`read_record()`][DataReader::read_record] then reads a single row
+/// into a caller-provided buffer, and returns a [`DataRecord`] which
+/// borrows from that buffer.
+///
+/// # Example
+/// ```
+/// use csv::StringRecord;
+/// use sdmx_csv::{DataOptions, DataReader};
+///
+/// fn main() -> Result<(), sdmx_csv::Error> {
+///     let csv = "STRUCTURE,STRUCTURE_ID,ACTION,FREQ,OBS_VALUE\n\
+///                dataflow,ECB:EXR(1.0.0),I,M,1.25\n";
+///     let mut reader = DataReader::new(csv.as_bytes(), DataOptions::default())?;
+///     let headers = reader.headers();
+///     let freq = headers.position("FREQ").unwrap();
+///
+///     let mut buffer = StringRecord::new();
+///     while let Some(record) = reader.read_record(&mut buffer)? {
+///         assert_eq!(record.structure_id.agency(), "ECB");
+///         assert_eq!(record.components[&freq], "M");
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DataReader<R> {
+	reader: Reader<R>,
+	options: DataOptions,
+	headers: StringRecord,
+	columns: DataColumns,
+	components: Vec<usize>,
+	other: Vec<usize>,
+	/// The column of the time dimension, if its values are normalized.
+	time_column: Option<usize>,
+}
+
+/// The positions of the reserved columns of a data message.
+#[derive(Debug, Clone, Copy)]
+struct DataColumns {
+	structure: usize,
+	structure_id: usize,
+	structure_name: Option<usize>,
+	action: Option<usize>,
+	series_key: Option<usize>,
+	obs_key: Option<usize>,
+}
+
+impl<R: io::Read> DataReader<R> {
+	/// Creates a new reader from any [`io::Read`] source, using
+	/// the default configuration of the `csv` crate.
+	pub fn new(reader: R, options: DataOptions) -> Result<Self, Error> {
+		Self::from_csv(Reader::from_reader(reader), options)
+	}
+
+	/// Creates a new reader from an already configured CSV reader
+	/// (e.g with a different delimiter). The reader is expected to
+	/// treat the first row as a header row.
+	pub fn from_csv(mut reader: Reader<R>, options: DataOptions) -> Result<Self, Error> {
+		let headers = reader.headers()?.clone();
+		let find = |name: &str| headers.iter().position(|h| h == name);
+		let require = |name: &'static str| find(name).ok_or(Error::MissingColumn(name));
+
+		let columns = DataColumns {
+			structure: require(STRUCTURE)?,
+			structure_id: require(STRUCTURE_ID)?,
+			structure_name: find(STRUCTURE_NAME),
+			action: find(ACTION),
+			series_key: find(SERIES_KEY),
+			obs_key: find(OBS_KEY),
+		};
+
+		if options.labels == Labels::Name && columns.structure_name.is_none() {
+			return Err(Error::MissingColumn(STRUCTURE_NAME));
+		}
+		if matches!(options.keys, Keys::Series | Keys::Both) && columns.series_key.is_none() {
+			return Err(Error::MissingColumn(SERIES_KEY));
+		}
+		if matches!(options.keys, Keys::Obs | Keys::Both) && columns.obs_key.is_none() {
+			return Err(Error::MissingColumn(OBS_KEY));
+		}
+
+		let components = dynamic_columns(
+			&headers,
+			&[
+				Some(columns.structure),
+				Some(columns.structure_id),
+				columns.structure_name,
+				columns.action,
+				columns.series_key,
+				columns.obs_key,
+			],
+		);
+		let time_column = match options.time_format {
+			TimeFormat::Normalized => components
+				.iter()
+				.copied()
+				.find(|column| header_id(&headers, *column, options.labels) == TIME_PERIOD),
+			TimeFormat::Original => None,
+		};
+
+		Ok(Self {
+			reader,
+			options,
+			headers,
+			columns,
+			components,
+			other: Vec::new(),
+			time_column,
+		})
+	}
+
+	/// Restricts the component columns to the given component IDs.
+	/// Any other non-reserved column is then stored in
+	/// [`DataRecord::other`] instead of [`DataRecord::components`].
+	pub fn with_component_ids<I, S>(mut self, ids: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		let labels = self.options.labels;
+		(self.components, self.other) =
+			partition_columns(&self.components, &self.other, ids, |column| {
+				header_id(&self.headers, column, labels)
+			});
+		self
+	}
+
+	/// The options this reader was configured with.
+	pub const fn options(&self) -> DataOptions {
+		self.options
+	}
+
+	/// The IDs of the component (and other non-reserved) columns,
+	/// indexed by their column position.
+	///
+	/// When the labels are configured as [`Labels::Both`], only the
+	/// ID part of each `<id>: <localized name>` header is kept.
+	pub fn headers(&self) -> InternedHeaders<'_> {
+		interned_headers(
+			&self.headers,
+			&self.components,
+			&self.other,
+			self.options.labels,
+		)
+	}
+
+	/// Reads the next row into the given buffer, returning `None`
+	/// once the end of the input is reached.
+	///
+	/// Empty component cells are omitted from the returned record.
+	///
+	/// When the time format is [`TimeFormat::Normalized`], the value of
+	/// the `TIME_PERIOD` column is converted back to its original format
+	/// (which is appended to the buffer as an extra field), unless it is
+	/// not a valid normalized time period, in which case it is kept as-is.
+	pub fn read_record<'r>(
+		&mut self,
+		record: &'r mut StringRecord,
+	) -> Result<Option<DataRecord<'r>>, Error> {
+		if !self.reader.read_record(record)? {
+			return Ok(None);
+		}
+		// the original time period is appended to the buffer,
+		// rather than replacing the normalized one in place
+		let mut time_field = None;
+		if let Some(column) = self.time_column {
+			if let Some(original) = record.get(column).and_then(original_time_period) {
+				record.push_field(&original);
+				time_field = Some((column, record.len() - 1));
+			}
+		}
+
+		let fields = Fields::new(record);
+		let columns = self.columns;
+		let structure = fields.structure(columns.structure, false)?;
+		let (structure_id, structure_name) = fields.structure_id(
+			columns.structure_id,
+			columns.structure_name,
+			self.options.labels,
+		)?;
+		let mut components = fields.cells(&self.components);
+		let mut other = fields.cells(&self.other);
+		if let Some((column, field)) = time_field {
+			// the time column is not a component if it was
+			// left out of the IDs given by `with_component_ids`
+			let cells = match self.other.contains(&column) {
+				true => &mut other,
+				false => &mut components,
+			};
+			cells.insert(column, fields.get(field));
+		}
+
+		Ok(Some(DataRecord {
+			structure,
+			structure_id,
+			structure_name,
+			action: fields.action(columns.action)?,
+			series_key: fields.optional(columns.series_key),
+			obs_key: fields.optional(columns.obs_key),
+			components,
+			other,
+		}))
+	}
+}
+
+/// A streaming reader for an SDMX-CSV Metadata Message.
+///
+/// This works the same way as a [`DataReader`], where each row is read
+/// into a caller-provided buffer and returned as a [`MetadataRecord`].
+/// The names of hierarchical metadata attribute columns (e.g. `A.B.C`)
+/// can be split with [`InternedHeaders::path()`].
+#[derive(Debug)]
+pub struct MetadataReader<R> {
+	reader: Reader<R>,
+	options: MetadataOptions,
+	headers: StringRecord,
+	columns: MetadataColumns,
+	components: Vec<usize>,
+	others: Vec<usize>,
+}
+
+/// The positions of the reserved columns of a metadata message.
+#[derive(Debug, Clone, Copy)]
+struct MetadataColumns {
+	structure: usize,
+	structure_id: usize,
+	structure_name: Option<usize>,
+	metadataset_id: usize,
+	metadataset_name: Option<usize>,
+	action: Option<usize>,
+	target_types: usize,
+	target_ids: usize,
+	target_names: Option<usize>,
+}
+
+impl<R: io::Read> MetadataReader<R> {
+	/// Creates a new reader from any [`io::Read`] source, using
+	/// the default configuration of the `csv` crate.
+	pub fn new(reader: R, options: MetadataOptions) -> Result<Self, Error> {
+		Self::from_csv(Reader::from_reader(reader), options)
+	}
+
+	/// Creates a new reader from an already configured CSV reader
+	/// (e.g with a different delimiter). The reader is expected to
+	/// treat the first row as a header row.
+	pub fn from_csv(mut reader: Reader<R>, options: MetadataOptions) -> Result<Self, Error> {
+		let headers = reader.headers()?.clone();
+		let find = |name: &str| headers.iter().position(|h| h == name);
+		let require = |name: &'static str| find(name).ok_or(Error::MissingColumn(name));
+
+		let columns = MetadataColumns {
+			structure: require(STRUCTURE)?,
+			structure_id: require(STRUCTURE_ID)?,
+			structure_name: find(STRUCTURE_NAME),
+			metadataset_id: require(METADATASET_ID)?,
+			metadataset_name: find(METADATASET_NAME),
+			action: find(ACTION),
+			target_types: require(TARGET_TYPES)?,
+			target_ids: require(TARGET_IDS)?,
+			target_names: find(TARGET_NAMES),
+		};
+
+		if options.labels == Labels::Name {
+			let names = [
+				(columns.structure_name, STRUCTURE_NAME),
+				(columns.metadataset_name, METADATASET_NAME),
+				(columns.target_names, TARGET_NAMES),
+			];
+			if let Some((_, name)) = names.iter().find(|(column, _)| column.is_none()) {
+				return Err(Error::MissingColumn(name));
+			}
+		}
+
+		let components = dynamic_columns(
+			&headers,
+			&[
+				Some(columns.structure),
+				Some(columns.structure_id),
+				columns.structure_name,
+				Some(columns.metadataset_id),
+				columns.metadataset_name,
+				columns.action,
+				Some(columns.target_types),
+				Some(columns.target_ids),
+				columns.target_names,
+			],
+		);
+
+		Ok(Self {
+			reader,
+			options,
+			headers,
+			columns,
+			components,
+			others: Vec::new(),
+		})
+	}
+
+	/// Restricts the metadata attribute columns to the given attribute
+	/// names (e.g. `A.B.C`). Any other non-reserved column is then stored
+	/// in [`MetadataRecord::others`] instead of [`MetadataRecord::components`].
+	pub fn with_component_ids<I, S>(mut self, ids: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		let labels = self.options.labels;
+		(self.components, self.others) =
+			partition_columns(&self.components, &self.others, ids, |column| {
+				header_id(&self.headers, column, labels)
+			});
+		self
+	}
+
+	/// The options this reader was configured with.
+	pub const fn options(&self) -> MetadataOptions {
+		self.options
+	}
+
+	/// The names of the metadata attribute (and other non-reserved)
+	/// columns, indexed by their column position.
+	///
+	/// When the labels are configured as [`Labels::Both`], only the
+	/// ID part of each `<id>: <localized name>` header is kept.
+	pub fn headers(&self) -> InternedHeaders<'_> {
+		interned_headers(
+			&self.headers,
+			&self.components,
+			&self.others,
+			self.options.labels,
+		)
+	}
+
+	/// Reads the next row into the given buffer, returning `None`
+	/// once the end of the input is reached.
+	///
+	/// Empty attribute cells are omitted from the returned record.
+	pub fn read_record<'r>(
+		&mut self,
+		record: &'r mut StringRecord,
+	) -> Result<Option<MetadataRecord<'r>>, Error> {
+		if !self.reader.read_record(record)? {
+			return Ok(None);
+		}
+
+		let fields = Fields::new(record);
+		let columns = self.columns;
+		let md_structure = fields.structure(columns.structure, true)?;
+		let (md_structure_id, md_structure_name) = fields.structure_id(
+			columns.structure_id,
+			columns.structure_name,
+			self.options.labels,
+		)?;
+
+		let metadataset = match self.options.labels {
+			Labels::Both => Label::parse(fields.get(columns.metadataset_id)),
+			_ => Label::from(fields.get(columns.metadataset_id)),
+		};
+		if metadataset.id.is_empty() {
+			return Err(fields.invalid(columns.metadataset_id, FieldErrorKind::MetadataSetId));
+		}
+
+		Ok(Some(MetadataRecord {
+			md_structure,
+			md_structure_id,
+			md_structure_name,
+			metadataset_id: metadataset.id,
+			metadataset_name: fields
+				.optional(columns.metadataset_name)
+				.or(metadataset.localized),
+			action: fields.action(columns.action)?,
+			target_types: split_multiple(fields.get(columns.target_types)),
+			target_ids: split_multiple(fields.get(columns.target_ids)),
+			target_names: fields.optional(columns.target_names),
+			components: fields.cells(&self.components),
+			others: fields.cells(&self.others),
+		}))
+	}
+}
+
+/// The fields of a single row, along with its position for reporting errors.
+struct Fields<'r> {
+	record: &'r StringRecord,
+	line: u64,
+}
+
+impl<'r> Fields<'r> {
+	fn new(record: &'r StringRecord) -> Self {
+		Self {
+			record,
+			line: record.position().map_or(0, |p| p.line()),
+		}
+	}
+
+	fn get(&self, column: usize) -> &'r str {
+		self.record.get(column).unwrap_or_default()
+	}
+
+	fn optional(&self, column: Option<usize>) -> Option<&'r str> {
+		column.map(|c| self.get(c)).filter(|s| !s.is_empty())
+	}
+
+	fn invalid(&self, column: usize, kind: FieldErrorKind) -> Error {
+		Error::InvalidField {
+			line: self.line,
+			column: column + 1,
+			kind,
+		}
+	}
+
+	fn structure(&self, column: usize, metadata: bool) -> Result<Structure, Error> {
+		Structure::try_from(self.get(column))
+			.ok()
+			.filter(|structure| structure.is_metadata() == metadata)
+			.ok_or_else(|| self.invalid(column, FieldErrorKind::Structure))
+	}
+
+	/// Reads the structure ID, and the structure name either from its
+	/// own column, or from the structure ID column with [`Labels::Both`].
+	fn structure_id(
+		&self,
+		column: usize,
+		name_column: Option<usize>,
+		labels: Labels,
+	) -> Result<(StructureId<'r>, Option<StructureName<'r>>), Error> {
+		let label = match labels {
+			Labels::Both => Label::parse(self.get(column)),
+			_ => Label::from(self.get(column)),
+		};
+		let id = StructureId::parse(label.id).map_err(|e| self.invalid(column, e.kind()))?;
+		let name = self
+			.optional(name_column)
+			.or(label.localized)
+			.map(|localized| StructureName { id, localized });
+		Ok((id, name))
+	}
+
+	fn action(&self, column: Option<usize>) -> Result<Action, Error> {
+		match (column, self.optional(column)) {
+			(Some(column), Some(action)) => {
+				parse_action(action).ok_or_else(|| self.invalid(column, FieldErrorKind::Action))
+			}
+			_ => Ok(Action::default()),
+		}
+	}
+
+	fn cells(&self, columns: &[usize]) -> HashMap<usize, &'r str> {
+		columns
+			.iter()
+			.map(|column| (*column, self.get(*column)))
+			.filter(|(_, value)| !value.is_empty())
+			.collect()
+	}
+}
+
+/// Returns the positions of all columns which are not reserved.
+fn dynamic_columns(headers: &StringRecord, reserved: &[Option<usize>]) -> Vec<usize> {
+	(0..headers.len())
+		.filter(|column| !reserved.contains(&Some(*column)))
+		.collect()
+}
+
+fn header_id(headers: &StringRecord, column: usize, labels: Labels) -> &str {
+	let header = headers.get(column).unwrap_or_default();
+	match labels {
+		Labels::Both => Label::parse(header).id,
+		_ => header,
+	}
+}
+
+fn interned_headers<'a>(
+	headers: &'a StringRecord,
+	components: &[usize],
+	other: &[usize],
+	labels: Labels,
+) -> InternedHeaders<'a> {
+	InternedHeaders(
+		components
+			.iter()
+			.chain(other.iter())
+			.map(|column| (*column, header_id(headers, *column, labels)))
+			.collect(),
+	)
+}
+
+/// Splits the dynamic columns into known components and other columns.
+fn partition_columns<'h, I, S>(
+	components: &[usize],
+	other: &[usize],
+	ids: I,
+	header_id: impl Fn(usize) -> &'h str,
+) -> (Vec<usize>, Vec<usize>)
+where
+	I: IntoIterator<Item = S>,
+	S: AsRef<str>,
+{
+	let ids: Vec<S> = ids.into_iter().collect();
+	let mut columns: Vec<usize> = components.iter().chain(other.iter()).copied().collect();
+	columns.sort_unstable();
+	columns.into_iter().partition(|column| {
+		let id = header_id(*column);
+		ids.iter().any(|known| known.as_ref() == id)
+	})
+}
+
+/// Splits a cell which may contain multiple values separated by `;`.
+fn split_multiple(s: &str) -> Vec<&str> {
+	if s.is_empty() {
+		return Vec::new();
+	}
+	s.split(';').collect()
+}
+
+fn parse_action(s: &str) -> Option<Action> {
+	let mut chars = s.chars();
+	match (chars.next(), chars.next()) {
+		(Some(c), None) => Action::try_from(c).ok(),
+		_ => None,
+	}
+}
@@ -0,0 +1,124 @@
+use sdmx_json::time::{DateTime, MonthDay, RangeStart, ReportingPeriod, TimePeriod};
+
+/// Converts an SDMX time period into the normalized ISO 8601 format
+/// of a `start/duration` interval, where the start is a date-time.
+///
+/// Gregorian periods (`2020`, `2020-01`, `2020-01-15`), reporting periods
+/// (`2020-A1`, `2020-S2`, `2020-T1`, `2020-Q1`, `2020-M01`, `2020-W05`,
+/// `2020-D100`), and intervals (`2020-01-15/P1M`) are supported.
+/// Reporting periods are based on a reporting year starting on
+/// January 1, whose weeks are ISO 8601 weeks. A date-time without a
+/// duration is returned as-is, as it is already a single point in time.
+///
+/// Returns `None` if the value is not a valid time period.
+///
+/// # Example
+/// ```
+/// use sdmx_csv::normalize_time_period;
+///
+/// let normalized = normalize_time_period("2020-Q2");
+/// assert_eq!(normalized.as_deref(), Some("2020-04-01T00:00:00/P3M"));
+/// let normalized = normalize_time_period("2020-W01");
+/// assert_eq!(normalized.as_deref(), Some("2019-12-30T00:00:00/P7D"));
+/// ```
+pub fn normalize_time_period(value: &str) -> Option<String> {
+	let period: TimePeriod = value.parse().ok()?;
+	let duration = match period {
+		// already a single point in time
+		TimePeriod::DateTime(_) => return Some(value.to_owned()),
+		TimePeriod::TimeRange { start, duration } => {
+			return Some(format!("{}/{}", start.date_time(), duration));
+		}
+		TimePeriod::GregorianYear(_) => "P1Y",
+		TimePeriod::GregorianYearMonth { .. } => "P1M",
+		TimePeriod::GregorianDay(_) => "P1D",
+		TimePeriod::Reporting { period, .. } => match period {
+			ReportingPeriod::Year => "P1Y",
+			ReportingPeriod::Semester(_) => "P6M",
+			ReportingPeriod::Trimester(_) => "P4M",
+			ReportingPeriod::Quarter(_) => "P3M",
+			ReportingPeriod::Month(_) => "P1M",
+			ReportingPeriod::Week(_) => "P7D",
+			ReportingPeriod::Day(_) => "P1D",
+		},
+	};
+	Some(format!("{}/{}", period.start(), duration))
+}
+
+/// Converts a normalized time period back into its original SDMX format,
+/// which is the inverse of [`normalize_time_period`].
+///
+/// Since multiple periods may share the same normalized interval (e.g.
+/// `2020` and `2020-A1`, `2020-11` and `2020-M11`, or `2020-04-09` and
+/// `2020-D100`), a gregorian period is preferred over a reporting period
+/// whenever one exists: only semesters, trimesters, quarters and weeks
+/// are written as reporting periods. Either way, the original period
+/// normalizes to the same interval again. An interval
+/// which does not match any period is kept as an interval.
+///
+/// Returns `None` if the value is not a valid normalized time period.
+///
+/// # Example
+/// ```
+/// use sdmx_csv::original_time_period;
+///
+/// let original = original_time_period("2020-04-01T00:00:00/P3M");
+/// assert_eq!(original.as_deref(), Some("2020-Q2"));
+/// let original = original_time_period("2020-04-01T00:00:00/P1M");
+/// assert_eq!(original.as_deref(), Some("2020-04"));
+/// ```
+pub fn original_time_period(value: &str) -> Option<String> {
+	let Some((_, duration)) = value.split_once('/') else {
+		value.parse::<DateTime>().ok()?;
+		return Some(value.to_owned());
+	};
+	let TimePeriod::TimeRange { start, .. } = value.parse().ok()? else {
+		return None;
+	};
+	let start = match start {
+		RangeStart::DateTime(start) if !is_midnight(&start) => return Some(value.to_owned()),
+		start => start.date_time(),
+	};
+
+	let date = start.date();
+	let (year, month) = (date.year(), date.month());
+	let months = |period: u8| date.day() == 1 && (month - 1) % period == 0;
+	let original = match duration {
+		"P1Y" if months(12) => format!("{:04}", year),
+		"P6M" if months(6) => format!("{:04}-S{}", year, (month - 1) / 6 + 1),
+		"P4M" if months(4) => format!("{:04}-T{}", year, (month - 1) / 4 + 1),
+		"P3M" if months(3) => format!("{:04}-Q{}", year, (month - 1) / 3 + 1),
+		"P1M" if months(1) => format!("{:04}-{:02}", year, month),
+		"P7D" if date.weekday() == 0 => {
+			// the week belongs to the latest year whose first week
+			// does not start after it
+			let first_week = |year| {
+				let period = TimePeriod::Reporting {
+					year,
+					period: ReportingPeriod::Week(1),
+					start_day: MonthDay::default(),
+				};
+				period.start()
+			};
+			let week_year = [year + 1, year, year - 1]
+				.into_iter()
+				.find(|week_year| first_week(*week_year) <= start)?;
+			let days = (start.unix_timestamp() - first_week(week_year).unix_timestamp()) / 86_400;
+			format!("{:04}-W{:02}", week_year, days / 7 + 1)
+		}
+		"P1D" => date.to_string(),
+		_ => format!("{}/{}", date, duration),
+	};
+	Some(original)
+}
+
+/// Whether a date-time is at midnight, without any offset from UTC.
+fn is_midnight(date_time: &DateTime) -> bool {
+	let time = (
+		date_time.hour(),
+		date_time.minute(),
+		date_time.second(),
+		date_time.nanosecond(),
+	);
+	time == (0, 0, 0, 0) && date_time.offset().is_none()
+}
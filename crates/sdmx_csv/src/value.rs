@@ -0,0 +1,190 @@
+use std::borrow::Cow;
+use std::fmt;
+
+const ESCAPE: char = '\\';
+
+/// The value of a cell, which may hold multiple values for a component
+/// which can occur more than once (written as `[a;b;c]`), or multiple
+/// localized texts for a multilingual component (written as
+/// `en:Text;fr:Texte`).
+///
+/// Since a multilingual value cannot be told apart from a single value
+/// without knowing the component (e.g. `en:Text` may as well be a code),
+/// it must be parsed explicitly with [`CellValue::parse_localized`].
+///
+/// Within an array, the `\`, `;`, `[` and `]` characters are escaped
+/// with a `\`. Within a localized text, the `\` and `;` characters are
+/// escaped the same way. A single value is kept as-is, unless it starts
+/// with a `[`, which is then escaped to not be mistaken for an array.
+///
+/// # Example
+/// ```
+/// use sdmx_csv::CellValue;
+///
+/// let value = CellValue::parse("[A;B\\;C]");
+/// assert_eq!(value, CellValue::Array(vec!["A".into(), "B;C".into()]));
+/// assert_eq!(value.to_string(), "[A;B\\;C]");
+///
+/// let value = CellValue::parse_localized("en:Text;fr:Texte");
+/// assert_eq!(value.localized("fr"), Some("Texte"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CellValue<'a> {
+	Single(Cow<'a, str>),
+	Array(Vec<Cow<'a, str>>),
+	/// Pairs of a locale and its text, in the order they appear.
+	Localized(Vec<(Cow<'a, str>, Cow<'a, str>)>),
+}
+
+impl<'a> CellValue<'a> {
+	/// Parses a cell as either an array (if enclosed in square
+	/// brackets), or as a single value. An empty array (`[]`)
+	/// contains no values.
+	pub fn parse(s: &'a str) -> Self {
+		if let Some(rest) = s.strip_prefix("\\[") {
+			let mut single = String::with_capacity(s.len());
+			single.push('[');
+			single.push_str(rest);
+			return Self::Single(Cow::Owned(single));
+		}
+
+		match s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+			Some("") => Self::Array(Vec::new()),
+			Some(inner) if !ends_with_escape(inner) => {
+				Self::Array(split_escaped(inner, ';').map(unescape).collect())
+			}
+			_ => Self::Single(Cow::Borrowed(s)),
+		}
+	}
+
+	/// Parses a cell of a multilingual component, as pairs of a locale
+	/// and its text separated by `:`, each pair separated by `;`.
+	/// A pair without a locale is given an empty locale.
+	pub fn parse_localized(s: &'a str) -> Self {
+		let pairs = split_escaped(s, ';')
+			.filter(|pair| !pair.is_empty())
+			.map(|pair| match pair.split_once(':') {
+				Some((locale, text)) => (Cow::Borrowed(locale), unescape(text)),
+				None => (Cow::Borrowed(""), unescape(pair)),
+			})
+			.collect();
+		Self::Localized(pairs)
+	}
+
+	/// Returns the value if it is a single value.
+	pub fn as_single(&self) -> Option<&str> {
+		match self {
+			Self::Single(value) => Some(value),
+			_ => None,
+		}
+	}
+
+	/// Returns the text for the given locale, if it is a multilingual value.
+	pub fn localized(&self, locale: &str) -> Option<&str> {
+		match self {
+			Self::Localized(pairs) => pairs
+				.iter()
+				.find_map(|(l, text)| (l == locale).then_some(text.as_ref())),
+			_ => None,
+		}
+	}
+
+	/// Copies any borrowed parts of the value, so that
+	/// it no longer borrows from the cell it was parsed from.
+	pub fn into_owned(self) -> CellValue<'static> {
+		let own = |value: Cow<'_, str>| Cow::Owned(value.into_owned());
+		match self {
+			Self::Single(value) => CellValue::Single(own(value)),
+			Self::Array(values) => CellValue::Array(values.into_iter().map(own).collect()),
+			Self::Localized(pairs) => CellValue::Localized(
+				pairs
+					.into_iter()
+					.map(|(locale, text)| (own(locale), own(text)))
+					.collect(),
+			),
+		}
+	}
+}
+
+impl<'a> From<&'a str> for CellValue<'a> {
+	fn from(value: &'a str) -> Self {
+		Self::Single(Cow::Borrowed(value))
+	}
+}
+
+impl fmt::Display for CellValue<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Single(value) => {
+				if value.starts_with('[') {
+					f.write_str("\\")?;
+				}
+				f.write_str(value)
+			}
+			Self::Array(values) => {
+				f.write_str("[")?;
+				for (i, value) in values.iter().enumerate() {
+					if i > 0 {
+						f.write_str(";")?;
+					}
+					write_escaped(f, value, &[ESCAPE, ';', '[', ']'])?;
+				}
+				f.write_str("]")
+			}
+			Self::Localized(pairs) => {
+				for (i, (locale, text)) in pairs.iter().enumerate() {
+					if i > 0 {
+						f.write_str(";")?;
+					}
+					write!(f, "{}:", locale)?;
+					write_escaped(f, text, &[ESCAPE, ';'])?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+/// Splits on a separator which is not preceded by an escape character.
+/// The segments are returned as-is, and still need to be unescaped.
+fn split_escaped(s: &str, separator: char) -> impl Iterator<Item = &str> {
+	let mut escaped = false;
+	s.split(move |c| {
+		let split = !escaped && c == separator;
+		escaped = !escaped && c == ESCAPE;
+		split
+	})
+}
+
+/// Removes escape characters, only allocating if there are any.
+fn unescape(s: &str) -> Cow<'_, str> {
+	if !s.contains(ESCAPE) {
+		return Cow::Borrowed(s);
+	}
+
+	let mut unescaped = String::with_capacity(s.len());
+	let mut chars = s.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			ESCAPE => unescaped.extend(chars.next()),
+			c => unescaped.push(c),
+		}
+	}
+	Cow::Owned(unescaped)
+}
+
+/// Whether the string ends with an escape character which is not
+/// itself escaped (i.e. an odd number of trailing escape characters).
+fn ends_with_escape(s: &str) -> bool {
+	s.chars().rev().take_while(|c| *c == ESCAPE).count() % 2 == 1
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, s: &str, special: &[char]) -> fmt::Result {
+	for c in s.chars() {
+		if special.contains(&c) {
+			f.write_str("\\")?;
+		}
+		write!(f, "{}", c)?;
+	}
+	Ok(())
+}
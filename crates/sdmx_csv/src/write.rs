@@ -0,0 +1,394 @@
+use crate::{
+	normalize_time_period, Action, DataOptions, DataRecord, Error, Keys, Label, Labels,
+	MetadataOptions, MetadataRecord, StructureId, StructureName, TimeFormat, ACTION,
+	METADATASET_ID, METADATASET_NAME, OBS_KEY, SERIES_KEY, STRUCTURE, STRUCTURE_ID, STRUCTURE_NAME,
+	TARGET_IDS, TARGET_NAMES, TARGET_TYPES, TIME_PERIOD,
+};
+use csv::Writer;
+use std::collections::HashMap;
+use std::io;
+
+/// A writer for an SDMX-CSV Data Message.
+///
+/// The header row is written when the writer is created, from a set of
+/// component columns indexed by the same column positions as the
+/// [`DataRecord::components`] (and [`DataRecord::other`]) of the records
+/// that will be written. Columns are written in ascending order of their
+/// position, after the reserved columns required by the [`DataOptions`].
+///
+/// # Example
+/// ```
+/// use sdmx_csv::{DataOptions, DataWriter, InternedHeaders};
+///
+/// fn main() -> Result<(), sdmx_csv::Error> {
+///     let headers = InternedHeaders([(0, "FREQ"), (1, "OBS_VALUE")].into());
+///     let writer = DataWriter::new(Vec::new(), DataOptions::default(), headers.iter())?;
+///     let csv = String::from_utf8(writer.into_inner()?).unwrap();
+///     assert_eq!(csv, "STRUCTURE,STRUCTURE_ID,ACTION,FREQ,OBS_VALUE\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DataWriter<W: io::Write> {
+	writer: Writer<W>,
+	options: DataOptions,
+	columns: Vec<usize>,
+	/// The column of the time dimension, if its values are normalized.
+	time_column: Option<usize>,
+}
+
+impl<W: io::Write> DataWriter<W> {
+	/// Creates a new writer for any [`io::Write`] destination, using
+	/// the default configuration of the `csv` crate, and writes the
+	/// header row.
+	///
+	/// When the labels are configured as [`Labels::Name`] or
+	/// [`Labels::Both`], columns given as a [`Label`] with a localized
+	/// name are written accordingly. Note that [`Labels::Name`] output
+	/// is lossy: as in SDMX-CSV, headers and coded values are written as
+	/// their localized names only, so a [`DataReader`](crate::DataReader)
+	/// cannot map them back to their IDs. Use [`Labels::Both`] instead
+	/// when the output is meant to be read back.
+	pub fn new<'h, I, L>(writer: W, options: DataOptions, headers: I) -> Result<Self, Error>
+	where
+		I: IntoIterator<Item = (usize, L)>,
+		L: Into<Label<'h>>,
+	{
+		Self::from_csv(Writer::from_writer(writer), options, headers)
+	}
+
+	/// Creates a new writer from an already configured CSV writer
+	/// (e.g with a different delimiter), and writes the header row.
+	pub fn from_csv<'h, I, L>(
+		mut writer: Writer<W>,
+		options: DataOptions,
+		headers: I,
+	) -> Result<Self, Error>
+	where
+		I: IntoIterator<Item = (usize, L)>,
+		L: Into<Label<'h>>,
+	{
+		writer.write_field(STRUCTURE)?;
+		writer.write_field(STRUCTURE_ID)?;
+		if options.labels == Labels::Name {
+			writer.write_field(STRUCTURE_NAME)?;
+		}
+		writer.write_field(ACTION)?;
+		if matches!(options.keys, Keys::Series | Keys::Both) {
+			writer.write_field(SERIES_KEY)?;
+		}
+		if matches!(options.keys, Keys::Obs | Keys::Both) {
+			writer.write_field(OBS_KEY)?;
+		}
+		let headers = write_headers(&mut writer, headers, options.labels)?;
+		let time_column = match options.time_format {
+			TimeFormat::Normalized => headers
+				.iter()
+				.find_map(|(column, label)| (label.id == TIME_PERIOD).then_some(*column)),
+			TimeFormat::Original => None,
+		};
+
+		Ok(Self {
+			writer,
+			options,
+			columns: headers.into_iter().map(|(column, _)| column).collect(),
+			time_column,
+		})
+	}
+
+	/// The options this writer was configured with.
+	pub const fn options(&self) -> DataOptions {
+		self.options
+	}
+
+	/// Writes a single record as a row.
+	///
+	/// Cells are written as-is; a component missing from the record is
+	/// written as an empty cell. When the labels are configured as
+	/// [`Labels::Both`], the structure name (if any) is appended to the
+	/// `STRUCTURE_ID` cell; when configured as [`Labels::Name`], it is
+	/// written to the `STRUCTURE_NAME` column.
+	///
+	/// When the time format is [`TimeFormat::Normalized`], the value of
+	/// the `TIME_PERIOD` column is normalized, unless it is not a valid
+	/// time period, in which case it is written as-is.
+	pub fn write_record(&mut self, record: &DataRecord<'_>) -> Result<(), Error> {
+		self.write_labelled_record(record, &HashMap::new())
+	}
+
+	/// Writes a single record as a row, where the coded values of some
+	/// components have a localized name, indexed by their column position.
+	///
+	/// A coded value with a name is written as a [`Label`] according to
+	/// the labels this writer is configured with (e.g. `<id>: <localized
+	/// name>` with [`Labels::Both`]); any other cell is written as with
+	/// [`write_record()`][Self::write_record].
+	pub fn write_labelled_record(
+		&mut self,
+		record: &DataRecord<'_>,
+		names: &HashMap<usize, &str>,
+	) -> Result<(), Error> {
+		let labels = self.options.labels;
+		self.writer.write_field(record.structure.as_str())?;
+		write_structure_id(
+			&mut self.writer,
+			record.structure_id,
+			record.structure_name,
+			labels,
+		)?;
+		write_action(&mut self.writer, record.action)?;
+
+		if matches!(self.options.keys, Keys::Series | Keys::Both) {
+			self.writer
+				.write_field(record.series_key.unwrap_or_default())?;
+		}
+		if matches!(self.options.keys, Keys::Obs | Keys::Both) {
+			self.writer
+				.write_field(record.obs_key.unwrap_or_default())?;
+		}
+
+		let time = self.time_column.and_then(|column| {
+			let value = record.components.get(&column)?;
+			Some((column, normalize_time_period(value)?))
+		});
+		let cells = Cells {
+			components: &record.components,
+			other: &record.other,
+			names,
+			labels,
+		};
+		write_cells(
+			&mut self.writer,
+			&self.columns,
+			&cells,
+			time.as_ref()
+				.map(|(column, value)| (*column, value.as_str())),
+		)
+	}
+
+	/// Flushes the underlying CSV writer.
+	pub fn flush(&mut self) -> Result<(), Error> {
+		self.writer.flush().map_err(|e| Error::Csv(e.into()))
+	}
+
+	/// Flushes the underlying CSV writer, and returns the
+	/// destination it was writing to.
+	pub fn into_inner(self) -> Result<W, Error> {
+		self.writer
+			.into_inner()
+			.map_err(|e| Error::Csv(e.into_error().into()))
+	}
+}
+
+/// A writer for an SDMX-CSV Metadata Message.
+///
+/// This works the same way as a [`DataWriter`], where the header row is
+/// written when the writer is created, from a set of metadata attribute
+/// columns indexed by the same column positions as the
+/// [`MetadataRecord::components`] of the records that will be written.
+/// Nested metadata attributes are expected to be named by their
+/// hierarchical path (e.g. `A.B.C`).
+#[derive(Debug)]
+pub struct MetadataWriter<W: io::Write> {
+	writer: Writer<W>,
+	options: MetadataOptions,
+	columns: Vec<usize>,
+}
+
+impl<W: io::Write> MetadataWriter<W> {
+	/// Creates a new writer for any [`io::Write`] destination, using
+	/// the default configuration of the `csv` crate, and writes the
+	/// header row.
+	pub fn new<'h, I, L>(writer: W, options: MetadataOptions, headers: I) -> Result<Self, Error>
+	where
+		I: IntoIterator<Item = (usize, L)>,
+		L: Into<Label<'h>>,
+	{
+		Self::from_csv(Writer::from_writer(writer), options, headers)
+	}
+
+	/// Creates a new writer from an already configured CSV writer
+	/// (e.g with a different delimiter), and writes the header row.
+	pub fn from_csv<'h, I, L>(
+		mut writer: Writer<W>,
+		options: MetadataOptions,
+		headers: I,
+	) -> Result<Self, Error>
+	where
+		I: IntoIterator<Item = (usize, L)>,
+		L: Into<Label<'h>>,
+	{
+		let names = options.labels == Labels::Name;
+		writer.write_field(STRUCTURE)?;
+		writer.write_field(STRUCTURE_ID)?;
+		if names {
+			writer.write_field(STRUCTURE_NAME)?;
+		}
+		writer.write_field(METADATASET_ID)?;
+		if names {
+			writer.write_field(METADATASET_NAME)?;
+		}
+		writer.write_field(ACTION)?;
+		writer.write_field(TARGET_TYPES)?;
+		writer.write_field(TARGET_IDS)?;
+		if names {
+			writer.write_field(TARGET_NAMES)?;
+		}
+		let headers = write_headers(&mut writer, headers, options.labels)?;
+
+		Ok(Self {
+			writer,
+			options,
+			columns: headers.into_iter().map(|(column, _)| column).collect(),
+		})
+	}
+
+	/// The options this writer was configured with.
+	pub const fn options(&self) -> MetadataOptions {
+		self.options
+	}
+
+	/// Writes a single record as a row.
+	///
+	/// Multiple target types and target IDs are joined with `;`.
+	pub fn write_record(&mut self, record: &MetadataRecord<'_>) -> Result<(), Error> {
+		let labels = self.options.labels;
+		self.writer.write_field(record.md_structure.as_str())?;
+		write_structure_id(
+			&mut self.writer,
+			record.md_structure_id,
+			record.md_structure_name,
+			labels,
+		)?;
+
+		let metadataset = Label {
+			id: record.metadataset_id,
+			localized: record.metadataset_name,
+		};
+		match labels {
+			Labels::Both => self
+				.writer
+				.write_field(metadataset.format(labels).as_ref())?,
+			_ => self.writer.write_field(metadataset.id)?,
+		}
+		if labels == Labels::Name {
+			self.writer
+				.write_field(metadataset.localized.unwrap_or_default())?;
+		}
+
+		write_action(&mut self.writer, record.action)?;
+		self.writer.write_field(record.target_types.join(";"))?;
+		self.writer.write_field(record.target_ids.join(";"))?;
+		if labels == Labels::Name {
+			self.writer
+				.write_field(record.target_names.unwrap_or_default())?;
+		}
+
+		let cells = Cells {
+			components: &record.components,
+			other: &record.others,
+			names: &HashMap::new(),
+			labels,
+		};
+		write_cells(&mut self.writer, &self.columns, &cells, None)
+	}
+
+	/// Flushes the underlying CSV writer.
+	pub fn flush(&mut self) -> Result<(), Error> {
+		self.writer.flush().map_err(|e| Error::Csv(e.into()))
+	}
+
+	/// Flushes the underlying CSV writer, and returns the
+	/// destination it was writing to.
+	pub fn into_inner(self) -> Result<W, Error> {
+		self.writer
+			.into_inner()
+			.map_err(|e| Error::Csv(e.into_error().into()))
+	}
+}
+
+/// Writes the dynamic column headers in ascending order of their position,
+/// then ends the header row. Returns the sorted headers.
+fn write_headers<'h, W, I, L>(
+	writer: &mut Writer<W>,
+	headers: I,
+	labels: Labels,
+) -> Result<Vec<(usize, Label<'h>)>, Error>
+where
+	W: io::Write,
+	I: IntoIterator<Item = (usize, L)>,
+	L: Into<Label<'h>>,
+{
+	let mut headers: Vec<(usize, Label<'h>)> = headers
+		.into_iter()
+		.map(|(column, label)| (column, label.into()))
+		.collect();
+	headers.sort_unstable_by_key(|(column, _)| *column);
+
+	for (_, label) in &headers {
+		writer.write_field(label.format(labels).as_ref())?;
+	}
+	writer.write_record(None::<&[u8]>)?;
+	Ok(headers)
+}
+
+/// Writes the structure ID cell, and the structure name cell if
+/// the labels are configured as [`Labels::Name`].
+fn write_structure_id<W: io::Write>(
+	writer: &mut Writer<W>,
+	id: StructureId<'_>,
+	name: Option<StructureName<'_>>,
+	labels: Labels,
+) -> Result<(), Error> {
+	let localized = name.map(|name| name.localized);
+	match (labels, localized) {
+		(Labels::Both, Some(localized)) => writer.write_field(format!("{}: {}", id, localized))?,
+		_ => writer.write_field(id.to_string())?,
+	}
+	if labels == Labels::Name {
+		writer.write_field(localized.unwrap_or_default())?;
+	}
+	Ok(())
+}
+
+fn write_action<W: io::Write>(writer: &mut Writer<W>, action: Action) -> Result<(), Error> {
+	let mut buffer = [0; 4];
+	let action = char::from(action).encode_utf8(&mut buffer);
+	writer.write_field(action.as_bytes())?;
+	Ok(())
+}
+
+/// The cells of a record, with the localized names of its coded values.
+struct Cells<'c, 'r> {
+	components: &'c HashMap<usize, &'r str>,
+	other: &'c HashMap<usize, &'r str>,
+	names: &'c HashMap<usize, &'c str>,
+	labels: Labels,
+}
+
+/// Writes the cells of the dynamic columns, then ends the row.
+/// The cell of a normalized time period replaces the original one.
+fn write_cells<W: io::Write>(
+	writer: &mut Writer<W>,
+	columns: &[usize],
+	cells: &Cells<'_, '_>,
+	time: Option<(usize, &str)>,
+) -> Result<(), Error> {
+	for column in columns {
+		let cell = match time {
+			Some((time_column, value)) if time_column == *column => Some(value),
+			_ => cells
+				.components
+				.get(column)
+				.or_else(|| cells.other.get(column))
+				.copied(),
+		};
+		let label = Label {
+			id: cell.unwrap_or_default(),
+			localized: cells.names.get(column).copied(),
+		};
+		writer.write_field(label.format(cells.labels).as_ref())?;
+	}
+	writer.write_record(None::<&[u8]>)?;
+	Ok(())
+}
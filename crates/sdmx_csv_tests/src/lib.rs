@@ -0,0 +1,979 @@
+use std::fs::File;
+
+pub fn open_csv(path: &str) -> File {
+	File::open(path).unwrap_or_else(|e| panic!("failed to open {}: {}", path, e))
+}
+
+#[macro_export]
+macro_rules! fixture {
+	($fname:expr) => {
+		concat!(env!("CARGO_MANIFEST_DIR"), "/files/", $fname) // assumes Linux ('/')!
+	};
+}
+
+#[cfg(test)]
+mod tests_data_reader {
+	use super::*;
+	use csv::StringRecord;
+	use sdmx_csv::{
+		Action, DataOptions, DataReader, Error, FieldErrorKind, Keys, Labels, Structure,
+	};
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_read_ids() {
+		let file = open_csv(fixture!("data/exr-ids.csv"));
+		let mut reader = DataReader::new(file, DataOptions::default()).unwrap();
+		let headers = reader.headers();
+		assert_eq!(headers.0.len(), 5);
+		let currency = headers.position("CURRENCY").unwrap();
+		let status = headers.position("OBS_STATUS").unwrap();
+
+		let mut buffer = StringRecord::new();
+		let mut currencies = Vec::new();
+		while let Some(record) = reader.read_record(&mut buffer).unwrap() {
+			assert_eq!(record.structure, Structure::DataFlow);
+			assert_eq!(record.structure_id.artefact_id(), "EXR");
+			assert_eq!(record.structure_id.version(), "1.0.0");
+			assert_eq!(record.action, Action::Information);
+			assert_eq!(record.structure_name, None);
+			currencies.push(record.components[&currency].to_owned());
+			if currencies.len() == 2 {
+				assert!(!record.components.contains_key(&status));
+			}
+		}
+		assert_eq!(currencies, ["USD", "USD", "JPY"]);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_read_both_labels_and_keys() {
+		let options = DataOptions {
+			labels: Labels::Both,
+			keys: Keys::Both,
+			..Default::default()
+		};
+		let file = open_csv(fixture!("data/exr-both.csv"));
+		let mut reader = DataReader::new(file, options).unwrap();
+		let freq = reader.headers().position("FREQ").unwrap();
+
+		let mut buffer = StringRecord::new();
+		let record = reader.read_record(&mut buffer).unwrap().unwrap();
+		assert_eq!(record.action, Action::Append);
+		assert_eq!(record.structure_id.agency(), "ECB");
+		assert_eq!(record.structure_name.unwrap().localized, "Exchange Rates");
+		assert_eq!(record.series_key, Some("M.USD"));
+		assert_eq!(record.obs_key, Some("M.USD.2020-01"));
+		assert_eq!(record.components[&freq], "M: Monthly");
+		assert!(reader.read_record(&mut buffer).unwrap().is_none());
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_read_other_columns() {
+		let file = open_csv(fixture!("data/exr-ids.csv"));
+		let mut reader = DataReader::new(file, DataOptions::default())
+			.unwrap()
+			.with_component_ids(["FREQ", "CURRENCY", "TIME_PERIOD", "OBS_VALUE"]);
+		let status = reader.headers().position("OBS_STATUS").unwrap();
+
+		let mut buffer = StringRecord::new();
+		let record = reader.read_record(&mut buffer).unwrap().unwrap();
+		assert_eq!(record.components.len(), 4);
+		assert_eq!(record.other[&status], "A");
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_missing_key_column() {
+		let options = DataOptions {
+			keys: Keys::Series,
+			..Default::default()
+		};
+		let file = open_csv(fixture!("data/exr-ids.csv"));
+		let result = DataReader::new(file, options);
+		assert!(matches!(result, Err(Error::MissingColumn("SERIES_KEY"))));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_invalid_action() {
+		let file = open_csv(fixture!("data/exr-invalid-action.csv"));
+		let mut reader = DataReader::new(file, DataOptions::default()).unwrap();
+		let mut buffer = StringRecord::new();
+		assert!(reader.read_record(&mut buffer).is_ok());
+
+		let error = reader.read_record(&mut buffer).unwrap_err();
+		assert!(matches!(
+			error,
+			Error::InvalidField {
+				line: 3,
+				column: 3,
+				kind: FieldErrorKind::Action,
+			}
+		));
+	}
+}
+
+#[cfg(test)]
+mod tests_data_writer {
+	use super::*;
+	use csv::StringRecord;
+	use sdmx_csv::{
+		Action, DataOptions, DataReader, DataRecord, DataWriter, Keys, Label, Labels, Structure,
+		StructureId, StructureName,
+	};
+	use std::collections::HashMap;
+	use std::fs::read_to_string;
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_round_trip_ids() {
+		let file = open_csv(fixture!("data/exr-ids.csv"));
+		let mut reader = DataReader::new(file, DataOptions::default()).unwrap();
+		let headers = reader.headers();
+		let mut writer = DataWriter::new(Vec::new(), reader.options(), headers.iter()).unwrap();
+
+		let mut buffer = StringRecord::new();
+		while let Some(record) = reader.read_record(&mut buffer).unwrap() {
+			writer.write_record(&record).unwrap();
+		}
+
+		let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+		let expected = read_to_string(fixture!("data/exr-ids.csv")).unwrap();
+		// an empty action cell is written back as the default action
+		assert_eq!(written, expected.replace(",,M,JPY", ",I,M,JPY"));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_round_trip_both_labels() {
+		let options = DataOptions {
+			labels: Labels::Both,
+			keys: Keys::Both,
+			..Default::default()
+		};
+		let file = open_csv(fixture!("data/exr-both.csv"));
+		let mut reader = DataReader::new(file, options).unwrap();
+		let header_row = {
+			let expected = read_to_string(fixture!("data/exr-both.csv")).unwrap();
+			expected.lines().next().unwrap().to_owned()
+		};
+		let labels: Vec<(usize, Label)> = header_row
+			.split(',')
+			.enumerate()
+			.skip(5)
+			.map(|(column, header)| (column, Label::parse(header)))
+			.collect();
+		let mut writer = DataWriter::new(Vec::new(), options, labels).unwrap();
+
+		let mut buffer = StringRecord::new();
+		while let Some(record) = reader.read_record(&mut buffer).unwrap() {
+			writer.write_record(&record).unwrap();
+		}
+
+		let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+		let expected = read_to_string(fixture!("data/exr-both.csv")).unwrap();
+		assert_eq!(written, expected);
+	}
+
+	#[test]
+	fn test_structure_name_column() {
+		let options = DataOptions {
+			labels: Labels::Name,
+			..Default::default()
+		};
+		let labels = [(0, Label::parse("FREQ: Frequency"))];
+		let writer = DataWriter::new(Vec::new(), options, labels).unwrap();
+		let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+		assert_eq!(
+			written,
+			"STRUCTURE,STRUCTURE_ID,STRUCTURE_NAME,ACTION,Frequency\n"
+		);
+	}
+
+	#[test]
+	fn test_round_trip_labelled_record() {
+		let headers = [
+			(0, Label::parse("FREQ: Frequency")),
+			(1, Label::parse("OBS_VALUE: Observation value")),
+		];
+		let names = HashMap::from([(0, "Monthly")]);
+		let structure_id = StructureId::parse("ECB:EXR(1.0.0)").unwrap();
+		let record = DataRecord {
+			structure: Structure::DataFlow,
+			structure_id,
+			structure_name: Some(StructureName {
+				id: structure_id,
+				localized: "Exchange rates",
+			}),
+			action: Action::Information,
+			series_key: None,
+			obs_key: None,
+			components: HashMap::from([(0, "M"), (1, "1.25")]),
+			other: HashMap::new(),
+		};
+
+		// Names are written in place of IDs (including in the headers),
+		// so that a record written with names cannot be mapped back.
+		for (labels, [freq_header, value_header], freq) in [
+			(Labels::Id, ["FREQ", "OBS_VALUE"], "M"),
+			(Labels::Name, ["Frequency", "Observation value"], "Monthly"),
+			(Labels::Both, ["FREQ", "OBS_VALUE"], "M: Monthly"),
+		] {
+			let options = DataOptions {
+				labels,
+				..Default::default()
+			};
+			let mut writer = DataWriter::new(Vec::new(), options, headers).unwrap();
+			writer.write_labelled_record(&record, &names).unwrap();
+			let written = writer.into_inner().unwrap();
+
+			let mut reader = DataReader::new(written.as_slice(), options).unwrap();
+			let headers = reader.headers();
+			let (freq_column, value_column) = (
+				headers.position(freq_header).unwrap(),
+				headers.position(value_header).unwrap(),
+			);
+			let mut buffer = StringRecord::new();
+			let read = reader.read_record(&mut buffer).unwrap().unwrap();
+			assert_eq!(read.structure_id, structure_id);
+			assert_eq!(read.components[&freq_column], freq);
+			assert_eq!(read.components[&value_column], "1.25");
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_metadata {
+	use super::*;
+	use csv::StringRecord;
+	use sdmx_csv::{
+		Error, FieldErrorKind, Labels, MetadataOptions, MetadataReader, MetadataWriter, Structure,
+	};
+	use std::fs::read_to_string;
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_read_metadata() {
+		let file = open_csv(fixture!("metadata/ecb-metadata.csv"));
+		let mut reader = MetadataReader::new(file, MetadataOptions::default()).unwrap();
+		let headers = reader.headers();
+		let email = headers.position("CONTACT.EMAIL").unwrap();
+		let path: Vec<&str> = headers.path(email).unwrap().collect();
+		assert_eq!(path, ["CONTACT", "EMAIL"]);
+
+		let mut buffer = StringRecord::new();
+		let record = reader.read_record(&mut buffer).unwrap().unwrap();
+		assert_eq!(record.md_structure, Structure::MetadataFlow);
+		assert_eq!(record.md_structure_id.artefact_id(), "MDF_QUALITY");
+		assert_eq!(record.metadataset_id, "MDS_1");
+		assert_eq!(record.target_types, ["Dataflow"]);
+		assert_eq!(record.components.len(), 3);
+		assert_eq!(record.components[&email], "statistics@ecb.europa.eu");
+
+		let record = reader.read_record(&mut buffer).unwrap().unwrap();
+		assert_eq!(record.target_types, ["Dataflow", "DataStructure"]);
+		assert_eq!(record.target_ids.len(), 2);
+		assert_eq!(record.components.len(), 1);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_round_trip_metadata() {
+		let file = open_csv(fixture!("metadata/ecb-metadata.csv"));
+		let mut reader = MetadataReader::new(file, MetadataOptions::default()).unwrap();
+		let headers = reader.headers();
+		let mut writer = MetadataWriter::new(Vec::new(), reader.options(), headers.iter()).unwrap();
+
+		let mut buffer = StringRecord::new();
+		while let Some(record) = reader.read_record(&mut buffer).unwrap() {
+			writer.write_record(&record).unwrap();
+		}
+
+		let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+		let expected = read_to_string(fixture!("metadata/ecb-metadata.csv")).unwrap();
+		assert_eq!(written, expected);
+	}
+
+	#[test]
+	fn test_data_structure_in_metadata() {
+		let csv = "STRUCTURE,STRUCTURE_ID,METADATASET_ID,TARGET_TYPES,TARGET_IDS\n\
+		           dataflow,ECB:EXR(1.0.0),MDS_1,Dataflow,EXR\n";
+		let mut reader = MetadataReader::new(csv.as_bytes(), MetadataOptions::default()).unwrap();
+		let mut buffer = StringRecord::new();
+		let error = reader.read_record(&mut buffer).unwrap_err();
+		assert!(matches!(
+			error,
+			Error::InvalidField {
+				line: 2,
+				column: 1,
+				kind: FieldErrorKind::Structure,
+			}
+		));
+	}
+
+	#[test]
+	fn test_metadata_name_columns() {
+		let options = MetadataOptions {
+			labels: Labels::Name,
+		};
+		let csv = "STRUCTURE,STRUCTURE_ID,METADATASET_ID,TARGET_TYPES,TARGET_IDS\n";
+		let result = MetadataReader::new(csv.as_bytes(), options);
+		assert!(matches!(
+			result,
+			Err(Error::MissingColumn("STRUCTURE_NAME"))
+		));
+
+		let writer = MetadataWriter::new(Vec::new(), options, [(0, "QUALITY")]).unwrap();
+		let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+		assert_eq!(
+			written,
+			"STRUCTURE,STRUCTURE_ID,STRUCTURE_NAME,METADATASET_ID,METADATASET_NAME,\
+			 ACTION,TARGET_TYPES,TARGET_IDS,TARGET_NAMES,QUALITY\n"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests_media_type {
+	use sdmx_csv::{DataOptions, Keys, Labels, MediaTypeError, MetadataOptions, TimeFormat};
+
+	#[test]
+	fn test_data_media_type() {
+		let options = DataOptions::from_media_type(
+			"application/vnd.sdmx.data+csv; version=2.0.0; labels=name; q=0.9; charset=utf-8",
+		)
+		.unwrap();
+		assert_eq!(options.labels, Labels::Name);
+		assert_eq!(options.time_format, TimeFormat::Original);
+		assert_eq!(options.keys, Keys::None);
+
+		let options = DataOptions {
+			labels: Labels::Both,
+			time_format: TimeFormat::Normalized,
+			keys: Keys::Obs,
+		};
+		let media_type = options.to_media_type();
+		assert_eq!(
+			media_type,
+			"application/vnd.sdmx.data+csv;version=2.0.0;labels=both;timeFormat=normalized;keys=obs"
+		);
+		assert_eq!(DataOptions::from_media_type(&media_type), Ok(options));
+	}
+
+	#[test]
+	fn test_metadata_media_type() {
+		let options =
+			MetadataOptions::from_media_type("application/vnd.sdmx.metadata+csv").unwrap();
+		assert_eq!(options, MetadataOptions::default());
+
+		let options = MetadataOptions {
+			labels: Labels::Name,
+		};
+		let media_type = options.to_media_type();
+		assert_eq!(MetadataOptions::from_media_type(&media_type), Ok(options));
+	}
+
+	#[test]
+	fn test_invalid_media_type() {
+		let result = DataOptions::from_media_type("application/vnd.sdmx.data+csv;labels=code");
+		assert_eq!(
+			result,
+			Err(MediaTypeError::InvalidParameterValue {
+				name: "labels".to_owned(),
+				value: "code".to_owned(),
+			})
+		);
+
+		for version in ["1.0.0", "2.banana", "2.", "2.1", "3", "", "2.0.0.0", "+2"] {
+			let media_type = format!("application/vnd.sdmx.data+csv;version={}", version);
+			let result = DataOptions::from_media_type(&media_type);
+			assert_eq!(
+				result,
+				Err(MediaTypeError::UnsupportedVersion(version.to_owned()))
+			);
+		}
+		for version in ["2", "2.0", "2.0.0", "2.0.1"] {
+			let media_type = format!("application/vnd.sdmx.data+csv;version={}", version);
+			assert!(
+				DataOptions::from_media_type(&media_type).is_ok(),
+				"{}",
+				version
+			);
+		}
+
+		let result = DataOptions::from_media_type("application/vnd.sdmx.metadata+csv");
+		assert!(matches!(result, Err(MediaTypeError::UnexpectedType(_))));
+	}
+}
+
+#[cfg(test)]
+mod tests_structure {
+	use sdmx_csv::{FieldErrorKind, Structure, StructureId, StructureName};
+
+	#[test]
+	fn test_parse_structure() {
+		let structure: Structure = "dataprovision".parse().unwrap();
+		assert_eq!(structure, Structure::DataProvision);
+		assert_eq!(structure.to_string(), "dataprovision");
+
+		let error = "DataFlow".parse::<Structure>().unwrap_err();
+		assert_eq!(error.kind(), FieldErrorKind::Structure);
+	}
+
+	#[test]
+	fn test_parse_structure_id() {
+		let id = StructureId::try_from("SDMX:AGENCIES(1.0)").unwrap();
+		assert_eq!(id.agency(), "SDMX");
+		assert_eq!(id.artefact_id(), "AGENCIES");
+		assert_eq!(id.version(), "1.0");
+
+		for invalid in [
+			"ECB:EXR",
+			"ECB:EXR()",
+			":EXR(1.0.0)",
+			"ECB(1.0.0)",
+			"ECB:EXR(1.0.0",
+		] {
+			let error = StructureId::parse(invalid).unwrap_err();
+			assert_eq!(error.kind(), FieldErrorKind::StructureId, "{}", invalid);
+		}
+	}
+
+	#[test]
+	fn test_parse_structure_name() {
+		let name = StructureName::parse("ECB:EXR(1.0.0): Exchange Rates: Daily").unwrap();
+		assert_eq!(name.id, StructureId::new("ECB", "EXR", "1.0.0"));
+		assert_eq!(name.localized, "Exchange Rates: Daily");
+		assert_eq!(name.to_string(), "ECB:EXR(1.0.0): Exchange Rates: Daily");
+
+		let error = StructureName::parse("ECB:EXR(1.0.0)").unwrap_err();
+		assert_eq!(error.kind(), FieldErrorKind::StructureName);
+	}
+}
+
+#[cfg(test)]
+mod tests_owned {
+	use super::*;
+	use csv::StringRecord;
+	use sdmx_csv::{
+		DataOptions, DataReader, DataWriter, MetadataOptions, MetadataReader, OwnedDataRecord,
+		OwnedStructureId,
+	};
+	use std::fs::read_to_string;
+	use std::sync::mpsc;
+	use std::thread;
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_owned_records_across_threads() {
+		let file = open_csv(fixture!("data/exr-ids.csv"));
+		let mut reader = DataReader::new(file, DataOptions::default()).unwrap();
+		let headers: Vec<(usize, String)> = reader
+			.headers()
+			.iter()
+			.map(|(column, header)| (column, header.to_owned()))
+			.collect();
+
+		let (sender, receiver) = mpsc::channel::<OwnedDataRecord>();
+		let worker = thread::spawn(move || {
+			let headers = headers.iter().map(|(c, h)| (*c, h.as_str()));
+			let mut writer = DataWriter::new(Vec::new(), DataOptions::default(), headers).unwrap();
+			for record in receiver {
+				writer.write_record(&record.as_record()).unwrap();
+			}
+			writer.into_inner().unwrap()
+		});
+
+		let mut buffer = StringRecord::new();
+		while let Some(record) = reader.read_record(&mut buffer).unwrap() {
+			sender.send(record.into_owned()).unwrap();
+		}
+		drop(sender);
+
+		let written = String::from_utf8(worker.join().unwrap()).unwrap();
+		let expected = read_to_string(fixture!("data/exr-ids.csv")).unwrap();
+		assert_eq!(written, expected.replace(",,M,JPY", ",I,M,JPY"));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_owned_metadata_record() {
+		let file = open_csv(fixture!("metadata/ecb-metadata.csv"));
+		let mut reader = MetadataReader::new(file, MetadataOptions::default()).unwrap();
+		let mut buffer = StringRecord::new();
+		let record = reader.read_record(&mut buffer).unwrap().unwrap();
+		let owned = record.clone().into_owned();
+		assert_eq!(owned.as_record(), record);
+	}
+
+	#[test]
+	fn test_parse_owned_structure_id() {
+		let id: OwnedStructureId = "ECB:EXR(1.0.0)".parse().unwrap();
+		assert_eq!(id, OwnedStructureId::new("ECB", "EXR", "1.0.0"));
+		assert_eq!(id.to_string(), "ECB:EXR(1.0.0)");
+		assert!("ECB:EXR".parse::<OwnedStructureId>().is_err());
+	}
+}
+
+#[cfg(test)]
+mod tests_value {
+	use sdmx_csv::CellValue;
+	use std::borrow::Cow;
+
+	#[test]
+	fn test_array_value() {
+		let value = CellValue::parse("[A;B;C]");
+		assert_eq!(
+			value,
+			CellValue::Array(vec!["A".into(), "B".into(), "C".into()])
+		);
+		assert_eq!(CellValue::parse("[]"), CellValue::Array(Vec::new()));
+
+		let values = vec![Cow::from("a;b"), Cow::from("[c]"), Cow::from("d\\")];
+		let written = CellValue::Array(values.clone()).to_string();
+		assert_eq!(written, r"[a\;b;\[c\];d\\]");
+		assert_eq!(CellValue::parse(&written), CellValue::Array(values));
+	}
+
+	#[test]
+	fn test_single_value() {
+		assert_eq!(CellValue::parse("1.2345").as_single(), Some("1.2345"));
+		assert_eq!(CellValue::parse("en:Text").as_single(), Some("en:Text"));
+
+		let value = CellValue::from("[not an array]");
+		let written = value.to_string();
+		assert_eq!(written, "\\[not an array]");
+		assert_eq!(CellValue::parse(&written), value);
+	}
+
+	#[test]
+	fn test_localized_value() {
+		let value = CellValue::parse_localized(r"en:Ratio: 1\;2;fr:Ratio : 1\;2");
+		assert_eq!(value.localized("en"), Some("Ratio: 1;2"));
+		assert_eq!(value.localized("fr"), Some("Ratio : 1;2"));
+		assert_eq!(value.localized("de"), None);
+		assert_eq!(value.to_string(), r"en:Ratio: 1\;2;fr:Ratio : 1\;2");
+
+		let owned = {
+			let cell = String::from("en:Text");
+			CellValue::parse_localized(&cell).into_owned()
+		};
+		assert_eq!(owned.localized("en"), Some("Text"));
+	}
+}
+
+#[cfg(test)]
+mod tests_json_export {
+	use sdmx_csv::json::{ConvertError, DataMessageExporter};
+	use sdmx_csv::{DataOptions, DataWriter, Keys, Labels};
+	use sdmx_json::data::DataMessage;
+	use std::collections::HashMap;
+	use std::fs::read_to_string;
+	use std::str::FromStr;
+
+	fn export(message: &DataMessage, options: DataOptions) -> Result<String, ConvertError> {
+		let exporter = DataMessageExporter::new(message);
+		let mut writer = DataWriter::new(Vec::new(), options, exporter.headers())?;
+		exporter.write(&mut writer)?;
+		Ok(String::from_utf8(writer.into_inner()?).unwrap())
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_export_ids() {
+		let json = read_to_string(fixture!("json/exr-data.json")).unwrap();
+		let message = DataMessage::from_str(&json).unwrap();
+		let options = DataOptions {
+			keys: Keys::Series,
+			..Default::default()
+		};
+
+		let written = export(&message, options).unwrap();
+		let expected = read_to_string(fixture!("json/exr-data.csv")).unwrap();
+		assert_eq!(written, expected);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_export_both_labels() {
+		let json = read_to_string(fixture!("json/exr-data.json")).unwrap();
+		let message = DataMessage::from_str(&json).unwrap();
+		let options = DataOptions {
+			labels: Labels::Both,
+			..Default::default()
+		};
+
+		let written = export(&message, options).unwrap();
+		let mut lines = written.lines();
+		assert!(lines
+			.next()
+			.unwrap()
+			.starts_with("STRUCTURE,STRUCTURE_ID,ACTION,FREQ: Frequency,CURRENCY: Currency,"));
+		assert!(lines
+			.next()
+			.unwrap()
+			.starts_with("dataflow,ECB:EXR(1.0.0),R,M: Monthly,USD: US dollar,2020-01,1.1,"));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_export_series_without_observations() {
+		let json = read_to_string(fixture!("json/exr-data.json")).unwrap();
+		let mut message = DataMessage::from_str(&json).unwrap();
+		let data = message.data.as_mut().unwrap();
+		let series = data.data_sets.as_mut().unwrap()[0].series.as_mut().unwrap();
+		series.get_mut("1").unwrap().observations = Some(HashMap::new());
+		// the time dimension is not decoded for a series without observations
+		series.remove("0");
+		let structure = &mut data.structures.as_mut().unwrap()[0];
+		structure.dimensions.observation.as_mut().unwrap()[0].values = Some(Vec::new());
+
+		let options = DataOptions {
+			keys: Keys::Series,
+			..Default::default()
+		};
+		let written = export(&message, options).unwrap();
+		let row = written.lines().nth(1).unwrap();
+		assert_eq!(
+			row,
+			"dataflow,ECB:EXR(1.0.0),R,M.JPY,M,JPY,,,en:Exchange rates;fr:Taux de change,2,"
+		);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_export_missing_reference() {
+		let json = read_to_string(fixture!("json/exr-data.json")).unwrap();
+		let mut message = DataMessage::from_str(&json).unwrap();
+		let data = message.data.as_mut().unwrap();
+		data.structures.as_mut().unwrap()[0].links = None;
+
+		let result = export(&message, DataOptions::default());
+		assert!(matches!(
+			result,
+			Err(ConvertError::MissingStructureReference)
+		));
+	}
+}
+
+#[cfg(test)]
+mod tests_json_import {
+	use super::*;
+	use csv::StringRecord;
+	use sdmx_csv::json::{ConvertError, DataMessageExporter, DataMessageImporter};
+	use sdmx_csv::{DataOptions, DataReader, DataWriter, Keys};
+	use sdmx_json::data::DataMessage;
+	use sdmx_json::primitives::SdmxValue;
+	use sdmx_json::structure::DataStructure;
+	use std::fs::read_to_string;
+	use std::io::Read;
+
+	fn structure() -> DataStructure {
+		let json = read_to_string(fixture!("json/exr-structure.json")).unwrap();
+		serde_json::from_str(&json).unwrap()
+	}
+
+	fn import(csv: impl Read, options: DataOptions) -> Result<DataMessage, ConvertError> {
+		import_with(&structure(), csv, options)
+	}
+
+	fn import_with(
+		structure: &DataStructure,
+		csv: impl Read,
+		options: DataOptions,
+	) -> Result<DataMessage, ConvertError> {
+		let mut reader = DataReader::new(csv, options)?;
+		let mut importer = DataMessageImporter::new(structure, &reader.headers(), options.labels);
+
+		let mut buffer = StringRecord::new();
+		while let Some(record) = reader.read_record(&mut buffer)? {
+			importer.add_record(&record)?;
+		}
+		Ok(importer.finish())
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_import_levels() {
+		let options = DataOptions {
+			keys: Keys::Series,
+			..Default::default()
+		};
+		let message = import(open_csv(fixture!("json/exr-data.csv")), options).unwrap();
+		let data = message.data.unwrap();
+		let structure = &data.structures.unwrap()[0];
+		let ids = |components: &Option<Vec<sdmx_json::data::Component>>| {
+			let components = components.as_deref().unwrap_or_default();
+			components.iter().map(|c| c.id.clone()).collect::<Vec<_>>()
+		};
+		assert_eq!(ids(&structure.dimensions.series), ["FREQ", "CURRENCY"]);
+		assert_eq!(ids(&structure.dimensions.observation), ["TIME_PERIOD"]);
+		assert_eq!(ids(&structure.attributes.data_set), ["TITLE"]);
+		assert_eq!(ids(&structure.attributes.series), ["DECIMALS"]);
+		assert_eq!(ids(&structure.attributes.observation), ["OBS_STATUS"]);
+
+		let data_set = &data.data_sets.unwrap()[0];
+		let series = data_set.series.as_ref().unwrap();
+		assert_eq!(series.len(), 2);
+		let jpy = &series["0:1"];
+		assert_eq!(jpy.attributes, Some(vec![SdmxValue::Integer(1)]));
+		let observation = &jpy.observations.as_ref().unwrap()["1"];
+		assert_eq!(
+			observation,
+			&vec![SdmxValue::Number(120.5), SdmxValue::Integer(0)]
+		);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_import_round_trip() {
+		let options = DataOptions {
+			keys: Keys::Series,
+			..Default::default()
+		};
+		let message = import(open_csv(fixture!("json/exr-data.csv")), options).unwrap();
+
+		let exporter = DataMessageExporter::new(&message);
+		let mut writer = DataWriter::new(Vec::new(), options, exporter.headers()).unwrap();
+		exporter.write(&mut writer).unwrap();
+		let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+		let expected = read_to_string(fixture!("json/exr-data.csv")).unwrap();
+		assert_eq!(written, expected);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_import_without_time_dimension() {
+		let json = read_to_string(fixture!("json/exr-structure.json")).unwrap();
+		let mut structure: serde_json::Value = serde_json::from_str(&json).unwrap();
+		let dimensions = &mut structure["dataStructureComponents"]["dimensionList"];
+		dimensions.as_object_mut().unwrap().remove("timeDimensions");
+		let structure: DataStructure = serde_json::from_value(structure).unwrap();
+
+		let csv =
+			"STRUCTURE,STRUCTURE_ID,ACTION,FREQ,CURRENCY,OBS_VALUE,TITLE,DECIMALS,OBS_STATUS\n\
+			dataflow,ECB:EXR(1.0.0),R,M,USD,1.1,,4,A\n\
+			dataflow,ECB:EXR(1.0.0),R,M,JPY,120.5,,2,A\n";
+		let options = DataOptions::default();
+		let message = import_with(&structure, csv.as_bytes(), options).unwrap();
+
+		// Attributes of the whole key are attached to the observations,
+		// as there are no series without a time dimension.
+		let data = message.data.as_ref().unwrap();
+		let json_structure = &data.structures.as_ref().unwrap()[0];
+		let ids = |components: &Option<Vec<sdmx_json::data::Component>>| {
+			let components = components.as_deref().unwrap_or_default();
+			components.iter().map(|c| c.id.clone()).collect::<Vec<_>>()
+		};
+		assert_eq!(ids(&json_structure.attributes.series), Vec::<String>::new());
+		assert_eq!(
+			ids(&json_structure.attributes.observation),
+			["DECIMALS", "OBS_STATUS"]
+		);
+		let data_set = &data.data_sets.as_ref().unwrap()[0];
+		assert_eq!(data_set.series, None);
+		let observations = data_set.observations.as_ref().unwrap();
+		assert_eq!(observations.len(), 2);
+		assert_eq!(
+			observations["0:1"],
+			vec![
+				SdmxValue::Number(120.5),
+				SdmxValue::Integer(1),
+				SdmxValue::Integer(0)
+			]
+		);
+
+		let exporter = DataMessageExporter::new(&message);
+		let mut writer = DataWriter::new(Vec::new(), options, exporter.headers()).unwrap();
+		exporter.write(&mut writer).unwrap();
+		let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+		assert_eq!(written, csv);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_import_missing_dimension() {
+		let csv = "STRUCTURE,STRUCTURE_ID,ACTION,FREQ,CURRENCY,TIME_PERIOD,OBS_VALUE\n\
+			dataflow,ECB:EXR(1.0.0),I,,USD,2020-01,1.1\n";
+		let result = import(csv.as_bytes(), DataOptions::default());
+		assert!(matches!(
+			result,
+			Err(ConvertError::MissingDimension(id)) if id == "FREQ"
+		));
+	}
+}
+
+#[cfg(test)]
+mod tests_time {
+	use csv::StringRecord;
+	use sdmx_csv::{
+		normalize_time_period, original_time_period, DataOptions, DataReader, DataWriter,
+		TimeFormat,
+	};
+
+	#[test]
+	fn test_normalize_periods() {
+		let periods = [
+			("2020", "2020-01-01T00:00:00/P1Y"),
+			("2020-03", "2020-03-01T00:00:00/P1M"),
+			("2020-02-29", "2020-02-29T00:00:00/P1D"),
+			("2020-A1", "2020-01-01T00:00:00/P1Y"),
+			("2020-S2", "2020-07-01T00:00:00/P6M"),
+			("2020-T3", "2020-09-01T00:00:00/P4M"),
+			("2020-Q4", "2020-10-01T00:00:00/P3M"),
+			("2020-M11", "2020-11-01T00:00:00/P1M"),
+			("2020-W05", "2020-01-27T00:00:00/P7D"),
+			("2020-W53", "2020-12-28T00:00:00/P7D"),
+			("2021-W01", "2021-01-04T00:00:00/P7D"),
+			("2020-D100", "2020-04-09T00:00:00/P1D"),
+			("2020-D366", "2020-12-31T00:00:00/P1D"),
+			("2020-01-15/P2M", "2020-01-15T00:00:00/P2M"),
+			("2020-01-15T12:30:00/PT6H", "2020-01-15T12:30:00/PT6H"),
+			("2020-01-15T12:30:00", "2020-01-15T12:30:00"),
+		];
+		for (period, normalized) in periods {
+			assert_eq!(
+				normalize_time_period(period).as_deref(),
+				Some(normalized),
+				"{}",
+				period
+			);
+		}
+
+		let invalid = [
+			"",
+			"20",
+			"2020-",
+			"2020-13",
+			"2019-02-29",
+			"2020-Q5",
+			"2020-Q0",
+			"2021-W53",
+			"2021-D366",
+			"2020-X1",
+			"2020/1Y",
+			"M",
+		];
+		for period in invalid {
+			assert_eq!(normalize_time_period(period), None, "{}", period);
+		}
+	}
+
+	#[test]
+	fn test_original_periods() {
+		let periods = [
+			"2020",
+			"2020-03",
+			"2020-02-29",
+			"2020-S2",
+			"2020-T3",
+			"2020-Q4",
+			"2020-W05",
+			"2020-W53",
+			"2021-W01",
+			"2020-01-15/P2M",
+		];
+		for period in periods {
+			let normalized = normalize_time_period(period).unwrap();
+			assert_eq!(
+				original_time_period(&normalized).as_deref(),
+				Some(period),
+				"{}",
+				normalized
+			);
+		}
+
+		// Reporting periods with a gregorian equivalent are not kept.
+		let normalized = normalize_time_period("2020-D100").unwrap();
+		assert_eq!(original_time_period(&normalized).unwrap(), "2020-04-09");
+		let normalized = normalize_time_period("2020-M11").unwrap();
+		assert_eq!(original_time_period(&normalized).unwrap(), "2020-11");
+		assert_eq!(original_time_period("2020-Q1"), None);
+	}
+
+	#[test]
+	fn test_round_trip_reporting_periods() {
+		// Reporting periods with a gregorian equivalent come back as that
+		// equivalent, which normalizes to the same interval.
+		let periods = [
+			("2020-A1", "2020"),
+			("2020-S1", "2020-S1"),
+			("2020-S2", "2020-S2"),
+			("2020-T1", "2020-T1"),
+			("2020-T2", "2020-T2"),
+			("2020-T3", "2020-T3"),
+			("2020-Q1", "2020-Q1"),
+			("2020-Q2", "2020-Q2"),
+			("2020-Q3", "2020-Q3"),
+			("2020-Q4", "2020-Q4"),
+			("2020-M01", "2020-01"),
+			("2020-M12", "2020-12"),
+			("2019-W01", "2019-W01"),
+			("2020-W01", "2020-W01"),
+			("2019-W52", "2019-W52"),
+			("2015-W53", "2015-W53"),
+			("2020-D001", "2020-01-01"),
+			("2020-D060", "2020-02-29"),
+			("2020-D366", "2020-12-31"),
+			("2021-D365", "2021-12-31"),
+		];
+		for (period, original) in periods {
+			let normalized = normalize_time_period(period).unwrap();
+			assert_eq!(
+				original_time_period(&normalized).as_deref(),
+				Some(original),
+				"{}",
+				period
+			);
+			assert_eq!(
+				normalize_time_period(original).as_deref(),
+				Some(normalized.as_str()),
+				"{}",
+				period
+			);
+		}
+	}
+
+	#[test]
+	fn test_write_and_read_normalized() {
+		let options = DataOptions {
+			time_format: TimeFormat::Normalized,
+			..Default::default()
+		};
+		let csv = "STRUCTURE,STRUCTURE_ID,ACTION,TIME_PERIOD,OBS_VALUE\n\
+		           dataflow,ECB:EXR(1.0.0),I,2020-Q1,1.1\n\
+		           dataflow,ECB:EXR(1.0.0),I,unknown,1.2\n";
+
+		let mut reader = DataReader::new(csv.as_bytes(), DataOptions::default()).unwrap();
+		let headers = reader.headers();
+		let mut writer = DataWriter::new(Vec::new(), options, headers.iter()).unwrap();
+		let mut buffer = StringRecord::new();
+		while let Some(record) = reader.read_record(&mut buffer).unwrap() {
+			writer.write_record(&record).unwrap();
+		}
+		let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+		assert_eq!(
+			written,
+			"STRUCTURE,STRUCTURE_ID,ACTION,TIME_PERIOD,OBS_VALUE\n\
+			 dataflow,ECB:EXR(1.0.0),I,2020-01-01T00:00:00/P3M,1.1\n\
+			 dataflow,ECB:EXR(1.0.0),I,unknown,1.2\n"
+		);
+
+		let mut reader = DataReader::new(written.as_bytes(), options).unwrap();
+		let time = reader.headers().position("TIME_PERIOD").unwrap();
+		let record = reader.read_record(&mut buffer).unwrap().unwrap();
+		assert_eq!(record.components[&time], "2020-Q1");
+		assert_eq!(record.components.len(), 2);
+		assert_eq!(buffer.position().unwrap().line(), 2);
+
+		// A time column which is not a component stays in the other cells.
+		let reader = DataReader::new(written.as_bytes(), options).unwrap();
+		let mut reader = reader.with_component_ids(["OBS_VALUE"]);
+		let record = reader.read_record(&mut buffer).unwrap().unwrap();
+		assert_eq!(record.other[&time], "2020-Q1");
+		assert_eq!(record.other.len(), 1);
+		assert_eq!(record.components.len(), 1);
+	}
+}
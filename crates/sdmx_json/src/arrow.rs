@@ -0,0 +1,344 @@
+use crate::data::{
+	components, sentinel, structure_of, CellValue, Component, Data, DataMessage, DataSet,
+	DataSetRows, DecodeError, Labels, Level, Row, Structure,
+};
+use crate::primitives::{DataType, SdmxValue};
+use arrow_array::builder::{
+	BooleanBuilder, Float64Builder, Int64Builder, StringBuilder, StringDictionaryBuilder,
+};
+use arrow_array::types::Int32Type;
+use arrow_array::{ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{ArrowError, DataType as ArrowType, Field, Schema};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+impl DataMessage {
+	/// Converts every dataset of this message into a [`RecordBatch`],
+	/// in the same order as the datasets.
+	///
+	/// See [`Data::to_record_batch`] for how the columns are typed.
+	pub fn to_record_batches(&self) -> Result<Vec<RecordBatch>, ToArrowError> {
+		let Some(data) = &self.data else {
+			return Ok(Vec::new());
+		};
+		let data_sets = data.data_sets.iter().flatten();
+		data_sets
+			.map(|data_set| data.to_record_batch(data_set))
+			.collect()
+	}
+}
+
+impl Data {
+	/// Converts a dataset into a [`RecordBatch`], according to the
+	/// structure it refers to in this data message. There is one row
+	/// per observation, and one column per dimension, measure and
+	/// attribute of the structure (in that order), named by their ID.
+	///
+	/// - Dimensions, and attributes with a list of values, are
+	///   dictionary-encoded columns of the IDs of their values.
+	/// - Measures are typed after the data type of their format: integers
+	///   as `Int64`, decimals and floating-point numbers as `Float64`,
+	///   booleans as `Boolean`, and anything else (or a measure without
+	///   a data type) as `Utf8`. Sentinel values of a typed measure
+	///   are nulls.
+	/// - Other attributes are `Utf8` columns, where values which are not
+	///   strings are written in JSON.
+	///
+	/// Attributes at the dataset, dimension group, and series levels are
+	/// inherited by every observation they are attached to.
+	///
+	/// # Example
+	/// ```no_run
+	/// use std::str::FromStr;
+	/// use std::fs::read_to_string;
+	/// use sdmx_json::data::DataMessage;
+	///
+	/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+	///     let file = read_to_string("sdmx-data.json")?;
+	///     let message = DataMessage::from_str(file.as_str())?;
+	///     let data = message.data.unwrap_or_default();
+	///     for data_set in data.data_sets.iter().flatten() {
+	///         let batch = data.to_record_batch(data_set)?;
+	///         println!("{} rows", batch.num_rows());
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn to_record_batch(&self, data_set: &DataSet) -> Result<RecordBatch, ToArrowError> {
+		let mut batch = BatchBuilder::new(structure_of(self, data_set)?)?;
+		let mut rows = DataSetRows::new(self, 0, data_set)?;
+		while let Some(row) = rows.next(Labels::Id) {
+			batch.append(&row?)?;
+		}
+		batch.finish()
+	}
+}
+
+/// An error which can occur when converting data into Arrow arrays.
+#[derive(Debug)]
+pub enum ToArrowError {
+	/// The observations of a dataset cannot be decoded.
+	Decode(DecodeError),
+	/// A value of a measure does not match the data type of its column.
+	InvalidValue { component: String, value: SdmxValue },
+	/// The arrays cannot be built by Arrow.
+	Arrow(ArrowError),
+}
+
+impl Error for ToArrowError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::Decode(error) => Some(error),
+			Self::Arrow(error) => Some(error),
+			Self::InvalidValue { .. } => None,
+		}
+	}
+}
+
+impl fmt::Display for ToArrowError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Decode(error) => error.fmt(f),
+			Self::InvalidValue { component, value } => {
+				write!(f, "invalid value {:?} for component {}", value, component)
+			}
+			Self::Arrow(error) => error.fmt(f),
+		}
+	}
+}
+
+impl From<DecodeError> for ToArrowError {
+	fn from(error: DecodeError) -> Self {
+		Self::Decode(error)
+	}
+}
+
+impl From<ArrowError> for ToArrowError {
+	fn from(error: ArrowError) -> Self {
+		Self::Arrow(error)
+	}
+}
+
+/// The columns of a record batch being built, with their schema.
+struct BatchBuilder<'d> {
+	components: Vec<&'d Component>,
+	fields: Vec<Field>,
+	columns: Vec<Column>,
+}
+
+enum Column {
+	Dictionary(StringDictionaryBuilder<Int32Type>),
+	String(StringBuilder),
+	Int64(Int64Builder),
+	Float64(Float64Builder),
+	Boolean(BooleanBuilder),
+}
+
+impl<'d> BatchBuilder<'d> {
+	fn new(structure: &'d Structure) -> Result<Self, ToArrowError> {
+		let mut batch = Self {
+			components: Vec::new(),
+			fields: Vec::new(),
+			columns: Vec::new(),
+		};
+
+		let levels = [Level::DataSet, Level::Series, Level::Observation];
+		for dimension in levels
+			.iter()
+			.flat_map(|level| structure.dimensions.components(*level))
+		{
+			batch.push(dimension, Column::dictionary(dimension)?);
+		}
+
+		let measures = structure.measures.as_ref();
+		let measures = measures.map(|m| components(&m.observation));
+		for measure in measures.unwrap_or_default() {
+			let data_type = measure.format.as_ref().and_then(|f| f.data_type.as_ref());
+			let column = match data_type {
+				Some(data_type) if is_integer(data_type) => Column::Int64(Int64Builder::new()),
+				Some(data_type) if is_float(data_type) => Column::Float64(Float64Builder::new()),
+				Some(DataType::Boolean) => Column::Boolean(BooleanBuilder::new()),
+				_ => Column::String(StringBuilder::new()),
+			};
+			batch.push(measure, column);
+		}
+
+		let levels = [
+			Level::DataSet,
+			Level::DimensionGroup,
+			Level::Series,
+			Level::Observation,
+		];
+		for attribute in levels
+			.iter()
+			.flat_map(|level| structure.attributes.components(*level))
+		{
+			let column = match &attribute.values {
+				Some(values) if !values.is_empty() => Column::dictionary(attribute)?,
+				_ => Column::String(StringBuilder::new()),
+			};
+			batch.push(attribute, column);
+		}
+		Ok(batch)
+	}
+
+	fn push(&mut self, component: &'d Component, column: Column) {
+		let data_type = match &column {
+			Column::Dictionary(_) => {
+				ArrowType::Dictionary(Box::new(ArrowType::Int32), Box::new(ArrowType::Utf8))
+			}
+			Column::String(_) => ArrowType::Utf8,
+			Column::Int64(_) => ArrowType::Int64,
+			Column::Float64(_) => ArrowType::Float64,
+			Column::Boolean(_) => ArrowType::Boolean,
+		};
+		self.fields
+			.push(Field::new(component.id.as_str(), data_type, true));
+		self.components.push(component);
+		self.columns.push(column);
+	}
+
+	/// Appends a row, whose cells are in the same order as the columns.
+	fn append(&mut self, row: &Row<'_>) -> Result<(), ToArrowError> {
+		let columns = self.columns.iter_mut().zip(&self.components);
+		for ((column, component), cell) in columns.zip(&row.cells) {
+			column.append(component, cell.value)?;
+		}
+		Ok(())
+	}
+
+	fn finish(self) -> Result<RecordBatch, ToArrowError> {
+		let columns: Vec<ArrayRef> = self
+			.columns
+			.into_iter()
+			.map(|column| match column {
+				Column::Dictionary(mut b) => Arc::new(b.finish()) as ArrayRef,
+				Column::String(mut b) => Arc::new(b.finish()),
+				Column::Int64(mut b) => Arc::new(b.finish()),
+				Column::Float64(mut b) => Arc::new(b.finish()),
+				Column::Boolean(mut b) => Arc::new(b.finish()),
+			})
+			.collect();
+		let schema = Arc::new(Schema::new(self.fields));
+		Ok(RecordBatch::try_new(schema, columns)?)
+	}
+}
+
+impl Column {
+	/// A dictionary-encoded column, whose dictionary starts
+	/// with the IDs of the values of a component.
+	fn dictionary(component: &Component) -> Result<Self, ArrowError> {
+		let values = component.values.iter().flatten().flatten();
+		let ids: StringArray = values.map(|value| Some(value.id.as_str())).collect();
+		let builder = StringDictionaryBuilder::new_with_dictionary(0, &ids)?;
+		Ok(Self::Dictionary(builder))
+	}
+
+	fn append(
+		&mut self,
+		component: &Component,
+		value: Option<CellValue<'_>>,
+	) -> Result<(), ToArrowError> {
+		let invalid_value = |value: &SdmxValue| ToArrowError::InvalidValue {
+			component: component.id.clone(),
+			value: value.clone(),
+		};
+		// A sentinel value of a typed column is a null,
+		// as it has a special meaning rather than a value.
+		let value = match (&self, value) {
+			(Self::Dictionary(_) | Self::String(_), value) => value,
+			(_, Some(CellValue::Value(value)))
+				if component
+					.format
+					.as_ref()
+					.and_then(|f| sentinel(f, value))
+					.is_some() =>
+			{
+				None
+			}
+			(_, value) => value,
+		};
+		match (self, value) {
+			(Self::Dictionary(b), None) => b.append_null(),
+			(Self::Dictionary(b), Some(value)) => {
+				b.append(text(value))?;
+			}
+			(Self::String(b), value) => b.append_option(value.map(text)),
+			(Self::Int64(b), None) => b.append_null(),
+			(Self::Int64(b), Some(CellValue::Value(value))) => {
+				let number = match value {
+					SdmxValue::Integer(integer) => Some(*integer as i64),
+					SdmxValue::Number(number) => integral(*number),
+					SdmxValue::String(string) => string.parse().ok(),
+					_ => None,
+				};
+				b.append_value(number.ok_or_else(|| invalid_value(value))?);
+			}
+			(Self::Float64(b), None) => b.append_null(),
+			(Self::Float64(b), Some(CellValue::Value(value))) => {
+				let number = match value {
+					SdmxValue::Integer(integer) => Some(*integer as f64),
+					SdmxValue::Number(number) => Some(*number),
+					SdmxValue::String(string) => string.parse().ok(),
+					_ => None,
+				};
+				b.append_value(number.ok_or_else(|| invalid_value(value))?);
+			}
+			(Self::Boolean(b), None) => b.append_null(),
+			(Self::Boolean(b), Some(CellValue::Value(value))) => {
+				let boolean = match value {
+					SdmxValue::Boolean(boolean) => Some(*boolean),
+					SdmxValue::String(string) => string.parse().ok(),
+					_ => None,
+				};
+				b.append_value(boolean.ok_or_else(|| invalid_value(value))?);
+			}
+			(_, Some(CellValue::Text(text))) => {
+				let value = SdmxValue::String(text.to_owned());
+				return Err(invalid_value(&value));
+			}
+		}
+		Ok(())
+	}
+}
+
+/// The text of a cell, where a value which
+/// is not a string is written in JSON.
+fn text(value: CellValue<'_>) -> String {
+	match value {
+		CellValue::Text(text) => text.to_owned(),
+		CellValue::Value(SdmxValue::String(text)) => text.clone(),
+		CellValue::Value(value) => serde_json::to_string(value).unwrap_or_default(),
+	}
+}
+
+/// A finite number without a fractional part (e.g. `3.0`),
+/// which is within the range of an `i64`.
+fn integral(number: f64) -> Option<i64> {
+	let in_range = (i64::MIN as f64..i64::MAX as f64).contains(&number);
+	(in_range && number.fract() == 0.0).then_some(number as i64)
+}
+
+fn is_integer(data_type: &DataType) -> bool {
+	matches!(
+		data_type,
+		DataType::BigInteger
+			| DataType::Integer
+			| DataType::Long
+			| DataType::Short
+			| DataType::Count
+	)
+}
+
+fn is_float(data_type: &DataType) -> bool {
+	matches!(
+		data_type,
+		DataType::Decimal
+			| DataType::Float
+			| DataType::Double
+			| DataType::InclusiveValueRange
+			| DataType::ExclusiveValueRange
+			| DataType::Incremental
+	)
+}
@@ -339,3 +339,5 @@ impl_extendable!(
 	DataSet,
 	Series,
 );
+
+impl_annotated!(Component, ComponentValue, DataSet, Series);
@@ -0,0 +1,48 @@
+use crate::data::{DecodeError, Structure};
+use crate::primitives::Annotation;
+
+/// An object of a data message which refers to annotations
+/// by their index in the annotations of its [`Structure`].
+pub trait Annotated {
+	/// The indices of the annotations this object refers to.
+	fn annotation_indices(&self) -> &[usize];
+}
+
+impl Structure {
+	/// The annotations referred to by a dataset, a series, a component,
+	/// or a component value, in the order of their indices. An index
+	/// which is out of range for the annotations of this structure is
+	/// an error.
+	///
+	/// # Example
+	/// ```
+	/// use sdmx_json::data::{Series, Structure};
+	/// use sdmx_json::primitives::Annotation;
+	///
+	/// let mut structure = Structure::default();
+	/// structure.annotations = Some(vec![Annotation {
+	///     title: Some("Break in series".to_owned()),
+	///     ..Default::default()
+	/// }]);
+	///
+	/// let series = Series {
+	///     annotations: Some(vec![0, 1]),
+	///     ..Default::default()
+	/// };
+	/// let mut annotations = structure.annotations_of(&series);
+	/// let footnote = annotations.next().unwrap().unwrap();
+	/// assert_eq!(footnote.title.as_deref(), Some("Break in series"));
+	/// assert!(annotations.next().unwrap().is_err());
+	/// ```
+	pub fn annotations_of<'a, T: Annotated>(
+		&'a self,
+		item: &'a T,
+	) -> impl Iterator<Item = Result<&'a Annotation, DecodeError>> + 'a {
+		let annotations = self.annotations.as_deref().unwrap_or_default();
+		item.annotation_indices().iter().map(|index| {
+			annotations
+				.get(*index)
+				.ok_or(DecodeError::MissingAnnotation(*index))
+		})
+	}
+}
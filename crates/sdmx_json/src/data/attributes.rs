@@ -0,0 +1,122 @@
+use crate::data::{
+	components, Component, ComponentValue, DecodeError, DimsMeasuresAttributes, Structure,
+};
+use crate::primitives::SdmxValue;
+
+/// The levels of a data message which components can be attached
+/// to, in the order they appear in a [`DimsMeasuresAttributes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Level {
+	/// The whole dataset.
+	DataSet,
+	/// A group of series, sharing the values of some dimensions.
+	DimensionGroup,
+	/// A single series.
+	Series,
+	/// A single observation.
+	Observation,
+}
+
+impl DimsMeasuresAttributes {
+	/// The components at a given level, if any.
+	pub fn components(&self, level: Level) -> &[Component] {
+		components(match level {
+			Level::DataSet => &self.data_set,
+			Level::DimensionGroup => &self.dimension_group,
+			Level::Series => &self.series,
+			Level::Observation => &self.observation,
+		})
+	}
+}
+
+impl Structure {
+	/// Resolves the values of the attributes at a given level, which are
+	/// positioned in the same order as the attributes of that level:
+	/// - [`DataSet::attributes`][crate::data::DataSet::attributes] for the dataset level,
+	/// - each of [`DataSet::dimension_group_attributes`][crate::data::DataSet::dimension_group_attributes]
+	///   for the dimension group level,
+	/// - [`Series::attributes`][crate::data::Series::attributes] for the series level,
+	/// - and each array of an observation for the observation level, where
+	///   the values of the measures come first and are skipped.
+	///
+	/// An attribute without a value (i.e. a `null` value, or an index to a
+	/// `null` value) is skipped. A value which is an index out of range for
+	/// the values of its attribute is an error.
+	///
+	/// # Example
+	/// ```
+	/// use sdmx_json::data::{AttributeValue, Component, ComponentValue, Level, Structure};
+	/// use sdmx_json::primitives::SdmxValue;
+	///
+	/// let mut structure = Structure::default();
+	/// let status = Component {
+	///     id: "OBS_STATUS".to_owned(),
+	///     values: Some(vec![Some(ComponentValue {
+	///         id: "A".to_owned(),
+	///         ..Default::default()
+	///     })]),
+	///     ..Default::default()
+	/// };
+	/// structure.attributes.series = Some(vec![status]);
+	///
+	/// let values = [SdmxValue::Integer(0)];
+	/// let mut attributes = structure.resolve_attributes(Level::Series, &values);
+	/// let (id, value) = attributes.next().unwrap().unwrap();
+	/// assert_eq!(id, "OBS_STATUS");
+	/// assert!(matches!(value, AttributeValue::Coded(code) if code.id == "A"));
+	/// ```
+	pub fn resolve_attributes<'d>(
+		&'d self,
+		level: Level,
+		values: &'d [SdmxValue],
+	) -> impl Iterator<Item = Result<(&'d str, AttributeValue<'d>), DecodeError>> + 'd {
+		let values = match level {
+			Level::Observation => {
+				let measures = self.measures.as_ref();
+				let measures = measures.map_or(0, |m| m.components(Level::Observation).len());
+				values.get(measures..).unwrap_or_default()
+			}
+			_ => values,
+		};
+		self.attributes
+			.components(level)
+			.iter()
+			.zip(values)
+			.filter_map(|(attribute, value)| {
+				let value = AttributeValue::resolve(attribute, value).transpose()?;
+				Some(value.map(|value| (attribute.id.as_str(), value)))
+			})
+	}
+}
+
+/// The value of an attribute, which is either one of
+/// the values of its component, or a literal value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeValue<'d> {
+	Coded(&'d ComponentValue),
+	Literal(&'d SdmxValue),
+}
+
+impl<'d> AttributeValue<'d> {
+	/// Resolves the value of an attribute, which is an index into the
+	/// values of its component if it has any (i.e. the list of values
+	/// is not empty), and otherwise a literal.
+	/// A `null` value (or an index to a `null` value) has no value.
+	pub(crate) fn resolve(
+		component: &'d Component,
+		value: &'d SdmxValue,
+	) -> Result<Option<Self>, DecodeError> {
+		match (value, &component.values) {
+			(SdmxValue::Null, _) => Ok(None),
+			(SdmxValue::Integer(index), Some(values)) if !values.is_empty() => {
+				let index = usize::try_from(*index)
+					.map_err(|_| DecodeError::invalid_index(component, *index))?;
+				match values.get(index) {
+					Some(value) => Ok(value.as_ref().map(Self::Coded)),
+					None => Err(DecodeError::invalid_index(component, index as isize)),
+				}
+			}
+			(value, _) => Ok(Some(Self::Literal(value))),
+		}
+	}
+}
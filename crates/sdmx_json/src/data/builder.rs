@@ -0,0 +1,600 @@
+use crate::data::{
+	AttributeRelationship as DataRelationship, Component, ComponentValue, Data, DataMessage,
+	DataSet, DimsMeasuresAttributes, Series, Structure,
+};
+use crate::primitives::{Action, Link, Location, SdmxValue};
+use crate::structure::{AttributeRelationship, DataStructure, Group};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+
+/// Builds an SDMX-JSON data message with a single dataset, from
+/// observations whose components are given by their ID, according to
+/// a data structure definition.
+///
+/// The values of the dimensions, and of the coded measures and attributes,
+/// are those actually used by the observations, in order of their first
+/// use. Series and observation keys are encoded as indices into them.
+///
+/// Attributes are placed at the level dictated by their relationship:
+/// - a relationship to the dataflow is at the dataset level,
+/// - a relationship to the observation, or to dimensions which include
+///   the time dimension (or all dimensions), is at the observation level,
+/// - a relationship to all the other dimensions is at the series level,
+///   if observations are in series,
+/// - and a relationship to a group, or to only some dimensions,
+///   is at the dimension group level.
+///
+/// # Example
+/// ```
+/// use sdmx_json::data::{DataMessageBuilder, NewObservation, ObservationLayout};
+/// use sdmx_json::primitives::SdmxValue;
+/// use sdmx_json::structure::DataStructure;
+///
+/// fn build(structure: &DataStructure) -> Result<(), Box<dyn std::error::Error + 'static>> {
+///     let mut builder = DataMessageBuilder::new(structure, ObservationLayout::Series);
+///     let observation = NewObservation::default()
+///         .dimension("FREQ", "A")
+///         .dimension("TIME_PERIOD", "2020")
+///         .measure("OBS_VALUE", SdmxValue::Number(1.5));
+///     builder.add_observation(&observation)?;
+///
+///     let message = builder.build();
+///     assert_eq!(message.data.unwrap().data_sets.unwrap().len(), 1);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DataMessageBuilder {
+	structure_link: Option<Link>,
+	action: Option<Action>,
+	/// All dimensions, in order of their position. The time
+	/// dimension (if any) is always last.
+	dimensions: Vec<BuiltComponent>,
+	/// The number of dimensions at the series level,
+	/// which are the first ones.
+	series_len: usize,
+	measures: Vec<BuiltComponent>,
+	attributes: Vec<BuiltComponent>,
+	data_set: BuiltDataSet,
+}
+
+/// Where the observations of a dataset are placed in a data message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ObservationLayout {
+	/// Observations are grouped in series, by all the dimensions except
+	/// the time dimension. Without a time dimension, observations are
+	/// not in series.
+	#[default]
+	Series,
+	/// Observations are not in series, and their keys
+	/// are made of all dimensions.
+	Flat,
+}
+
+/// An observation to add to a [`DataMessageBuilder`],
+/// with its components indexed by their ID.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NewObservation {
+	/// The ID of the value of every dimension.
+	pub dimensions: HashMap<String, String>,
+	/// The values of the measures. A coded measure has
+	/// the ID of its value as a string.
+	pub measures: HashMap<String, SdmxValue>,
+	/// The values of the attributes. A coded attribute has the ID of its
+	/// value as a string (or an array of IDs, for multiple values).
+	pub attributes: HashMap<String, SdmxValue>,
+}
+
+impl NewObservation {
+	/// Sets the value of a dimension.
+	pub fn dimension(mut self, id: impl Into<String>, value: impl Into<String>) -> Self {
+		self.dimensions.insert(id.into(), value.into());
+		self
+	}
+
+	/// Sets the value of a measure.
+	pub fn measure(mut self, id: impl Into<String>, value: SdmxValue) -> Self {
+		self.measures.insert(id.into(), value);
+		self
+	}
+
+	/// Sets the value of an attribute.
+	pub fn attribute(mut self, id: impl Into<String>, value: SdmxValue) -> Self {
+		self.attributes.insert(id.into(), value);
+		self
+	}
+}
+
+/// An error which can occur when adding an observation
+/// to a [`DataMessageBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+	/// A dimension of the structure has no value.
+	MissingDimension(String),
+	/// A component is not in the structure.
+	UnknownComponent(String),
+	/// A value of a coded component is not the ID of a value.
+	InvalidCode(String),
+}
+
+impl Error for BuildError {}
+
+impl fmt::Display for BuildError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::MissingDimension(id) => write!(f, "missing value for dimension {}", id),
+			Self::UnknownComponent(id) => write!(f, "unknown component {}", id),
+			Self::InvalidCode(id) => write!(f, "invalid coded value for component {}", id),
+		}
+	}
+}
+
+/// The level of an attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Attachment {
+	DataSet,
+	/// The positions of the dimensions in the group.
+	DimensionGroup(Vec<usize>),
+	Series,
+	Observation,
+}
+
+#[derive(Debug, Clone)]
+struct BuiltComponent {
+	id: String,
+	coded: bool,
+	/// Only used for attributes.
+	attachment: Attachment,
+	relationship: Option<DataRelationship>,
+	/// The position of this attribute among the
+	/// other attributes at the same level.
+	offset: usize,
+	values: Vec<ComponentValue>,
+	indices: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct BuiltDataSet {
+	attributes: Vec<SdmxValue>,
+	groups: BTreeMap<String, Vec<SdmxValue>>,
+	series: BTreeMap<String, BuiltSeries>,
+	observations: BTreeMap<String, Vec<SdmxValue>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct BuiltSeries {
+	attributes: Vec<SdmxValue>,
+	observations: BTreeMap<String, Vec<SdmxValue>>,
+}
+
+impl DataMessageBuilder {
+	/// Creates a builder for the observations of a data structure.
+	pub fn new(structure: &DataStructure, layout: ObservationLayout) -> Self {
+		let components = structure.data_structure_components.as_ref();
+
+		let mut dimensions: Vec<_> = components
+			.and_then(|c| c.dimension_list.dimensions.as_ref())
+			.into_iter()
+			.flatten()
+			.map(|dimension| {
+				let id = dimension.id.as_deref();
+				let id = id.unwrap_or_else(|| dimension.concept_id());
+				(dimension.position, BuiltComponent::new(id, true))
+			})
+			.collect();
+		dimensions.sort_by_key(|(position, _)| *position);
+		let mut dimensions: Vec<_> = dimensions.into_iter().map(|(_, c)| c).collect();
+
+		let time = components.and_then(|c| c.dimension_list.time_dimensions.as_ref());
+		if let Some(time) = time {
+			let id = time.id.as_deref();
+			let id = id.unwrap_or_else(|| time.concept_id());
+			dimensions.push(BuiltComponent::new(id, true));
+		}
+		let series_len = match layout {
+			ObservationLayout::Series if time.is_some() => dimensions.len() - 1,
+			_ => 0,
+		};
+
+		let measures = components
+			.and_then(|c| c.measure_list.as_ref())
+			.and_then(|m| m.measures.as_ref())
+			.into_iter()
+			.flatten()
+			.map(|measure| {
+				let representation = &measure.local_representation;
+				BuiltComponent::new(&measure.id, representation.enumeration.is_some())
+			})
+			.collect();
+
+		let groups = components.and_then(|c| c.groups.as_deref());
+		let mut offsets = HashMap::new();
+		let attributes = components
+			.and_then(|c| c.attribute_list.as_ref())
+			.and_then(|a| a.attributes.as_ref())
+			.into_iter()
+			.flatten()
+			.map(|attribute| {
+				let representation = &attribute.local_representation;
+				let mut component =
+					BuiltComponent::new(&attribute.id, representation.enumeration.is_some());
+				let (attachment, relationship) = attachment(
+					&attribute.attribute_relationship,
+					&dimensions,
+					time.map(|_| dimensions.len() - 1),
+					series_len,
+					groups.unwrap_or_default(),
+				);
+				let offset = offsets.entry(attachment_kind(&attachment)).or_insert(0);
+				component.offset = *offset;
+				*offset += 1;
+				component.attachment = attachment;
+				component.relationship = Some(relationship);
+				component
+			})
+			.collect();
+
+		let structure_link = structure.common.agency_id.as_deref().and_then(|agency| {
+			let version = structure.common.version.as_deref()?;
+			let urn = format!(
+				"urn:sdmx:org.sdmx.infomodel.datastructure.DataStructure={}:{}({})",
+				agency, structure.common.id, version
+			);
+			Some(Link {
+				location: Location::Urn(urn),
+				rel: "datastructure".to_owned(),
+				url: None,
+				uri: None,
+				title: None,
+				titles: None,
+				type_: None,
+				hreflang: None,
+				other: None,
+			})
+		});
+
+		Self {
+			structure_link,
+			action: None,
+			dimensions,
+			series_len,
+			measures,
+			attributes,
+			data_set: BuiltDataSet::default(),
+		}
+	}
+
+	/// Sets the action of the dataset.
+	pub fn action(mut self, action: Action) -> Self {
+		self.action = Some(action);
+		self
+	}
+
+	/// Adds an observation to the dataset. An observation with the same
+	/// key as a previous one replaces it, while the values of attributes
+	/// above the observation level are merged.
+	///
+	/// Returns an error if a dimension has no value, if a component is not
+	/// in the structure, or if a coded value is not a string. The builder
+	/// is left unchanged when the observation is invalid.
+	pub fn add_observation(&mut self, observation: &NewObservation) -> Result<(), BuildError> {
+		let unknown = observation
+			.dimensions
+			.keys()
+			.find(|id| !self.dimensions.iter().any(|d| &d.id == *id))
+			.or_else(|| {
+				let ids = observation.measures.keys();
+				ids.chain(observation.attributes.keys()).find(|id| {
+					!self
+						.measures
+						.iter()
+						.chain(&self.attributes)
+						.any(|c| &c.id == *id)
+				})
+			});
+		if let Some(id) = unknown {
+			return Err(BuildError::UnknownComponent(id.clone()));
+		}
+		for dimension in &self.dimensions {
+			if !observation.dimensions.contains_key(&dimension.id) {
+				return Err(BuildError::MissingDimension(dimension.id.clone()));
+			}
+		}
+		let coded = self.measures.iter().map(|m| (m, &observation.measures));
+		let coded = coded.chain(self.attributes.iter().map(|a| (a, &observation.attributes)));
+		for (component, values) in coded.filter(|(c, _)| c.coded) {
+			if !values.get(&component.id).map_or(true, is_code) {
+				return Err(BuildError::InvalidCode(component.id.clone()));
+			}
+		}
+
+		let key: Vec<usize> = self
+			.dimensions
+			.iter_mut()
+			.map(|dimension| dimension.intern(&observation.dimensions[&dimension.id]))
+			.collect();
+		let series_key = join_key(&key[..self.series_len]);
+		let obs_key = join_key(&key[self.series_len..]);
+
+		let mut values: Vec<SdmxValue> = self
+			.measures
+			.iter_mut()
+			.map(|measure| measure.value(observation.measures.get(&measure.id)))
+			.collect();
+
+		let data_set = &mut self.data_set;
+		for attribute in &mut self.attributes {
+			let value = attribute.value(observation.attributes.get(&attribute.id));
+			if value == SdmxValue::Null {
+				continue;
+			}
+
+			let offset = attribute.offset;
+			match &attribute.attachment {
+				Attachment::DataSet => set(&mut data_set.attributes, offset, value),
+				Attachment::DimensionGroup(positions) => {
+					let group_key = key
+						.iter()
+						.enumerate()
+						.map(|(position, index)| match positions.contains(&position) {
+							true => index.to_string(),
+							false => String::new(),
+						})
+						.collect::<Vec<_>>()
+						.join(":");
+					let group = data_set.groups.entry(group_key).or_default();
+					set(group, offset, value);
+				}
+				Attachment::Series => {
+					let series = data_set.series.entry(series_key.clone()).or_default();
+					set(&mut series.attributes, offset, value);
+				}
+				Attachment::Observation => set(&mut values, self.measures.len() + offset, value),
+			}
+		}
+
+		if self.series_len > 0 {
+			let series = data_set.series.entry(series_key).or_default();
+			series.observations.insert(obs_key, values);
+		} else {
+			data_set.observations.insert(obs_key, values);
+		}
+		Ok(())
+	}
+
+	/// Builds the data message from all the observations added so far.
+	pub fn build(self) -> DataMessage {
+		let mut dimensions: Vec<Component> = self
+			.dimensions
+			.iter()
+			.enumerate()
+			.map(|(position, dimension)| Component {
+				key_position: Some(position),
+				..dimension.to_component()
+			})
+			.collect();
+		let observation_dimensions = dimensions.split_off(self.series_len);
+
+		let attributes_at = |kind: u8| -> Option<Vec<Component>> {
+			let attributes: Vec<Component> = self
+				.attributes
+				.iter()
+				.filter(|a| attachment_kind(&a.attachment) == kind)
+				.map(|a| Component {
+					relationship: a.relationship.clone(),
+					..a.to_component()
+				})
+				.collect();
+			(!attributes.is_empty()).then_some(attributes)
+		};
+
+		let structure = Structure {
+			links: self.structure_link.map(|link| vec![link]),
+			dimensions: DimsMeasuresAttributes {
+				series: (!dimensions.is_empty()).then_some(dimensions),
+				observation: (!observation_dimensions.is_empty()).then_some(observation_dimensions),
+				..Default::default()
+			},
+			measures: Some(DimsMeasuresAttributes {
+				observation: Some(self.measures.iter().map(|m| m.to_component()).collect()),
+				..Default::default()
+			}),
+			attributes: DimsMeasuresAttributes {
+				data_set: attributes_at(0),
+				dimension_group: attributes_at(1),
+				series: attributes_at(2),
+				observation: attributes_at(3),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		let data_set = self.data_set;
+		let series: HashMap<String, Series> = data_set
+			.series
+			.into_iter()
+			.map(|(key, series)| {
+				let series = Series {
+					attributes: (!series.attributes.is_empty()).then_some(series.attributes),
+					observations: (!series.observations.is_empty())
+						.then(|| series.observations.into_iter().collect()),
+					..Default::default()
+				};
+				(key, series)
+			})
+			.collect();
+		let data_set = DataSet {
+			structure: Some(0),
+			action: self.action,
+			attributes: (!data_set.attributes.is_empty()).then_some(data_set.attributes),
+			dimension_group_attributes: (!data_set.groups.is_empty())
+				.then(|| data_set.groups.into_iter().collect()),
+			series: (!series.is_empty()).then_some(series),
+			observations: (!data_set.observations.is_empty())
+				.then(|| data_set.observations.into_iter().collect()),
+			..Default::default()
+		};
+
+		DataMessage {
+			data: Some(Data {
+				structures: Some(vec![structure]),
+				data_sets: Some(vec![data_set]),
+				other: None,
+			}),
+			..Default::default()
+		}
+	}
+}
+
+impl BuiltComponent {
+	fn new(id: &str, coded: bool) -> Self {
+		Self {
+			id: id.to_owned(),
+			coded,
+			attachment: Attachment::Observation,
+			relationship: None,
+			offset: 0,
+			values: Vec::new(),
+			indices: HashMap::new(),
+		}
+	}
+
+	/// Returns the index of a coded value, adding it to
+	/// the values of this component if it is not used yet.
+	fn intern(&mut self, id: &str) -> usize {
+		if let Some(index) = self.indices.get(id) {
+			return *index;
+		}
+
+		let index = self.values.len();
+		self.values.push(ComponentValue {
+			id: id.to_owned(),
+			..Default::default()
+		});
+		self.indices.insert(id.to_owned(), index);
+		index
+	}
+
+	/// Encodes a value, which is an index if the component is coded.
+	fn value(&mut self, value: Option<&SdmxValue>) -> SdmxValue {
+		match value {
+			None => SdmxValue::Null,
+			Some(SdmxValue::String(id)) if self.coded => {
+				SdmxValue::Integer(self.intern(id) as isize)
+			}
+			Some(SdmxValue::Array(values)) if self.coded => {
+				let values = values.iter().map(|value| self.value(Some(value)));
+				SdmxValue::Array(Box::new(values.collect()))
+			}
+			Some(value) => value.clone(),
+		}
+	}
+
+	fn to_component(&self) -> Component {
+		Component {
+			id: self.id.clone(),
+			values: (self.coded || !self.values.is_empty())
+				.then(|| self.values.iter().cloned().map(Some).collect()),
+			..Default::default()
+		}
+	}
+}
+
+/// Whether a value is the ID of a coded value (or multiple IDs).
+fn is_code(value: &SdmxValue) -> bool {
+	match value {
+		SdmxValue::Null | SdmxValue::String(_) => true,
+		SdmxValue::Array(values) => values.iter().all(|v| matches!(v, SdmxValue::String(_))),
+		_ => false,
+	}
+}
+
+/// Determines the level of an attribute from its relationship, and
+/// converts the relationship to its SDMX-JSON Data Message equivalent.
+fn attachment(
+	relationship: &AttributeRelationship,
+	dimensions: &[BuiltComponent],
+	time: Option<usize>,
+	series_len: usize,
+	groups: &[Group],
+) -> (Attachment, DataRelationship) {
+	let empty = || Value::Object(Map::new());
+	let at_dimensions = |ids: &[String]| {
+		let positions: Vec<usize> = ids
+			.iter()
+			.filter_map(|id| dimensions.iter().position(|d| &d.id == id))
+			.collect();
+		let attachment = if positions.is_empty() {
+			Attachment::DataSet
+		} else if positions.len() == dimensions.len()
+			|| time.is_some_and(|t| positions.contains(&t))
+		{
+			Attachment::Observation
+		} else if series_len > 0 && positions.len() == series_len {
+			Attachment::Series
+		} else {
+			Attachment::DimensionGroup(positions)
+		};
+		let relationship = DataRelationship {
+			dimensions: Some(ids.to_vec()),
+			..Default::default()
+		};
+		(attachment, relationship)
+	};
+
+	match relationship {
+		AttributeRelationship::DataFlow(_) => (
+			Attachment::DataSet,
+			DataRelationship {
+				dataflow: Some(empty()),
+				..Default::default()
+			},
+		),
+		AttributeRelationship::Dimensions(d) => {
+			at_dimensions(d.dimensions.as_deref().unwrap_or_default())
+		}
+		AttributeRelationship::Groups(g) => {
+			let ids = groups
+				.iter()
+				.find(|group| group.id == g.group)
+				.and_then(|group| group.group_dimensions.as_deref())
+				.unwrap_or_default();
+			at_dimensions(ids)
+		}
+		AttributeRelationship::Observations(_) => (
+			Attachment::Observation,
+			DataRelationship {
+				observation: Some(empty()),
+				..Default::default()
+			},
+		),
+	}
+}
+
+/// Identifies a level regardless of the dimensions in a group,
+/// in the order the levels appear in a data message.
+fn attachment_kind(attachment: &Attachment) -> u8 {
+	match attachment {
+		Attachment::DataSet => 0,
+		Attachment::DimensionGroup(_) => 1,
+		Attachment::Series => 2,
+		Attachment::Observation => 3,
+	}
+}
+
+/// Sets a value in a list of values, filling any gap with `null`.
+fn set(values: &mut Vec<SdmxValue>, position: usize, value: SdmxValue) {
+	if values.len() <= position {
+		values.resize(position + 1, SdmxValue::Null);
+	}
+	values[position] = value;
+}
+
+fn join_key(key: &[usize]) -> String {
+	key.iter()
+		.map(usize::to_string)
+		.collect::<Vec<_>>()
+		.join(":")
+}
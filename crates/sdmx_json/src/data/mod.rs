@@ -0,0 +1,20 @@
+mod all;
+mod annotations;
+mod attributes;
+mod builder;
+mod observations;
+mod rows;
+mod store;
+mod stream;
+mod typed;
+mod validation;
+pub use all::*;
+pub use annotations::*;
+pub use attributes::*;
+pub use builder::*;
+pub use observations::*;
+pub use rows::*;
+pub use store::*;
+pub use stream::*;
+pub use typed::*;
+pub use validation::*;
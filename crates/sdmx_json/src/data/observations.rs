@@ -0,0 +1,304 @@
+use crate::data::{
+	AttributeValue, Component, ComponentValue, Data, DataSet, Level, Series, Structure,
+};
+use crate::primitives::SdmxValue;
+use std::collections::{hash_map, HashMap};
+use std::error::Error;
+use std::fmt;
+
+impl Data {
+	/// Decodes the observations of a dataset, according to
+	/// the structure it refers to in this data message.
+	///
+	/// Returns an error if the structure does not exist, or if the values
+	/// of the dimensions at the dataset level cannot be decoded.
+	///
+	/// # Example
+	/// ```no_run
+	/// use std::str::FromStr;
+	/// use std::fs::read_to_string;
+	/// use sdmx_json::data::DataMessage;
+	///
+	/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+	///     let file = read_to_string("sdmx-data.json")?;
+	///     let message = DataMessage::from_str(file.as_str())?;
+	///     let data = message.data.unwrap_or_default();
+	///     for data_set in data.data_sets.iter().flatten() {
+	///         for observation in data.observations(data_set)? {
+	///             let observation = observation?;
+	///             let time = &observation.dimensions["TIME_PERIOD"].id;
+	///             println!("{}: {:?}", time, observation.measures.get("OBS_VALUE"));
+	///         }
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn observations<'d>(
+		&'d self,
+		data_set: &'d DataSet,
+	) -> Result<Observations<'d>, DecodeError> {
+		Observations::new(self, data_set)
+	}
+}
+
+/// An iterator over the decoded observations of a dataset,
+/// in no particular order.
+///
+/// Observations are read from the series of the dataset (when the
+/// observation keys only cover the observation dimensions), and from
+/// the observations of the dataset itself (when the observation keys
+/// cover both the series and observation dimensions).
+#[derive(Debug, Clone)]
+pub struct Observations<'d> {
+	decoder: Decoder<'d>,
+	series: Option<hash_map::Iter<'d, String, Series>>,
+	/// The current series, with the values of its dimensions.
+	current: Option<(&'d str, Vec<DimensionValue<'d>>, ObservationsIter<'d>)>,
+	flat: Option<ObservationsIter<'d>>,
+}
+
+/// The components needed to decode the observations of a dataset.
+#[derive(Debug, Clone)]
+struct Decoder<'d> {
+	/// The values of the dimensions at the dataset level,
+	/// which are the same for every observation.
+	data_set_dimensions: Vec<DimensionValue<'d>>,
+	series_dimensions: Vec<&'d Component>,
+	observation_dimensions: Vec<&'d Component>,
+	/// Both the series and the observation dimensions.
+	flat_dimensions: Vec<&'d Component>,
+	measures: &'d [Component],
+	structure: &'d Structure,
+}
+
+pub(crate) type DimensionValue<'d> = (&'d str, &'d ComponentValue);
+type ObservationsIter<'d> = hash_map::Iter<'d, String, Vec<SdmxValue>>;
+
+/// An observation of a dataset, with the values of
+/// its components indexed by their ID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation<'d> {
+	/// The key of the series this observation belongs
+	/// to, unless the observations are not in series.
+	pub series_key: Option<&'d str>,
+	/// The key of this observation, as it appears in the message.
+	pub key: &'d str,
+	/// The values of all dimensions, including those
+	/// at the dataset and series levels.
+	pub dimensions: HashMap<&'d str, &'d ComponentValue>,
+	/// The values of the measures. A measure without
+	/// a value is omitted.
+	pub measures: HashMap<&'d str, &'d SdmxValue>,
+	/// The values of the attributes at the observation level.
+	/// An attribute without a value is omitted.
+	pub attributes: HashMap<&'d str, AttributeValue<'d>>,
+}
+
+/// An error which can occur when decoding the
+/// observations of a dataset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+	/// A dataset refers to a structure which is not in the message.
+	MissingStructure(isize),
+	/// A series or observation key does not have as many
+	/// indices as there are dimensions to decode.
+	InvalidKey(String),
+	/// An index is out of range for the values of its component.
+	InvalidValueIndex { component: String, index: isize },
+	/// An index is out of range for the annotations of the structure.
+	MissingAnnotation(usize),
+}
+
+impl DecodeError {
+	pub(crate) fn invalid_index(component: &Component, index: isize) -> Self {
+		Self::InvalidValueIndex {
+			component: component.id.clone(),
+			index,
+		}
+	}
+}
+
+impl Error for DecodeError {}
+
+impl fmt::Display for DecodeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::MissingStructure(index) => write!(f, "missing structure at index {}", index),
+			Self::InvalidKey(key) => write!(f, "invalid key: {}", key),
+			Self::MissingAnnotation(index) => write!(f, "missing annotation at index {}", index),
+			Self::InvalidValueIndex { component, index } => {
+				write!(
+					f,
+					"invalid value index {} for component {}",
+					index, component
+				)
+			}
+		}
+	}
+}
+
+impl<'d> Observations<'d> {
+	fn new(data: &'d Data, data_set: &'d DataSet) -> Result<Self, DecodeError> {
+		let structure = structure_of(data, data_set)?;
+		let dimensions = &structure.dimensions;
+		let data_set_dimensions = components(&dimensions.data_set)
+			.iter()
+			.map(|dimension| Ok((dimension.id.as_str(), dimension_value(dimension, 0)?)))
+			.collect::<Result<_, DecodeError>>()?;
+		let series_dimensions: Vec<_> = components(&dimensions.series).iter().collect();
+		let observation_dimensions: Vec<_> = components(&dimensions.observation).iter().collect();
+		let flat_dimensions = series_dimensions
+			.iter()
+			.chain(&observation_dimensions)
+			.copied()
+			.collect();
+
+		let decoder = Decoder {
+			data_set_dimensions,
+			series_dimensions,
+			observation_dimensions,
+			flat_dimensions,
+			measures: structure
+				.measures
+				.as_ref()
+				.map(|m| components(&m.observation))
+				.unwrap_or_default(),
+			structure,
+		};
+		Ok(Self {
+			decoder,
+			series: data_set.series.as_ref().map(|series| series.iter()),
+			current: None,
+			flat: data_set.observations.as_ref().map(|o| o.iter()),
+		})
+	}
+}
+
+impl<'d> Decoder<'d> {
+	/// Decodes an observation, given the values of the dimensions of its
+	/// series (if any), and the dimensions which are part of its key.
+	fn decode(
+		&self,
+		series: Option<(&'d str, &[DimensionValue<'d>])>,
+		key: &'d str,
+		dimensions: &[&'d Component],
+		values: &'d [SdmxValue],
+	) -> Result<Observation<'d>, DecodeError> {
+		let (series_key, inherited) = series.unzip();
+		let mut observation = Observation {
+			series_key,
+			key,
+			dimensions: self
+				.data_set_dimensions
+				.iter()
+				.chain(inherited.unwrap_or_default())
+				.copied()
+				.collect(),
+			measures: HashMap::new(),
+			attributes: HashMap::new(),
+		};
+		observation.dimensions.extend(decode_key(key, dimensions)?);
+
+		for (measure, value) in self.measures.iter().zip(values) {
+			if *value != SdmxValue::Null {
+				observation.measures.insert(&measure.id, value);
+			}
+		}
+
+		for attribute in self
+			.structure
+			.resolve_attributes(Level::Observation, values)
+		{
+			let (id, value) = attribute?;
+			observation.attributes.insert(id, value);
+		}
+		Ok(observation)
+	}
+}
+
+impl<'d> Iterator for Observations<'d> {
+	type Item = Result<Observation<'d>, DecodeError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let decoder = &self.decoder;
+		loop {
+			if let Some((series_key, inherited, observations)) = &mut self.current {
+				if let Some((key, values)) = observations.next() {
+					return Some(decoder.decode(
+						Some((series_key, inherited)),
+						key,
+						&decoder.observation_dimensions,
+						values,
+					));
+				}
+				self.current = None;
+			}
+
+			if let Some((series_key, series)) = self.series.as_mut().and_then(Iterator::next) {
+				let inherited = match decode_key(series_key, &decoder.series_dimensions) {
+					Ok(inherited) => inherited,
+					Err(error) => return Some(Err(error)),
+				};
+				if let Some(observations) = &series.observations {
+					self.current = Some((series_key, inherited, observations.iter()));
+				}
+				continue;
+			}
+
+			let (key, values) = self.flat.as_mut()?.next()?;
+			return Some(decoder.decode(None, key, &decoder.flat_dimensions, values));
+		}
+	}
+}
+
+/// The structure a dataset refers to, in the structures of a data message.
+pub(crate) fn structure_of<'d>(
+	data: &'d Data,
+	data_set: &DataSet,
+) -> Result<&'d Structure, DecodeError> {
+	let index = data_set.structure.unwrap_or_default();
+	usize::try_from(index)
+		.ok()
+		.and_then(|i| data.structures.as_ref()?.get(i))
+		.ok_or(DecodeError::MissingStructure(index))
+}
+
+pub(crate) fn components(components: &Option<Vec<Component>>) -> &[Component] {
+	components.as_deref().unwrap_or_default()
+}
+
+/// Decodes a key made of indices separated by `:`, into
+/// the values of the dimensions in the same order.
+pub(crate) fn decode_key<'d>(
+	key: &str,
+	dimensions: &[&'d Component],
+) -> Result<Vec<DimensionValue<'d>>, DecodeError> {
+	let invalid_key = || DecodeError::InvalidKey(key.to_owned());
+	let indices: Vec<&str> = match key {
+		"" => Vec::new(),
+		key => key.split(':').collect(),
+	};
+	if indices.len() != dimensions.len() {
+		return Err(invalid_key());
+	}
+
+	dimensions
+		.iter()
+		.zip(indices)
+		.map(|(dimension, index)| {
+			let index = index.parse().map_err(|_| invalid_key())?;
+			Ok((dimension.id.as_str(), dimension_value(dimension, index)?))
+		})
+		.collect()
+}
+
+pub(crate) fn dimension_value(
+	dimension: &Component,
+	index: usize,
+) -> Result<&ComponentValue, DecodeError> {
+	dimension
+		.values
+		.as_ref()
+		.and_then(|values| values.get(index)?.as_ref())
+		.ok_or_else(|| DecodeError::invalid_index(dimension, index as isize))
+}
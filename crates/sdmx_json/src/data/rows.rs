@@ -0,0 +1,331 @@
+use crate::data::{
+	structure_of, AttributeValue, Component, ComponentValue, Data, DataMessage, DataSet,
+	DecodeError, Level, Observation, Observations, Structure,
+};
+use crate::primitives::{LocalizedText, SdmxValue};
+use std::collections::HashMap;
+use std::{iter, slice};
+
+impl DataMessage {
+	/// Flattens the datasets of this message into rows, with one row per
+	/// observation and one cell per dimension, measure and attribute.
+	///
+	/// Rows are decoded lazily, one dataset after the other. The values of
+	/// the attributes at the dataset, dimension group and series levels are
+	/// inherited by every observation they are attached to.
+	///
+	/// # Example
+	/// ```no_run
+	/// use std::str::FromStr;
+	/// use std::fs::read_to_string;
+	/// use sdmx_json::data::{CellValue, DataMessage, Labels};
+	///
+	/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+	///     let file = read_to_string("sdmx-data.json")?;
+	///     let message = DataMessage::from_str(file.as_str())?;
+	///     for row in message.rows(Labels::Name("en")) {
+	///         let row = row?;
+	///         let cells: Vec<_> = row
+	///             .cells
+	///             .iter()
+	///             .map(|cell| match cell.value {
+	///                 Some(CellValue::Text(text)) => text.to_owned(),
+	///                 Some(CellValue::Value(value)) => format!("{:?}", value),
+	///                 None => String::new(),
+	///             })
+	///             .collect();
+	///         println!("{}", cells.join(","));
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn rows<'d>(&'d self, labels: Labels<'d>) -> Rows<'d> {
+		let data = self.data.as_ref();
+		let data_sets = data.and_then(|data| data.data_sets.as_deref());
+		Rows {
+			data,
+			labels,
+			data_sets: data_sets.unwrap_or_default().iter().enumerate(),
+			current: None,
+		}
+	}
+}
+
+/// How the columns and the coded values of rows are labelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Labels<'l> {
+	/// The ID of the components and of their values.
+	#[default]
+	Id,
+	/// The name of the components and of their values in a language,
+	/// falling back to their default name, and then to their ID.
+	/// Localized literal values are also read in that language.
+	Name(&'l str),
+}
+
+impl Labels<'_> {
+	fn label<'d>(
+		&self,
+		id: &'d str,
+		name: &'d Option<String>,
+		names: &'d Option<LocalizedText>,
+	) -> &'d str {
+		let Self::Name(language) = self else {
+			return id;
+		};
+		names
+			.as_ref()
+			.and_then(|names| names.get(*language))
+			.or(name.as_ref())
+			.map_or(id, String::as_str)
+	}
+
+	fn component<'d>(&self, component: &'d Component) -> &'d str {
+		self.label(&component.id, &component.name, &component.names)
+	}
+
+	fn value<'d>(&self, value: &'d ComponentValue) -> CellValue<'d> {
+		CellValue::Text(self.label(&value.id, &value.name, &value.names))
+	}
+
+	fn attribute<'d>(&self, value: AttributeValue<'d>) -> CellValue<'d> {
+		match value {
+			AttributeValue::Coded(value) => self.value(value),
+			AttributeValue::Literal(literal) => {
+				let text = match (self, literal) {
+					(Self::Name(language), SdmxValue::LocalizedText(texts)) => texts.get(*language),
+					_ => None,
+				};
+				text.map_or(CellValue::Value(literal), |text| CellValue::Text(text))
+			}
+		}
+	}
+}
+
+/// An iterator over the rows of every dataset of a data message.
+/// The rows of a dataset are in no particular order.
+#[derive(Debug, Clone)]
+pub struct Rows<'d> {
+	data: Option<&'d Data>,
+	labels: Labels<'d>,
+	data_sets: iter::Enumerate<slice::Iter<'d, DataSet>>,
+	current: Option<DataSetRows<'d>>,
+}
+
+/// The rows of the dataset being decoded, with the values of
+/// the attributes which are shared by multiple observations.
+#[derive(Debug, Clone)]
+pub(crate) struct DataSetRows<'d> {
+	index: usize,
+	data_set: &'d DataSet,
+	structure: &'d Structure,
+	observations: Observations<'d>,
+	attributes: Vec<(&'d str, AttributeValue<'d>)>,
+	/// The attributes of the current series, with its key.
+	series: Option<(&'d str, Vec<(&'d str, AttributeValue<'d>)>)>,
+}
+
+/// An observation of a dataset, flattened into a row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row<'d> {
+	/// The index of the dataset of this row in the message.
+	pub data_set: usize,
+	/// A cell for each dimension, measure and attribute of the
+	/// structure of the dataset, in that order. Components are
+	/// ordered by level, from the dataset to the observation.
+	pub cells: Vec<Cell<'d>>,
+}
+
+impl<'d> Row<'d> {
+	/// The value of the cell in a column, if any.
+	pub fn get(&self, column: &str) -> Option<&CellValue<'d>> {
+		let cell = self.cells.iter().find(|cell| cell.column == column)?;
+		cell.value.as_ref()
+	}
+}
+
+/// The value of a component in a row.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell<'d> {
+	/// The label of the component.
+	pub column: &'d str,
+	/// The value of the component, unless it has none.
+	pub value: Option<CellValue<'d>>,
+}
+
+/// The value of a cell, which is either a label
+/// (of a coded value, or of a localized text) or a literal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellValue<'d> {
+	Text(&'d str),
+	Value(&'d SdmxValue),
+}
+
+impl<'d> Iterator for Rows<'d> {
+	type Item = Result<Row<'d>, DecodeError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(current) = &mut self.current {
+				match current.next(self.labels) {
+					Some(row) => return Some(row),
+					None => self.current = None,
+				}
+			}
+
+			let (index, data_set) = self.data_sets.next()?;
+			match DataSetRows::new(self.data?, index, data_set) {
+				Ok(rows) => self.current = Some(rows),
+				Err(error) => return Some(Err(error)),
+			}
+		}
+	}
+}
+
+impl<'d> DataSetRows<'d> {
+	pub(crate) fn new(
+		data: &'d Data,
+		index: usize,
+		data_set: &'d DataSet,
+	) -> Result<Self, DecodeError> {
+		let structure = structure_of(data, data_set)?;
+		let values = data_set.attributes.as_deref().unwrap_or_default();
+		Ok(Self {
+			index,
+			data_set,
+			structure,
+			observations: data.observations(data_set)?,
+			attributes: structure
+				.resolve_attributes(Level::DataSet, values)
+				.collect::<Result<_, _>>()?,
+			series: None,
+		})
+	}
+
+	pub(crate) fn next(&mut self, labels: Labels<'_>) -> Option<Result<Row<'d>, DecodeError>> {
+		match self.observations.next()? {
+			Ok(observation) => Some(self.row(observation, labels)),
+			Err(error) => Some(Err(error)),
+		}
+	}
+
+	fn row(
+		&mut self,
+		mut observation: Observation<'d>,
+		labels: Labels<'_>,
+	) -> Result<Row<'d>, DecodeError> {
+		let structure = self.structure;
+		let mut attributes: HashMap<_, _> = self.attributes.iter().copied().collect();
+		for values in self.group_values(&observation) {
+			for attribute in structure.resolve_attributes(Level::DimensionGroup, values) {
+				let (id, value) = attribute?;
+				attributes.insert(id, value);
+			}
+		}
+		if let Some(series_key) = observation.series_key {
+			attributes.extend(self.series_attributes(series_key)?.iter().copied());
+		}
+		attributes.extend(observation.attributes.drain());
+
+		let levels = [Level::DataSet, Level::Series, Level::Observation];
+		let dimensions = levels
+			.iter()
+			.flat_map(|level| structure.dimensions.components(*level))
+			.map(|dimension| Cell {
+				column: labels.component(dimension),
+				value: observation
+					.dimensions
+					.get(dimension.id.as_str())
+					.map(|value| labels.value(value)),
+			});
+
+		let measures = structure.measures.as_ref();
+		let measures = measures.map(|m| m.components(Level::Observation));
+		let measures = measures.unwrap_or_default().iter().map(|measure| Cell {
+			column: labels.component(measure),
+			value: observation
+				.measures
+				.get(measure.id.as_str())
+				.map(|value| CellValue::Value(value)),
+		});
+
+		let levels = [
+			Level::DataSet,
+			Level::DimensionGroup,
+			Level::Series,
+			Level::Observation,
+		];
+		let attributes = levels
+			.iter()
+			.flat_map(|level| structure.attributes.components(*level))
+			.map(|attribute| Cell {
+				column: labels.component(attribute),
+				value: attributes
+					.get(attribute.id.as_str())
+					.map(|value| labels.attribute(*value)),
+			});
+
+		Ok(Row {
+			data_set: self.index,
+			cells: dimensions.chain(measures).chain(attributes).collect(),
+		})
+	}
+
+	/// The attributes of a series, which are resolved once
+	/// for all the observations of that series.
+	fn series_attributes(
+		&mut self,
+		key: &'d str,
+	) -> Result<&[(&'d str, AttributeValue<'d>)], DecodeError> {
+		if self
+			.series
+			.as_ref()
+			.map_or(true, |(current, _)| *current != key)
+		{
+			let series = self.data_set.series.as_ref().and_then(|s| s.get(key));
+			let values = series.and_then(|s| s.attributes.as_deref());
+			let attributes = self
+				.structure
+				.resolve_attributes(Level::Series, values.unwrap_or_default())
+				.collect::<Result<_, _>>()?;
+			self.series = Some((key, attributes));
+		}
+		Ok(self
+			.series
+			.as_ref()
+			.map(|(_, a)| a.as_slice())
+			.unwrap_or_default())
+	}
+
+	/// The values of the attributes of every dimension group an observation
+	/// belongs to. The key of a group has an index for each dimension of
+	/// the dataset (at every level), which is empty if the dimension is
+	/// not part of the group.
+	fn group_values(&self, observation: &Observation<'d>) -> Vec<&'d [SdmxValue]> {
+		let Some(groups) = &self.data_set.dimension_group_attributes else {
+			return Vec::new();
+		};
+		let data_set_dimensions = self.structure.dimensions.components(Level::DataSet);
+		let keys = observation.series_key.into_iter().chain([observation.key]);
+		let indices: Vec<&str> = iter::repeat("0")
+			.take(data_set_dimensions.len())
+			.chain(
+				keys.filter(|key| !key.is_empty())
+					.flat_map(|key| key.split(':')),
+			)
+			.collect();
+
+		groups
+			.iter()
+			.filter(|(key, _)| {
+				let group: Vec<&str> = key.split(':').collect();
+				group.len() == indices.len()
+					&& group
+						.iter()
+						.zip(&indices)
+						.all(|(group, index)| group.is_empty() || group == index)
+			})
+			.map(|(_, values)| values.as_slice())
+			.collect()
+	}
+}
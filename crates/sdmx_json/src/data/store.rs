@@ -0,0 +1,390 @@
+use crate::data::{
+	components, decode_key, dimension_value, structure_of, AttributeValue, DataMessage, DataSet,
+	DecodeError, DimensionValue, Level, Structure,
+};
+use crate::primitives::{Action, SdmxValue};
+use std::collections::{BTreeMap, HashMap};
+
+/// The key of a series, made of the IDs of the values of its dimensions
+/// (i.e. every dimension but the time dimension), indexed by their ID.
+pub type SeriesKey = BTreeMap<String, String>;
+
+/// An in-memory store of observations, keyed by series key and time period,
+/// which is kept up to date by applying successive data messages according
+/// to the action of their datasets.
+///
+/// Values are stored by the ID of their component. The values of coded
+/// attributes are stored as the ID of their value (as a string), so that
+/// messages with different structures (or differently ordered values) can
+/// update the same series.
+///
+/// # Example
+/// ```no_run
+/// use std::str::FromStr;
+/// use std::fs::read_to_string;
+/// use sdmx_json::data::{DataMessage, DataStore};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+///     let mut store = DataStore::new();
+///     for path in ["initial.json", "update-1.json", "update-2.json"] {
+///         let file = read_to_string(path)?;
+///         store.apply(&DataMessage::from_str(file.as_str())?)?;
+///     }
+///     for (key, series) in store.series() {
+///         println!("{:?}: {} observations", key, series.observations.len());
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataStore {
+	time_dimension: String,
+	attributes: HashMap<String, SdmxValue>,
+	groups: BTreeMap<SeriesKey, HashMap<String, SdmxValue>>,
+	series: BTreeMap<SeriesKey, StoredSeries>,
+}
+
+/// A series of a [`DataStore`], with the values of
+/// its attributes and its observations.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StoredSeries {
+	pub attributes: HashMap<String, SdmxValue>,
+	/// The observations of the series, keyed by time period.
+	pub observations: BTreeMap<String, StoredObservation>,
+}
+
+/// An observation of a [`DataStore`], with the values
+/// of its measures and attributes.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StoredObservation {
+	pub measures: HashMap<String, SdmxValue>,
+	pub attributes: HashMap<String, SdmxValue>,
+}
+
+/// A change to a part of a store, decoded from a dataset.
+struct Update {
+	target: Target,
+	measures: HashMap<String, SdmxValue>,
+	attributes: HashMap<String, SdmxValue>,
+}
+
+enum Target {
+	DataSet,
+	Group(SeriesKey),
+	Series(SeriesKey),
+	Observation(SeriesKey, String),
+}
+
+impl Default for DataStore {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl DataStore {
+	/// Creates an empty store, whose time dimension is `TIME_PERIOD`.
+	pub fn new() -> Self {
+		Self::with_time_dimension("TIME_PERIOD")
+	}
+
+	/// Creates an empty store, with the ID of the dimension whose
+	/// values are the time periods of the observations.
+	pub fn with_time_dimension(id: impl Into<String>) -> Self {
+		Self {
+			time_dimension: id.into(),
+			attributes: HashMap::new(),
+			groups: BTreeMap::new(),
+			series: BTreeMap::new(),
+		}
+	}
+
+	/// Applies the datasets of a message to this store, in order,
+	/// according to their action:
+	///
+	/// - `Append` adds the values which are not in the store yet,
+	///   without replacing any existing value.
+	/// - `Replace` replaces the values of the attributes it gives, and
+	///   every observation it gives as a whole (adding what is missing).
+	/// - `Delete` deletes the lowest level of information it gives: an
+	///   observation it gives with a measure value, or only with its key,
+	///   is deleted as a whole. Otherwise, the values of the attributes it
+	///   gives are deleted (whatever their value), or else a whole series
+	///   or dimension group when only its key is given (e.g. a series
+	///   without attributes, whose observations are missing or empty).
+	///   An observation left without values is deleted too. A dataset
+	///   with nothing in it deletes nothing.
+	/// - `Information` (the default, e.g. of the response to a query)
+	///   is applied as `Replace`, so that a store can be seeded from it.
+	///
+	/// A message is either applied as a whole, or not at all if one of
+	/// its datasets cannot be decoded.
+	pub fn apply(&mut self, message: &DataMessage) -> Result<(), DecodeError> {
+		let Some(data) = &message.data else {
+			return Ok(());
+		};
+
+		let mut updates = Vec::new();
+		for data_set in data.data_sets.iter().flatten() {
+			let structure = structure_of(data, data_set)?;
+			let action = data_set.action.unwrap_or_default();
+			for update in self.decode(structure, data_set)? {
+				updates.push((action, update));
+			}
+		}
+		for (action, update) in updates {
+			self.update(action, update);
+		}
+		Ok(())
+	}
+
+	/// The values of the attributes at the dataset level.
+	pub fn attributes(&self) -> &HashMap<String, SdmxValue> {
+		&self.attributes
+	}
+
+	/// The values of the attributes of dimension groups, keyed by the
+	/// values of the dimensions which are part of the group.
+	pub fn groups(&self) -> &BTreeMap<SeriesKey, HashMap<String, SdmxValue>> {
+		&self.groups
+	}
+
+	/// The series of this store, ordered by key.
+	pub fn series(&self) -> &BTreeMap<SeriesKey, StoredSeries> {
+		&self.series
+	}
+
+	/// The observation of a series at a time period, if any.
+	pub fn observation(&self, key: &SeriesKey, time_period: &str) -> Option<&StoredObservation> {
+		self.series.get(key)?.observations.get(time_period)
+	}
+
+	/// Decodes the changes that a dataset makes, from the dataset level
+	/// down to the observation level.
+	fn decode(
+		&self,
+		structure: &Structure,
+		data_set: &DataSet,
+	) -> Result<Vec<Update>, DecodeError> {
+		let dimensions = &structure.dimensions;
+		let data_set_dimensions = components(&dimensions.data_set)
+			.iter()
+			.map(|dimension| Ok((dimension.id.as_str(), dimension_value(dimension, 0)?)))
+			.collect::<Result<Vec<_>, DecodeError>>()?;
+		let series_dimensions: Vec<_> = components(&dimensions.series).iter().collect();
+		let observation_dimensions: Vec<_> = components(&dimensions.observation).iter().collect();
+		let flat_dimensions: Vec<_> = series_dimensions
+			.iter()
+			.chain(&observation_dimensions)
+			.copied()
+			.collect();
+
+		let mut updates = Vec::new();
+		let values = data_set.attributes.as_deref().unwrap_or_default();
+		let attributes = resolve_values(structure, Level::DataSet, values)?;
+		if !attributes.is_empty() {
+			updates.push(Update::new(Target::DataSet, attributes));
+		}
+
+		let all_dimensions: Vec<_> = components(&dimensions.data_set)
+			.iter()
+			.chain(flat_dimensions.iter().copied())
+			.collect();
+		for (key, values) in data_set.dimension_group_attributes.iter().flatten() {
+			let indices = key.split(':');
+			let mut group = SeriesKey::new();
+			for (dimension, index) in all_dimensions.iter().zip(indices) {
+				if !index.is_empty() {
+					let index = index
+						.parse()
+						.map_err(|_| DecodeError::InvalidKey(key.clone()))?;
+					let value = dimension_value(dimension, index)?;
+					group.insert(dimension.id.clone(), value.id.clone());
+				}
+			}
+			let attributes = resolve_values(structure, Level::DimensionGroup, values)?;
+			updates.push(Update::new(Target::Group(group), attributes));
+		}
+
+		for (key, series) in data_set.series.iter().flatten() {
+			let mut inherited = data_set_dimensions.clone();
+			inherited.extend(decode_key(key, &series_dimensions)?);
+			let values = series.attributes.as_deref().unwrap_or_default();
+			let attributes = resolve_values(structure, Level::Series, values)?;
+			let observations = series.observations.as_ref();
+			if !attributes.is_empty() || observations.map_or(true, HashMap::is_empty) {
+				let (series_key, _) = self.split(inherited.clone());
+				updates.push(Update::new(Target::Series(series_key), attributes));
+			}
+			for (key, values) in series.observations.iter().flatten() {
+				let mut dimensions = inherited.clone();
+				dimensions.extend(decode_key(key, &observation_dimensions)?);
+				updates.push(self.decode_observation(structure, dimensions, values)?);
+			}
+		}
+
+		for (key, values) in data_set.observations.iter().flatten() {
+			let mut dimensions = data_set_dimensions.clone();
+			dimensions.extend(decode_key(key, &flat_dimensions)?);
+			updates.push(self.decode_observation(structure, dimensions, values)?);
+		}
+		Ok(updates)
+	}
+
+	fn decode_observation(
+		&self,
+		structure: &Structure,
+		dimensions: Vec<DimensionValue<'_>>,
+		values: &[SdmxValue],
+	) -> Result<Update, DecodeError> {
+		let (series_key, time_period) = self.split(dimensions);
+		let measures = structure.measures.as_ref();
+		let measures = measures.map(|m| m.components(Level::Observation));
+		let mut update = Update::new(
+			Target::Observation(series_key, time_period),
+			resolve_values(structure, Level::Observation, values)?,
+		);
+		update.measures = measures
+			.unwrap_or_default()
+			.iter()
+			.zip(values)
+			.filter(|(_, value)| **value != SdmxValue::Null)
+			.map(|(measure, value)| (measure.id.clone(), value.clone()))
+			.collect();
+		Ok(update)
+	}
+
+	/// Splits the values of the dimensions of an observation into
+	/// its series key and its time period (empty if it has none).
+	fn split(&self, dimensions: Vec<DimensionValue<'_>>) -> (SeriesKey, String) {
+		let mut time_period = String::new();
+		let mut key = SeriesKey::new();
+		for (id, value) in dimensions {
+			if id == self.time_dimension {
+				time_period.clone_from(&value.id);
+			} else {
+				key.insert(id.to_owned(), value.id.clone());
+			}
+		}
+		(key, time_period)
+	}
+
+	fn update(&mut self, action: Action, update: Update) {
+		let Update {
+			target,
+			measures,
+			attributes,
+		} = update;
+
+		if action == Action::Delete {
+			let whole = measures.is_empty() && attributes.is_empty();
+			match target {
+				Target::DataSet => remove(&mut self.attributes, &attributes),
+				Target::Group(key) if whole => {
+					self.groups.remove(&key);
+				}
+				Target::Group(key) => {
+					if let Some(group) = self.groups.get_mut(&key) {
+						remove(group, &attributes);
+					}
+				}
+				Target::Series(key) if whole => {
+					self.series.remove(&key);
+				}
+				Target::Series(key) => {
+					if let Some(series) = self.series.get_mut(&key) {
+						remove(&mut series.attributes, &attributes);
+					}
+				}
+				Target::Observation(key, time_period) => {
+					let series = self.series.get_mut(&key);
+					let observations = series.map(|s| &mut s.observations);
+					let Some(observations) = observations else {
+						return;
+					};
+					// Only attributes are deleted from an observation
+					// when no measure is given.
+					if measures.is_empty() && !attributes.is_empty() {
+						let Some(observation) = observations.get_mut(&time_period) else {
+							return;
+						};
+						remove(&mut observation.attributes, &attributes);
+						if !observation.measures.is_empty() || !observation.attributes.is_empty() {
+							return;
+						}
+					}
+					observations.remove(&time_period);
+				}
+			}
+			return;
+		}
+
+		let replace = action != Action::Append;
+		match target {
+			Target::DataSet => merge(&mut self.attributes, attributes, replace),
+			Target::Group(key) => merge(self.groups.entry(key).or_default(), attributes, replace),
+			Target::Series(key) => {
+				let series = self.series.entry(key).or_default();
+				merge(&mut series.attributes, attributes, replace);
+			}
+			Target::Observation(key, time_period) => {
+				let series = self.series.entry(key).or_default();
+				let observation = series.observations.entry(time_period).or_default();
+				if replace {
+					*observation = StoredObservation {
+						measures,
+						attributes,
+					};
+				} else {
+					merge(&mut observation.measures, measures, false);
+					merge(&mut observation.attributes, attributes, false);
+				}
+			}
+		}
+	}
+}
+
+impl Update {
+	fn new(target: Target, attributes: HashMap<String, SdmxValue>) -> Self {
+		Self {
+			target,
+			measures: HashMap::new(),
+			attributes,
+		}
+	}
+}
+
+/// Resolves the values of the attributes at a level,
+/// where coded values are replaced by their ID.
+fn resolve_values(
+	structure: &Structure,
+	level: Level,
+	values: &[SdmxValue],
+) -> Result<HashMap<String, SdmxValue>, DecodeError> {
+	structure
+		.resolve_attributes(level, values)
+		.map(|attribute| {
+			let (id, value) = attribute?;
+			let value = match value {
+				AttributeValue::Coded(value) => SdmxValue::String(value.id.clone()),
+				AttributeValue::Literal(value) => value.clone(),
+			};
+			Ok((id.to_owned(), value))
+		})
+		.collect()
+}
+
+fn merge(
+	stored: &mut HashMap<String, SdmxValue>,
+	values: HashMap<String, SdmxValue>,
+	replace: bool,
+) {
+	for (id, value) in values {
+		if replace || !stored.contains_key(&id) {
+			stored.insert(id, value);
+		}
+	}
+}
+
+fn remove(stored: &mut HashMap<String, SdmxValue>, values: &HashMap<String, SdmxValue>) {
+	stored.retain(|id, _| !values.contains_key(id));
+}
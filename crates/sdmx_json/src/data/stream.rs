@@ -0,0 +1,407 @@
+use crate::data::{Data, DataMessage, DataSet, Series, Structure};
+use crate::primitives::{Meta, SdmxValue};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::io::Read;
+
+/// Receives the parts of a data message while it is being streamed
+/// with [`DataMessage::stream_from_reader`].
+///
+/// Returning an error from any method stops the streaming,
+/// and the error is reported as a deserialization error.
+pub trait DataHandler {
+	type Error: fmt::Display;
+
+	/// Called with the metadata of the message, once it is parsed.
+	fn meta(&mut self, meta: &Meta) -> Result<(), Self::Error> {
+		let _ = meta;
+		Ok(())
+	}
+
+	/// Called with the structures of the message, once they are parsed.
+	fn structures(&mut self, structures: &[Structure]) -> Result<(), Self::Error> {
+		let _ = structures;
+		Ok(())
+	}
+
+	/// Called for each series of a dataset (by its index in the message),
+	/// after all of its observations, and without them.
+	fn series(&mut self, data_set: usize, key: &str, series: Series) -> Result<(), Self::Error> {
+		let _ = (data_set, key, series);
+		Ok(())
+	}
+
+	/// Called for each observation of a dataset (by its index in
+	/// the message), along with the key of its series, if any.
+	fn observation(
+		&mut self,
+		data_set: usize,
+		series_key: Option<&str>,
+		key: &str,
+		values: Vec<SdmxValue>,
+	) -> Result<(), Self::Error>;
+}
+
+impl DataMessage {
+	/// Deserializes a data message from a reader, while handing out its
+	/// series and observations one at a time, so that they never have to
+	/// be all held in memory at once.
+	///
+	/// The metadata and the structures of the message are handed out as
+	/// soon as they are parsed, which is before any dataset if they come
+	/// first in the JSON file (as they usually do).
+	///
+	/// Returns the rest of the message, where datasets have
+	/// neither series nor observations. Since the reader is read
+	/// byte by byte, it should be buffered (e.g. with a
+	/// [`BufReader`][std::io::BufReader]).
+	///
+	/// # Example
+	/// ```no_run
+	/// use std::fs::File;
+	/// use std::io::BufReader;
+	/// use std::convert::Infallible;
+	/// use sdmx_json::data::{DataHandler, DataMessage};
+	/// use sdmx_json::primitives::SdmxValue;
+	///
+	/// struct Counter(usize);
+	///
+	/// impl DataHandler for Counter {
+	///     type Error = Infallible;
+	///     fn observation(
+	///         &mut self,
+	///         _data_set: usize,
+	///         _series_key: Option<&str>,
+	///         _key: &str,
+	///         _values: Vec<SdmxValue>,
+	///     ) -> Result<(), Self::Error> {
+	///         self.0 += 1;
+	///         Ok(())
+	///     }
+	/// }
+	///
+	/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+	///     let reader = BufReader::new(File::open("sdmx-data.json")?);
+	///     let mut counter = Counter(0);
+	///     DataMessage::stream_from_reader(reader, &mut counter)?;
+	///     println!("{} observations", counter.0);
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn stream_from_reader<R, H>(reader: R, handler: &mut H) -> Result<Self, serde_json::Error>
+	where
+		R: Read,
+		H: DataHandler,
+	{
+		let mut deserializer = serde_json::Deserializer::from_reader(reader);
+		let message = MessageSeed { handler }.deserialize(&mut deserializer)?;
+		deserializer.end()?;
+		Ok(message)
+	}
+}
+
+struct MessageSeed<'h, H> {
+	handler: &'h mut H,
+}
+
+struct DataSeed<'h, H> {
+	handler: &'h mut H,
+}
+
+struct DataSetsSeed<'h, H> {
+	handler: &'h mut H,
+}
+
+struct DataSetSeed<'h, H> {
+	handler: &'h mut H,
+	index: usize,
+}
+
+struct SeriesMapSeed<'h, H> {
+	handler: &'h mut H,
+	data_set: usize,
+}
+
+struct SeriesSeed<'h, 'k, H> {
+	handler: &'h mut H,
+	data_set: usize,
+	key: &'k str,
+}
+
+struct ObservationsSeed<'h, 'k, H> {
+	handler: &'h mut H,
+	data_set: usize,
+	series_key: Option<&'k str>,
+}
+
+/// Converts the fields which are not streamed into their type.
+fn from_fields<T, E>(fields: Map<String, Value>) -> Result<T, E>
+where
+	T: de::DeserializeOwned,
+	E: de::Error,
+{
+	serde_json::from_value(Value::Object(fields)).map_err(E::custom)
+}
+
+impl<'de, H: DataHandler> DeserializeSeed<'de> for MessageSeed<'_, H> {
+	type Value = DataMessage;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, H: DataHandler> Visitor<'de> for MessageSeed<'_, H> {
+	type Value = DataMessage;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("an SDMX-JSON data message")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let mut fields = Map::new();
+		let mut meta = None;
+		let mut data = None;
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"meta" => {
+					let value: Option<Meta> = map.next_value()?;
+					if let Some(value) = &value {
+						self.handler.meta(value).map_err(de::Error::custom)?;
+					}
+					meta = value;
+				}
+				"data" => {
+					let handler = &mut *self.handler;
+					data = map.next_value_seed(DataSeed { handler })?;
+				}
+				_ => {
+					fields.insert(key, map.next_value()?);
+				}
+			}
+		}
+
+		let mut message: DataMessage = from_fields(fields)?;
+		message.meta = meta;
+		message.data = data;
+		Ok(message)
+	}
+}
+
+impl<'de, H: DataHandler> DeserializeSeed<'de> for DataSeed<'_, H> {
+	type Value = Option<Data>;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_any(self)
+	}
+}
+
+impl<'de, H: DataHandler> Visitor<'de> for DataSeed<'_, H> {
+	type Value = Option<Data>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("the data of a data message")
+	}
+
+	fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let mut fields = Map::new();
+		let mut structures = None;
+		let mut data_sets = None;
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"structures" => {
+					let value: Option<Vec<Structure>> = map.next_value()?;
+					if let Some(value) = &value {
+						self.handler.structures(value).map_err(de::Error::custom)?;
+					}
+					structures = value;
+				}
+				"dataSets" => {
+					let handler = &mut *self.handler;
+					data_sets = map.next_value_seed(DataSetsSeed { handler })?;
+				}
+				_ => {
+					fields.insert(key, map.next_value()?);
+				}
+			}
+		}
+
+		let mut data: Data = from_fields(fields)?;
+		data.structures = structures;
+		data.data_sets = data_sets;
+		Ok(Some(data))
+	}
+}
+
+impl<'de, H: DataHandler> DeserializeSeed<'de> for DataSetsSeed<'_, H> {
+	type Value = Option<Vec<DataSet>>;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_any(self)
+	}
+}
+
+impl<'de, H: DataHandler> Visitor<'de> for DataSetsSeed<'_, H> {
+	type Value = Option<Vec<DataSet>>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a list of datasets")
+	}
+
+	fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut data_sets = Vec::new();
+		loop {
+			let seed = DataSetSeed {
+				handler: &mut *self.handler,
+				index: data_sets.len(),
+			};
+			match seq.next_element_seed(seed)? {
+				Some(data_set) => data_sets.push(data_set),
+				None => return Ok(Some(data_sets)),
+			}
+		}
+	}
+}
+
+impl<'de, H: DataHandler> DeserializeSeed<'de> for DataSetSeed<'_, H> {
+	type Value = DataSet;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, H: DataHandler> Visitor<'de> for DataSetSeed<'_, H> {
+	type Value = DataSet;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a dataset")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let mut fields = Map::new();
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"series" => map.next_value_seed(SeriesMapSeed {
+					handler: &mut *self.handler,
+					data_set: self.index,
+				})?,
+				"observations" => map.next_value_seed(ObservationsSeed {
+					handler: &mut *self.handler,
+					data_set: self.index,
+					series_key: None,
+				})?,
+				_ => {
+					fields.insert(key, map.next_value()?);
+				}
+			}
+		}
+		from_fields(fields)
+	}
+}
+
+impl<'de, H: DataHandler> DeserializeSeed<'de> for SeriesMapSeed<'_, H> {
+	type Value = ();
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_any(self)
+	}
+}
+
+impl<'de, H: DataHandler> Visitor<'de> for SeriesMapSeed<'_, H> {
+	type Value = ();
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a map of series")
+	}
+
+	fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(())
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		while let Some(key) = map.next_key::<String>()? {
+			let series = map.next_value_seed(SeriesSeed {
+				handler: &mut *self.handler,
+				data_set: self.data_set,
+				key: &key,
+			})?;
+			self.handler
+				.series(self.data_set, &key, series)
+				.map_err(de::Error::custom)?;
+		}
+		Ok(())
+	}
+}
+
+impl<'de, H: DataHandler> DeserializeSeed<'de> for SeriesSeed<'_, '_, H> {
+	type Value = Series;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, H: DataHandler> Visitor<'de> for SeriesSeed<'_, '_, H> {
+	type Value = Series;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a series")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let mut fields = Map::new();
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"observations" => map.next_value_seed(ObservationsSeed {
+					handler: &mut *self.handler,
+					data_set: self.data_set,
+					series_key: Some(self.key),
+				})?,
+				_ => {
+					fields.insert(key, map.next_value()?);
+				}
+			}
+		}
+		from_fields(fields)
+	}
+}
+
+impl<'de, H: DataHandler> DeserializeSeed<'de> for ObservationsSeed<'_, '_, H> {
+	type Value = ();
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_any(self)
+	}
+}
+
+impl<'de, H: DataHandler> Visitor<'de> for ObservationsSeed<'_, '_, H> {
+	type Value = ();
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a map of observations")
+	}
+
+	fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(())
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		while let Some(key) = map.next_key::<String>()? {
+			let values = map.next_value()?;
+			self.handler
+				.observation(self.data_set, self.series_key, &key, values)
+				.map_err(de::Error::custom)?;
+		}
+		Ok(())
+	}
+}
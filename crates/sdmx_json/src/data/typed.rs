@@ -0,0 +1,289 @@
+use crate::data::{components, Component, Format, Structure};
+use crate::primitives::{DataType, LocalizedText, NumberOrString, SdmxValue};
+use crate::structure::TimeDataType;
+use crate::time::TimePeriod;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+/// A value of a component, typed after the data type of its format.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue<'d> {
+	/// A value of a decimal or floating-point data type.
+	Decimal(f64),
+	/// A value of an integer data type.
+	Integer(i64),
+	Boolean(bool),
+	/// A value of a time data type.
+	Period(TimePeriod),
+	/// A value of any other data type (e.g. `String`, `URI`, `Duration`).
+	String(Cow<'d, str>),
+	/// A text in multiple languages.
+	Localized(&'d LocalizedText),
+	/// Multiple values of the same data type.
+	Multiple(Vec<TypedValue<'d>>),
+	/// A sentinel value of the format, which has a special meaning
+	/// (e.g. `-1` for a value which is not applicable).
+	Sentinel(&'d NumberOrString),
+	/// No value (i.e. `null`).
+	Missing,
+}
+
+/// An error which can occur when typing the value of a component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeError {
+	/// The value contradicts the data type of its component.
+	InvalidValue {
+		component: String,
+		data_type: Option<DataType>,
+	},
+	/// The value has more decimals than allowed by the format of its component.
+	TooManyDecimals { component: String, decimals: usize },
+}
+
+impl Error for TypeError {}
+
+impl fmt::Display for TypeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidValue {
+				component,
+				data_type: Some(data_type),
+			} => write!(
+				f,
+				"invalid value for component {} of type {:?}",
+				component, data_type
+			),
+			Self::InvalidValue { component, .. } => {
+				write!(f, "invalid value for component {}", component)
+			}
+			Self::TooManyDecimals {
+				component,
+				decimals,
+			} => write!(
+				f,
+				"value of component {} has more than {} decimals",
+				component, decimals
+			),
+		}
+	}
+}
+
+impl Component {
+	/// Types a value of this component, according to the data type,
+	/// the decimals, and the sentinel values of its format.
+	///
+	/// A value which is one of the sentinel values is always valid.
+	/// A component without a data type has its value typed
+	/// after its JSON type.
+	///
+	/// Returns an error if the value contradicts the data type (e.g. a
+	/// string which is not a number for a `Double`, or an integer which
+	/// is out of range for a `Short`), or if it has too many decimals.
+	///
+	/// # Example
+	/// ```
+	/// use sdmx_json::data::{Component, Format, TypedValue};
+	/// use sdmx_json::primitives::{DataType, SdmxValue};
+	///
+	/// let component = Component {
+	///     id: "OBS_VALUE".to_owned(),
+	///     format: Some(Format {
+	///         data_type: Some(DataType::Decimal),
+	///         decimals: Some(2),
+	///         ..Default::default()
+	///     }),
+	///     ..Default::default()
+	/// };
+	///
+	/// let value = SdmxValue::String("1.25".to_owned());
+	/// assert_eq!(component.typed_value(&value), Ok(TypedValue::Decimal(1.25)));
+	/// assert!(component.typed_value(&SdmxValue::Number(1.125)).is_err());
+	/// assert!(component.typed_value(&SdmxValue::Boolean(true)).is_err());
+	/// ```
+	pub fn typed_value<'d>(&'d self, value: &'d SdmxValue) -> Result<TypedValue<'d>, TypeError> {
+		let format = self.format.as_ref();
+		if let Some(sentinel) = format.and_then(|f| sentinel(f, value)) {
+			return Ok(TypedValue::Sentinel(sentinel));
+		}
+
+		let data_type = format.and_then(|f| f.data_type.as_ref());
+		let invalid = || TypeError::InvalidValue {
+			component: self.id.clone(),
+			data_type: data_type.cloned(),
+		};
+		let typed = match (data_type, value) {
+			(_, SdmxValue::Null) => TypedValue::Missing,
+			(_, SdmxValue::Array(values)) => {
+				let values = values.iter().map(|value| self.typed_value(value));
+				TypedValue::Multiple(values.collect::<Result<_, _>>()?)
+			}
+			(None, value) => untyped(value),
+			(Some(data_type), value) => typed(data_type, value).ok_or_else(invalid)?,
+		};
+
+		let decimals = format.and_then(|f| f.decimals);
+		match (decimals, value) {
+			(Some(decimals), SdmxValue::Number(_) | SdmxValue::String(_))
+				if matches!(typed, TypedValue::Decimal(_)) && count_decimals(value) > decimals =>
+			{
+				Err(TypeError::TooManyDecimals {
+					component: self.id.clone(),
+					decimals,
+				})
+			}
+			_ => Ok(typed),
+		}
+	}
+}
+
+impl Structure {
+	/// Types the values of the measures of an observation, which come
+	/// first in its array of values, as with [`Component::typed_value`].
+	pub fn typed_measures<'d>(
+		&'d self,
+		values: &'d [SdmxValue],
+	) -> impl Iterator<Item = Result<(&'d str, TypedValue<'d>), TypeError>> + 'd {
+		let measures = self.measures.as_ref().map(|m| components(&m.observation));
+		measures
+			.unwrap_or_default()
+			.iter()
+			.zip(values)
+			.map(|(measure, value)| Ok((measure.id.as_str(), measure.typed_value(value)?)))
+	}
+}
+
+/// The sentinel value of a format which a value is equal to, if any.
+pub(crate) fn sentinel<'d>(format: &'d Format, value: &SdmxValue) -> Option<&'d NumberOrString> {
+	let sentinels = format.sentinel_values.as_deref()?;
+	sentinels
+		.iter()
+		.find(|sentinel| is_sentinel(sentinel, value))
+}
+
+/// Whether a value is equal to a sentinel value.
+pub(crate) fn is_sentinel(sentinel: &NumberOrString, value: &SdmxValue) -> bool {
+	match (sentinel, value) {
+		(NumberOrString::Number(number), SdmxValue::Integer(integer)) => number == integer,
+		(NumberOrString::Number(number), SdmxValue::Number(float)) => *number as f64 == *float,
+		(NumberOrString::String(string), SdmxValue::String(other)) => string == other,
+		_ => false,
+	}
+}
+
+/// Types a value after its JSON type.
+fn untyped(value: &SdmxValue) -> TypedValue<'_> {
+	match value {
+		SdmxValue::Integer(integer) => TypedValue::Integer(*integer as i64),
+		SdmxValue::Number(number) => TypedValue::Decimal(*number),
+		SdmxValue::Boolean(boolean) => TypedValue::Boolean(*boolean),
+		SdmxValue::String(string) => TypedValue::String(Cow::Borrowed(string)),
+		SdmxValue::LocalizedText(texts) => TypedValue::Localized(texts),
+		SdmxValue::Null | SdmxValue::Array(_) => TypedValue::Missing,
+	}
+}
+
+/// Types a single value after a data type, if it is valid for it.
+fn typed<'d>(data_type: &DataType, value: &'d SdmxValue) -> Option<TypedValue<'d>> {
+	let string = match value {
+		SdmxValue::String(string) => Some(string.as_str()),
+		_ => None,
+	};
+
+	let typed = match data_type {
+		DataType::BigInteger
+		| DataType::Integer
+		| DataType::Long
+		| DataType::Short
+		| DataType::Count => {
+			let integer = match value {
+				SdmxValue::Integer(integer) => *integer as i64,
+				_ => string?.parse().ok()?,
+			};
+			let in_range = match data_type {
+				DataType::Short => i16::try_from(integer).is_ok(),
+				DataType::Integer => i32::try_from(integer).is_ok(),
+				DataType::Count => integer >= 0,
+				_ => true,
+			};
+			TypedValue::Integer(in_range.then_some(integer)?)
+		}
+		DataType::Decimal
+		| DataType::Float
+		| DataType::Double
+		| DataType::InclusiveValueRange
+		| DataType::ExclusiveValueRange
+		| DataType::Incremental => match value {
+			SdmxValue::Integer(integer) => TypedValue::Decimal(*integer as f64),
+			SdmxValue::Number(number) => TypedValue::Decimal(*number),
+			_ => TypedValue::Decimal(string?.parse().ok()?),
+		},
+		DataType::Boolean => match value {
+			SdmxValue::Boolean(boolean) => TypedValue::Boolean(*boolean),
+			_ => TypedValue::Boolean(string?.parse().ok()?),
+		},
+		DataType::Alpha | DataType::AlphaNumeric | DataType::Numeric => {
+			let valid = |c: char| match data_type {
+				DataType::Alpha => c.is_alphabetic(),
+				DataType::AlphaNumeric => c.is_alphanumeric(),
+				_ => c.is_ascii_digit(),
+			};
+			match value {
+				SdmxValue::Integer(integer) if *data_type != DataType::Alpha && *integer >= 0 => {
+					TypedValue::String(Cow::Owned(integer.to_string()))
+				}
+				_ if string?.chars().all(valid) => TypedValue::String(Cow::Borrowed(string?)),
+				_ => return None,
+			}
+		}
+		data_type => match time_data_type(data_type) {
+			Some(time_data_type) => {
+				let period: TimePeriod = string?.parse().ok()?;
+				TypedValue::Period(period.is_of_type(time_data_type).then_some(period)?)
+			}
+			None => match value {
+				SdmxValue::LocalizedText(texts) => TypedValue::Localized(texts),
+				_ => TypedValue::String(Cow::Borrowed(string?)),
+			},
+		},
+	};
+	Some(typed)
+}
+
+/// The time data type equivalent to a data type, if any.
+fn time_data_type(data_type: &DataType) -> Option<TimeDataType> {
+	let time_data_type = match data_type {
+		DataType::ObservationalTimePeriod => TimeDataType::ObservationalTimePeriod,
+		DataType::StandardTimePeriod => TimeDataType::StandardTimePeriod,
+		DataType::BasicTimePeriod => TimeDataType::BasicTimePeriod,
+		DataType::GregorianTimePeriod => TimeDataType::GregorianTimePeriod,
+		DataType::GregorianYear => TimeDataType::GregorianYear,
+		DataType::GregorianYearMonth => TimeDataType::GregorianYearMonth,
+		DataType::GregorianDay => TimeDataType::GregorianDay,
+		DataType::ReportingTimePeriod => TimeDataType::ReportingTimePeriod,
+		DataType::ReportingYear => TimeDataType::ReportingYear,
+		DataType::ReportingSemester => TimeDataType::ReportingSemester,
+		DataType::ReportingTrimester => TimeDataType::ReportingTrimester,
+		DataType::ReportingQuarter => TimeDataType::ReportingQuarter,
+		DataType::ReportingMonth => TimeDataType::ReportingMonth,
+		DataType::ReportingWeek => TimeDataType::ReportingWeek,
+		DataType::ReportingDay => TimeDataType::ReportingDay,
+		DataType::DateTime => TimeDataType::DateTime,
+		DataType::TimeRange => TimeDataType::TimeRange,
+		_ => return None,
+	};
+	Some(time_data_type)
+}
+
+/// The number of decimals of a number, as written in JSON or in a string.
+pub(crate) fn count_decimals(value: &SdmxValue) -> usize {
+	let written = match value {
+		SdmxValue::Number(number) => number.to_string(),
+		SdmxValue::String(string) => string.clone(),
+		_ => return 0,
+	};
+	let mantissa = written.split(['e', 'E']).next().unwrap_or_default();
+	mantissa
+		.split_once('.')
+		.map_or(0, |(_, decimals)| decimals.len())
+}
@@ -0,0 +1,355 @@
+use crate::data::{count_decimals, is_sentinel, CellValue, DataMessage, DecodeError, Labels, Row};
+use crate::primitives::SdmxValue;
+use crate::structure::{DataStructure, Format, LocalRepresentation, Resolver, TimeDataType, Usage};
+use crate::time::TimePeriod;
+use regex_lite::Regex;
+use std::collections::HashSet;
+use std::fmt;
+
+/// A value of an observation which does not conform
+/// to the data structure of its dataset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+	/// The index of the dataset of the observation in the message.
+	pub data_set: usize,
+	/// The key of the observation, made of the IDs of the values of its
+	/// dimensions (in the order of the data structure, with the time
+	/// dimension last) separated by `.`, and empty for a missing value.
+	pub key: String,
+	pub kind: ViolationKind,
+}
+
+/// How a value of an observation does not conform to its data structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+	/// A dimension of the data structure has no value.
+	MissingDimension(String),
+	/// A coded value is not in the codelist its component is enumerated by.
+	UnknownCode { component: String, code: String },
+	/// A mandatory attribute has no value.
+	MissingAttribute(String),
+	/// A value of a measure does not respect a facet of its format.
+	InvalidFacet { measure: String, facet: Facet },
+	/// A time period is not of the data type of the time dimension.
+	InvalidTimePeriod {
+		period: String,
+		data_type: TimeDataType,
+	},
+}
+
+/// A facet of a format, which restricts the values of a component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Facet {
+	MinLength(usize),
+	MaxLength(usize),
+	MinValue(isize),
+	MaxValue(isize),
+	Pattern(String),
+	Decimals(usize),
+}
+
+impl fmt::Display for Violation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "dataset {}, key {}: ", self.data_set, self.key)?;
+		match &self.kind {
+			ViolationKind::MissingDimension(dimension) => {
+				write!(f, "missing dimension {}", dimension)
+			}
+			ViolationKind::UnknownCode { component, code } => {
+				write!(f, "unknown code {} for component {}", code, component)
+			}
+			ViolationKind::MissingAttribute(attribute) => {
+				write!(f, "missing mandatory attribute {}", attribute)
+			}
+			ViolationKind::InvalidFacet { measure, facet } => {
+				write!(
+					f,
+					"value of measure {} does not respect {:?}",
+					measure, facet
+				)
+			}
+			ViolationKind::InvalidTimePeriod { period, data_type } => {
+				write!(f, "time period {} is not of type {:?}", period, data_type)
+			}
+		}
+	}
+}
+
+impl DataMessage {
+	/// Checks that the observations of this message conform to a data
+	/// structure, whose codelists are resolved by a [`Resolver`]:
+	///
+	/// - every dimension (including the time dimension) has a value,
+	/// - coded values of dimensions and attributes are in the codelist
+	///   their component is enumerated by,
+	/// - mandatory attributes have a value (which may be attached to
+	///   the dataset, a dimension group, or the series),
+	/// - values of measures respect the length, value, pattern, and
+	///   decimals facets of their format (unless they are sentinel values),
+	/// - time periods are of the data type of the time dimension.
+	///
+	/// Each observation is checked along with the attributes it inherits,
+	/// so a value attached to a series is reported for every observation
+	/// of that series. Components whose codelist cannot be resolved,
+	/// and patterns which are not valid regular expressions, are not checked.
+	///
+	/// Returns an error if the observations cannot be decoded
+	/// (see [`Data::observations`](crate::data::Data::observations)).
+	///
+	/// # Example
+	/// ```no_run
+	/// use std::str::FromStr;
+	/// use std::fs::read_to_string;
+	/// use sdmx_json::data::DataMessage;
+	/// use sdmx_json::structure::{Resolved, Resolver, StructureMessage};
+	///
+	/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+	///     let file = read_to_string("sdmx-structure.json")?;
+	///     let structures = StructureMessage::from_str(file.as_str())?.data.unwrap_or_default();
+	///     let resolver = Resolver::new(&structures);
+	///     let urn = "urn:sdmx:org.sdmx.infomodel.datastructure.DataStructure=ECB:EXR(1.0)";
+	///     let Resolved::DataStructure(structure) = resolver.resolve_str(urn)? else {
+	///         return Ok(());
+	///     };
+	///
+	///     let file = read_to_string("sdmx-data.json")?;
+	///     let message = DataMessage::from_str(file.as_str())?;
+	///     for violation in message.validate(structure, &resolver)? {
+	///         println!("{}", violation);
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn validate(
+		&self,
+		structure: &DataStructure,
+		resolver: &Resolver<'_>,
+	) -> Result<Vec<Violation>, DecodeError> {
+		let validator = Validator::new(structure, resolver);
+		let mut violations = Vec::new();
+		for row in self.rows(Labels::Id) {
+			validator.validate(&row?, &mut violations);
+		}
+		Ok(violations)
+	}
+}
+
+/// The components of a data structure, with what is needed
+/// to check their values.
+struct Validator<'s> {
+	/// The dimensions ordered by position, with the time dimension last.
+	dimensions: Vec<Coded<'s>>,
+	time_dimension: Option<(&'s str, TimeDataType)>,
+	attributes: Vec<(Coded<'s>, Usage)>,
+	measures: Vec<(&'s str, &'s Format, Option<Regex>)>,
+}
+
+/// A component, with the IDs of the codes of its codelist if it is enumerated.
+type Coded<'s> = (&'s str, Option<HashSet<&'s str>>);
+
+impl<'s> Validator<'s> {
+	fn new(structure: &'s DataStructure, resolver: &Resolver<'s>) -> Self {
+		let codes = |representation: Option<&LocalRepresentation>| {
+			let enumeration = representation?.enumeration.as_deref()?;
+			let items = resolver.resolve_str(enumeration).ok()?.items()?;
+			Some(items.iter().map(|item| item.id.as_str()).collect())
+		};
+
+		let components = structure.data_structure_components.as_ref();
+		let dimension_list = components.map(|c| &c.dimension_list);
+		let mut dimensions: Vec<_> = dimension_list
+			.and_then(|list| list.dimensions.as_deref())
+			.unwrap_or_default()
+			.iter()
+			.map(|d| {
+				// a dimension without an ID is identified by its concept
+				let id = d.id.as_deref();
+				let id = id.unwrap_or_else(|| d.concept_id());
+				(d.position, (id, codes(d.local_representation.as_ref())))
+			})
+			.collect();
+		dimensions.sort_by_key(|(position, _)| *position);
+		let mut dimensions: Vec<_> = dimensions.into_iter().map(|(_, d)| d).collect();
+
+		let time_dimension = dimension_list.and_then(|list| list.time_dimensions.as_ref());
+		let time_dimension = time_dimension.map(|dimension| {
+			let format = dimension.local_representation.format.as_ref();
+			let data_type = format.and_then(|f| f.data_type.clone()?.try_into().ok());
+			let id = dimension.id.as_deref();
+			let id = id.unwrap_or_else(|| dimension.concept_id());
+			dimensions.push((id, None));
+			(
+				id,
+				data_type.unwrap_or(TimeDataType::ObservationalTimePeriod),
+			)
+		});
+
+		let attribute_list = components.and_then(|c| c.attribute_list.as_ref());
+		let attributes = attribute_list.and_then(|list| list.attributes.as_deref());
+		let attributes = attributes.unwrap_or_default().iter().map(|attribute| {
+			let representation = Some(&attribute.local_representation);
+			(
+				(attribute.id.as_str(), codes(representation)),
+				attribute.usage,
+			)
+		});
+
+		let measure_list = components.and_then(|c| c.measure_list.as_ref());
+		let measures = measure_list.and_then(|list| list.measures.as_deref());
+		let measures = measures.unwrap_or_default().iter().filter_map(|measure| {
+			let format = measure.local_representation.format.as_ref()?;
+			let pattern = format.pattern.as_deref().and_then(pattern_regex);
+			Some((measure.id.as_str(), format, pattern))
+		});
+
+		Self {
+			dimensions,
+			time_dimension,
+			attributes: attributes.collect(),
+			measures: measures.collect(),
+		}
+	}
+
+	fn validate(&self, row: &Row<'_>, violations: &mut Vec<Violation>) {
+		let key: Vec<&str> = self
+			.dimensions
+			.iter()
+			.map(|(id, _)| match row.get(id) {
+				Some(CellValue::Text(value)) => value,
+				_ => "",
+			})
+			.collect();
+		let mut report = |kind| {
+			violations.push(Violation {
+				data_set: row.data_set,
+				key: key.join("."),
+				kind,
+			})
+		};
+
+		for (id, codes) in &self.dimensions {
+			match row.get(id) {
+				None => report(ViolationKind::MissingDimension(id.to_string())),
+				Some(value) => check_code(id, codes, value, &mut report),
+			}
+		}
+		for ((id, codes), usage) in &self.attributes {
+			match row.get(id) {
+				None if *usage == Usage::Mandatory => {
+					report(ViolationKind::MissingAttribute(id.to_string()))
+				}
+				None => {}
+				Some(value) => check_code(id, codes, value, &mut report),
+			}
+		}
+
+		for (id, format, pattern) in &self.measures {
+			let Some(CellValue::Value(value)) = row.get(id) else {
+				continue;
+			};
+			let sentinels = format.sentinel_value.iter().flatten();
+			if sentinels
+				.filter_map(|sentinel| sentinel.value.as_ref())
+				.any(|sentinel| is_sentinel(sentinel, value))
+			{
+				continue;
+			}
+			for facet in invalid_facets(format, pattern.as_ref(), value) {
+				report(ViolationKind::InvalidFacet {
+					measure: id.to_string(),
+					facet,
+				});
+			}
+		}
+
+		if let Some((id, data_type)) = self.time_dimension {
+			if let Some(CellValue::Text(period)) = row.get(id) {
+				let parsed = period.parse::<TimePeriod>();
+				if !parsed.is_ok_and(|parsed| parsed.is_of_type(data_type)) {
+					report(ViolationKind::InvalidTimePeriod {
+						period: period.to_string(),
+						data_type,
+					});
+				}
+			}
+		}
+	}
+}
+
+/// Reports a coded value which is not in the codelist of its component.
+fn check_code(
+	component: &str,
+	codes: &Option<HashSet<&str>>,
+	value: &CellValue<'_>,
+	report: &mut impl FnMut(ViolationKind),
+) {
+	let Some(codes) = codes else {
+		return;
+	};
+	let code = match value {
+		CellValue::Text(code) => code,
+		CellValue::Value(SdmxValue::String(code)) => code.as_str(),
+		CellValue::Value(_) => return,
+	};
+	if !codes.contains(code) {
+		report(ViolationKind::UnknownCode {
+			component: component.to_owned(),
+			code: code.to_string(),
+		});
+	}
+}
+
+/// The regular expression of a pattern facet, which matches whole values,
+/// unless the pattern is not a valid regular expression.
+pub(crate) fn pattern_regex(pattern: &str) -> Option<Regex> {
+	Regex::new(&format!("^(?:{})$", pattern)).ok()
+}
+
+/// The facets of a format which a value does not respect.
+pub(crate) fn invalid_facets(
+	format: &Format,
+	pattern: Option<&Regex>,
+	value: &SdmxValue,
+) -> Vec<Facet> {
+	let written = match value {
+		SdmxValue::String(string) => string.clone(),
+		SdmxValue::Integer(integer) => integer.to_string(),
+		SdmxValue::Number(number) => number.to_string(),
+		_ => return Vec::new(),
+	};
+	let number: Option<f64> = match value {
+		SdmxValue::Integer(integer) => Some(*integer as f64),
+		SdmxValue::Number(number) => Some(*number),
+		_ => written.parse().ok(),
+	};
+	let length = written.chars().count();
+
+	let mut facets = Vec::new();
+	if let Some(min) = format.min_length.filter(|min| length < *min) {
+		facets.push(Facet::MinLength(min));
+	}
+	if let Some(max) = format.max_length.filter(|max| length > *max) {
+		facets.push(Facet::MaxLength(max));
+	}
+	if let Some(min) = format.min_value {
+		if number.is_some_and(|number| number < min as f64) {
+			facets.push(Facet::MinValue(min));
+		}
+	}
+	if let Some(max) = format.max_value {
+		if number.is_some_and(|number| number > max as f64) {
+			facets.push(Facet::MaxValue(max));
+		}
+	}
+	if let (Some(regex), Some(pattern)) = (pattern, &format.pattern) {
+		if !regex.is_match(&written) {
+			facets.push(Facet::Pattern(pattern.clone()));
+		}
+	}
+	if let Some(decimals) = format.decimals {
+		if number.is_some() && count_decimals(value) > decimals {
+			facets.push(Facet::Decimals(decimals));
+		}
+	}
+	facets
+}
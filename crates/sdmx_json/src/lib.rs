@@ -17,10 +17,24 @@
 //!   which internally calls [`serde_json::from_value()`][serde_json::from_value]
 //! - any type that implements [`Read`](`std::io::Read`) by directly calling
 //!   [`serde_json::from_reader()`][serde_json::from_reader]
+//!
+//! # Features
+//! - `arrow`: enables the `arrow` module, for converting data
+//!   messages into Apache Arrow record batches.
 
 #[macro_use]
 mod macros;
 
+/// Conversion of SDMX-JSON data messages into Apache Arrow record batches
+///
+/// This module implements conversions from a
+/// [`DataMessage`][crate::data::DataMessage] (or a single dataset of its
+/// [`Data`][crate::data::Data]) into [`RecordBatch`][arrow_array::RecordBatch]es,
+/// whose schema is derived from the structure of each dataset.
+#[cfg(feature = "arrow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub mod arrow;
+
 /// SDMX-JSON Data Message format, 2.0.0 (aligned with SDMX 3.0.0)
 ///
 /// This module implements [SDMX-JSON Data Message 2.0.0][data].
@@ -66,3 +80,11 @@ pub mod primitives;
 ///
 /// [structure]: https://github.com/sdmx-twg/sdmx-json/tree/master/structure-message
 pub mod structure;
+
+/// Time periods of SDMX, as described by a time data type
+///
+/// This module implements [`TimePeriod`][crate::time::TimePeriod], which
+/// can be parsed from (and written back as) any of the formats of a
+/// [`TimeDataType`][crate::structure::TimeDataType], such as the string
+/// values of time dimensions, or the reporting periods of a data set.
+pub mod time;
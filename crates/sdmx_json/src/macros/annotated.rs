@@ -0,0 +1,11 @@
+macro_rules! impl_annotated {
+	($($struct:ty),+ $(,)?) => {
+		$(
+			impl $crate::data::Annotated for $struct {
+				fn annotation_indices(&self) -> &[usize] {
+					self.annotations.as_deref().unwrap_or_default()
+				}
+			}
+		)+
+	}
+}
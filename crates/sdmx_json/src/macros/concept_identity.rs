@@ -0,0 +1,26 @@
+macro_rules! impl_concept_identity {
+	($($struct:ty),+ $(,)?) => {
+		$(
+			impl $struct {
+				/// The URN of the concept which identifies this component.
+				pub fn concept_identity_urn(
+					&self,
+				) -> Result<$crate::structure::Urn, $crate::structure::ParseUrnError> {
+					self.concept_identity.parse()
+				}
+
+				/// The ID of the concept which identifies this component,
+				/// i.e. the last item of its URN (or the whole concept
+				/// identity if it is not the URN of an item).
+				pub fn concept_id(&self) -> &str {
+					let identity = self.concept_identity.as_str();
+					let urn = self.concept_identity_urn().ok();
+					match urn.as_ref().and_then(|urn| urn.item_path.last()) {
+						Some(id) => &identity[identity.len() - id.len()..],
+						None => identity,
+					}
+				}
+			}
+		)+
+	}
+}
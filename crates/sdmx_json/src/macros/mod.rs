@@ -1,6 +1,10 @@
 #[macro_use]
+mod annotated;
+#[macro_use]
 mod artefact;
 #[macro_use]
+mod concept_identity;
+#[macro_use]
 mod extendable;
 #[macro_use]
 mod item_scheme;
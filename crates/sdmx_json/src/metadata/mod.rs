@@ -0,0 +1,4 @@
+mod all;
+mod validation;
+pub use all::*;
+pub use validation::*;
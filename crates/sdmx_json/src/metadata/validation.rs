@@ -0,0 +1,233 @@
+use crate::data::{invalid_facets, pattern_regex, Facet};
+use crate::metadata::{Attribute, MetadataMessage};
+use crate::primitives::SdmxValue;
+use crate::structure::{
+	MetadataAttribute, MetadataAttributeRepresentation, MetadataStructure, Occurrence, Resolver,
+};
+use std::fmt;
+
+/// A reported metadata attribute which does not conform
+/// to the metadata structure of its metadata set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+	/// The index of the metadata set of the attribute in the message.
+	pub metadata_set: usize,
+	/// The path of the attribute, made of its ID and the IDs
+	/// of its parent attributes, separated by `.`.
+	pub path: String,
+	pub kind: ViolationKind,
+}
+
+/// How a reported metadata attribute does not conform
+/// to its metadata structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+	/// The attribute is not defined in the metadata structure,
+	/// under the definition of its parent attribute.
+	UnknownAttribute,
+	/// The attribute is reported fewer times than its minimum occurrences.
+	TooFewOccurrences { occurrences: usize, min: usize },
+	/// The attribute is reported more times than its maximum occurrences.
+	TooManyOccurrences { occurrences: usize, max: usize },
+	/// A coded value is not in the codelist the attribute is enumerated by.
+	UnknownCode(String),
+	/// The value does not respect a facet of the format of the attribute.
+	InvalidFacet(Facet),
+	/// The attribute is presentational, but has a value.
+	PresentationalValue,
+}
+
+impl fmt::Display for Violation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"metadata set {}, attribute {}: ",
+			self.metadata_set, self.path
+		)?;
+		match &self.kind {
+			ViolationKind::UnknownAttribute => write!(f, "unknown attribute"),
+			ViolationKind::TooFewOccurrences { occurrences, min } => {
+				write!(f, "{} occurrences instead of at least {}", occurrences, min)
+			}
+			ViolationKind::TooManyOccurrences { occurrences, max } => {
+				write!(f, "{} occurrences instead of at most {}", occurrences, max)
+			}
+			ViolationKind::UnknownCode(code) => write!(f, "unknown code {}", code),
+			ViolationKind::InvalidFacet(facet) => write!(f, "value does not respect {:?}", facet),
+			ViolationKind::PresentationalValue => write!(f, "presentational attribute has a value"),
+		}
+	}
+}
+
+impl MetadataMessage {
+	/// Checks that the attributes reported by the metadata sets of this
+	/// message conform to a metadata structure, whose codelists are
+	/// resolved by a [`Resolver`]:
+	///
+	/// - every attribute is defined in the metadata attribute list,
+	///   under the definition of its parent attribute,
+	/// - every attribute is reported between its minimum and maximum
+	///   occurrences (at most once when its maximum is not set),
+	/// - coded values are in the codelist the attribute is enumerated by,
+	/// - values respect the length, value, pattern, and decimals
+	///   facets of the format of the attribute,
+	/// - presentational attributes have no value.
+	///
+	/// Attributes whose codelist cannot be resolved, and patterns
+	/// which are not valid regular expressions, are not checked.
+	///
+	/// # Example
+	/// ```no_run
+	/// use std::str::FromStr;
+	/// use std::fs::read_to_string;
+	/// use sdmx_json::metadata::MetadataMessage;
+	/// use sdmx_json::structure::{Resolved, Resolver, StructureMessage};
+	///
+	/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+	///     let file = read_to_string("sdmx-structure.json")?;
+	///     let structures = StructureMessage::from_str(file.as_str())?.data.unwrap_or_default();
+	///     let resolver = Resolver::new(&structures);
+	///     let urn = "urn:sdmx:org.sdmx.infomodel.metadatastructure.MetadataStructure=ECB:MSD(1.0)";
+	///     let Resolved::MetadataStructure(structure) = resolver.resolve_str(urn)? else {
+	///         return Ok(());
+	///     };
+	///
+	///     let file = read_to_string("sdmx-metadata.json")?;
+	///     let message = MetadataMessage::from_str(file.as_str())?;
+	///     for violation in message.validate(structure, &resolver) {
+	///         println!("{}", violation);
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn validate(
+		&self,
+		structure: &MetadataStructure,
+		resolver: &Resolver<'_>,
+	) -> Vec<Violation> {
+		let components = structure.metadata_structure_components.as_ref();
+		let list = components.and_then(|c| c.metadata_attribute_list.as_ref());
+		let definitions = list.and_then(|l| l.metadata_attributes.as_deref());
+
+		let mut violations = Vec::new();
+		let metadata_sets = self
+			.data
+			.iter()
+			.flat_map(|d| d.metadata_sets.iter().flatten());
+		for (index, metadata_set) in metadata_sets.enumerate() {
+			let mut validator = Validator {
+				metadata_set: index,
+				resolver,
+				violations: &mut violations,
+			};
+			validator.validate(
+				&[],
+				definitions.unwrap_or_default(),
+				&metadata_set.attributes,
+			);
+		}
+		violations
+	}
+}
+
+/// Checks the attributes of a metadata set.
+struct Validator<'v, 'r> {
+	metadata_set: usize,
+	resolver: &'v Resolver<'r>,
+	violations: &'v mut Vec<Violation>,
+}
+
+impl Validator<'_, '_> {
+	fn report(&mut self, path: &[&str], kind: ViolationKind) {
+		self.violations.push(Violation {
+			metadata_set: self.metadata_set,
+			path: path.join("."),
+			kind,
+		});
+	}
+
+	/// Checks sibling attributes against the definitions of the
+	/// attributes at their level, given the path of their parent.
+	fn validate(
+		&mut self,
+		parent: &[&str],
+		definitions: &[MetadataAttribute],
+		attributes: &[Attribute],
+	) {
+		for definition in definitions {
+			let path = [parent, &[definition.id.as_str()]].concat();
+			let occurrences = attributes.iter().filter(|a| a.id == definition.id).count();
+			if occurrences < definition.min_occurs {
+				let min = definition.min_occurs;
+				self.report(&path, ViolationKind::TooFewOccurrences { occurrences, min });
+			}
+			let max = match definition.max_occurs {
+				Some(Occurrence::Signed(max)) => Some(max),
+				Some(Occurrence::Unbounded) => None,
+				None => Some(1),
+			};
+			if let Some(max) = max.filter(|max| occurrences > *max) {
+				self.report(
+					&path,
+					ViolationKind::TooManyOccurrences { occurrences, max },
+				);
+			}
+		}
+
+		for attribute in attributes {
+			let path = [parent, &[attribute.id.as_str()]].concat();
+			let Some(definition) = definitions.iter().find(|d| d.id == attribute.id) else {
+				self.report(&path, ViolationKind::UnknownAttribute);
+				continue;
+			};
+			if let Some(value) = attribute.value.as_ref().filter(|v| **v != SdmxValue::Null) {
+				self.validate_value(&path, definition, value);
+			}
+			self.validate(
+				&path,
+				definition
+					.metadata_attributes
+					.as_deref()
+					.unwrap_or_default(),
+				attribute.attributes.as_deref().unwrap_or_default(),
+			);
+		}
+	}
+
+	fn validate_value(&mut self, path: &[&str], definition: &MetadataAttribute, value: &SdmxValue) {
+		if definition.is_presentational == Some(true) {
+			self.report(path, ViolationKind::PresentationalValue);
+		}
+		let values = match value {
+			SdmxValue::Array(values) => values.as_slice(),
+			value => std::slice::from_ref(value),
+		};
+
+		match &definition.local_representation {
+			Some(MetadataAttributeRepresentation::Enumeration(enumeration)) => {
+				let resolved = self.resolver.resolve_str(&enumeration.enumeration);
+				let Some(items) = resolved.ok().and_then(|r| r.items()) else {
+					return;
+				};
+				for value in values {
+					let SdmxValue::String(code) = value else {
+						continue;
+					};
+					if !items.iter().any(|item| &item.id == code) {
+						self.report(path, ViolationKind::UnknownCode(code.clone()));
+					}
+				}
+			}
+			Some(MetadataAttributeRepresentation::Format(format)) => {
+				let format = &format.format;
+				let pattern = format.pattern.as_deref().and_then(pattern_regex);
+				for value in values {
+					for facet in invalid_facets(format, pattern.as_ref(), value) {
+						self.report(path, ViolationKind::InvalidFacet(facet));
+					}
+				}
+			}
+			None => {}
+		}
+	}
+}
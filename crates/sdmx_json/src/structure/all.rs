@@ -427,6 +427,10 @@ pub struct Format {
 	pub min_value: Option<isize>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub max_value: Option<isize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub decimals: Option<usize>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub pattern: Option<String>,
 	pub is_multilingual: bool,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub sentinel_value: Option<Vec<SentinelValue>>,
@@ -1153,3 +1157,11 @@ impl_item_scheme! {
 	(TransformationScheme, transformations),
 	(UserDefinedOperatorsScheme, user_defined_operators),
 }
+
+impl_concept_identity!(
+	Attribute,
+	Dimension,
+	TimeDimension,
+	Measure,
+	MetadataAttribute
+);
@@ -8,7 +8,10 @@ use std::collections::HashMap;
 #[serde(rename_all = "camelCase")]
 pub struct CommonArtefactType {
 	pub id: String,
+	/// Spelled `agencyID` in SDMX-JSON, unlike the camel-cased
+	/// name of every other field.
 	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(rename = "agencyID", alias = "agencyId")]
 	pub agency_id: Option<String>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub version: Option<String>,
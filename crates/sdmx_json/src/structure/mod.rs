@@ -1,8 +1,14 @@
 mod all;
 mod common;
 mod constraints;
+mod resolver;
 mod traits;
+mod urn;
+mod validation;
 pub use all::*;
 pub use common::*;
 pub use constraints::*;
+pub use resolver::*;
 pub use traits::*;
+pub use urn::*;
+pub use validation::*;
@@ -0,0 +1,501 @@
+use crate::structure::{
+	AgencyScheme, Attribute, Categorization, CategoryScheme, Class, Codelist, CommonArtefactType,
+	ConceptScheme, CustomTypeScheme, Data, DataConstraint, DataConsumerScheme, DataProviderScheme,
+	DataStructure, Dataflow, Dimension, Item, ItemScheme, LocalRepresentation, Measure,
+	MetadataAttribute, MetadataAttributeRepresentation, MetadataConstraint, MetadataProviderScheme,
+	MetadataStructure, NamePersonalizationScheme, OrganizationUnitScheme, ParseUrnError,
+	ReportingTaxonomy, RulesetScheme, TimeDimension, TransformationScheme, Urn,
+	UserDefinedOperatorsScheme, VtlMappingScheme,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// An index over the artefacts of structure data, which resolves
+/// URN references into the artefacts, items, or components they
+/// refer to.
+///
+/// A version with wildcards resolves to the latest stable version
+/// it matches, where `+` after a part of the version matches any
+/// greater or equal version with the same previous parts (e.g. `1.2+.0`
+/// matches `1.2.1` and `1.3.0`, but not `2.0.0`), and `*` matches any
+/// stable version.
+///
+/// # Example
+/// ```no_run
+/// use std::str::FromStr;
+/// use std::fs::read_to_string;
+/// use sdmx_json::structure::{Resolved, Resolver, StructureMessage};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+///     let file = read_to_string("sdmx-structure.json")?;
+///     let message = StructureMessage::from_str(file.as_str())?;
+///     let data = message.data.unwrap_or_default();
+///     let resolver = Resolver::new(&data);
+///     for dataflow in data.dataflows.iter().flatten() {
+///         if let Some(urn) = &dataflow.structure {
+///             if let Resolved::DataStructure(structure) = resolver.resolve_str(urn)? {
+///                 println!("{}", structure.common.id);
+///             }
+///         }
+///     }
+///     for error in resolver.unresolved() {
+///         println!("{}", error);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Resolver<'s> {
+	data: &'s Data,
+	/// The versions of every artefact, by class, agency ID and ID.
+	artefacts: HashMap<ArtefactKey<'s>, Vec<(&'s str, Resolved<'s>)>>,
+	/// Every artefact with its URN, in the order of the structure data.
+	urns: Vec<(Urn, Resolved<'s>)>,
+}
+
+type ArtefactKey<'s> = (Class, &'s str, &'s str);
+
+/// An artefact, item, or component of structure data,
+/// which a URN resolves to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolved<'s> {
+	DataStructure(&'s DataStructure),
+	MetadataStructure(&'s MetadataStructure),
+	CategoryScheme(&'s CategoryScheme),
+	ConceptScheme(&'s ConceptScheme),
+	/// A codelist, a geographic codelist, or a geographic grid codelist.
+	Codelist(&'s Codelist),
+	AgencyScheme(&'s AgencyScheme),
+	DataProviderScheme(&'s DataProviderScheme),
+	DataConsumerScheme(&'s DataConsumerScheme),
+	MetadataProviderScheme(&'s MetadataProviderScheme),
+	OrganizationUnitScheme(&'s OrganizationUnitScheme),
+	Dataflow(&'s Dataflow),
+	ReportingTaxonomy(&'s ReportingTaxonomy),
+	Categorization(&'s Categorization),
+	DataConstraint(&'s DataConstraint),
+	MetadataConstraint(&'s MetadataConstraint),
+	CustomTypeScheme(&'s CustomTypeScheme),
+	VtlMappingScheme(&'s VtlMappingScheme),
+	NamePersonalizationScheme(&'s NamePersonalizationScheme),
+	RulesetScheme(&'s RulesetScheme),
+	TransformationScheme(&'s TransformationScheme),
+	UserDefinedOperatorsScheme(&'s UserDefinedOperatorsScheme),
+	/// Any other artefact (e.g. a metadataflow, a provision
+	/// agreement, or a structure map), of which only the
+	/// common properties are known.
+	Other(&'s CommonArtefactType),
+	/// An item of an item scheme (e.g. a code, or a concept).
+	Item(&'s Item),
+	Dimension(&'s Dimension),
+	TimeDimension(&'s TimeDimension),
+	Attribute(&'s Attribute),
+	Measure(&'s Measure),
+	MetadataAttribute(&'s MetadataAttribute),
+}
+
+/// An error which can occur when resolving a reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+	/// The reference is not a valid URN.
+	InvalidUrn { urn: String, error: ParseUrnError },
+	/// No artefact, item, or component matches the URN.
+	Unresolved(Urn),
+}
+
+impl Error for ResolveError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::InvalidUrn { error, .. } => Some(error),
+			Self::Unresolved(_) => None,
+		}
+	}
+}
+
+impl fmt::Display for ResolveError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidUrn { urn, error } => write!(f, "{}: {}", error, urn),
+			Self::Unresolved(urn) => write!(f, "unresolved reference: {}", urn),
+		}
+	}
+}
+
+impl<'s> Resolver<'s> {
+	/// Indexes the artefacts of structure data. Artefacts without
+	/// a version are indexed as version `1.0`.
+	pub fn new(data: &'s Data) -> Self {
+		let mut resolver = Self {
+			data,
+			artefacts: HashMap::new(),
+			urns: Vec::new(),
+		};
+
+		macro_rules! index {
+			($($field:ident => $class:ident, $variant:ident, $common:ident);+ $(;)?) => {
+				$(
+					for artefact in data.$field.iter().flatten() {
+						resolver.insert(
+							Class::$class,
+							&artefact.$common,
+							Resolved::$variant(artefact),
+						);
+					}
+				)+
+			};
+		}
+		index! {
+			data_structures => DataStructure, DataStructure, common;
+			metadata_structures => MetadataStructure, MetadataStructure, common;
+			category_schemas => CategoryScheme, CategoryScheme, artefact;
+			concept_schemas => ConceptScheme, ConceptScheme, artefact;
+			concepts => Codelist, Codelist, artefact;
+			geographic_codelists => GeographicCodelist, Codelist, artefact;
+			geo_grid_codelists => GeoGridCodelist, Codelist, artefact;
+			agency_schemes => AgencyScheme, AgencyScheme, artefact;
+			data_provider_schemes => DataProviderScheme, DataProviderScheme, artefact;
+			data_consumer_schemes => DataConsumerScheme, DataConsumerScheme, artefact;
+			metadata_provider_schemes => MetadataProviderScheme, MetadataProviderScheme, artefact;
+			organisation_unit_schemes => OrganisationUnitScheme, OrganizationUnitScheme, artefact;
+			dataflows => Dataflow, Dataflow, artefact;
+			reporting_taxonomies => ReportingTaxonomy, ReportingTaxonomy, artefact;
+			categorisations => Categorisation, Categorization, artefact;
+			data_constraints => DataConstraint, DataConstraint, artefact;
+			metadata_constraints => MetadataConstraint, MetadataConstraint, artefact;
+			custom_type_schemes => CustomTypeScheme, CustomTypeScheme, artefact;
+			vtl_mapping_schemes => VtlMappingScheme, VtlMappingScheme, artefact;
+			name_personalisation_schemes => NamePersonalisationScheme, NamePersonalizationScheme, artefact;
+			ruleset_schemes => RulesetScheme, RulesetScheme, artefact;
+			transformation_schemes => TransformationScheme, TransformationScheme, artefact;
+			user_defined_operator_schemes => UserDefinedOperatorScheme, UserDefinedOperatorsScheme, artefact;
+		}
+
+		let others = [
+			(&data.value_lists, Class::Valuelist),
+			(&data.hierarchies, Class::Hierarchy),
+			(&data.hierarchy_associations, Class::HierarchyAssociation),
+			(&data.metadataflows, Class::Metadataflow),
+			(&data.provision_agreements, Class::ProvisionAgreement),
+			(
+				&data.metadata_provision_agreements,
+				Class::MetadataProvisionAgreement,
+			),
+			(&data.structure_maps, Class::StructureMap),
+			(&data.representation_maps, Class::RepresentationMap),
+			(&data.concept_scheme_maps, Class::ConceptSchemeMap),
+			(&data.category_scheme_maps, Class::CategorySchemeMap),
+			(&data.organisation_scheme_maps, Class::OrganisationSchemeMap),
+			(&data.reporting_taxonomy_maps, Class::ReportingTaxonomyMap),
+			(&data.processes, Class::Process),
+		];
+		for (artefacts, class) in others {
+			for artefact in artefacts.iter().flatten() {
+				resolver.insert(class, artefact, Resolved::Other(artefact));
+			}
+		}
+		resolver
+	}
+
+	fn insert(&mut self, class: Class, artefact: &'s CommonArtefactType, resolved: Resolved<'s>) {
+		let agency_id = artefact.agency_id.as_deref().unwrap_or_default();
+		let version = artefact.version.as_deref().unwrap_or("1.0");
+		let key = (class, agency_id, artefact.id.as_str());
+		self.artefacts
+			.entry(key)
+			.or_default()
+			.push((version, resolved));
+		let urn = Urn {
+			class,
+			agency_id: agency_id.to_owned(),
+			id: artefact.id.clone(),
+			version: version.to_owned(),
+			item_path: Vec::new(),
+		};
+		self.urns.push((urn, resolved));
+	}
+
+	/// Every indexed artefact with its URN, in the order of the structure data.
+	pub(crate) fn artefacts(&self) -> &[(Urn, Resolved<'s>)] {
+		&self.urns
+	}
+
+	/// Resolves a URN into the artefact, item, or component it refers to.
+	///
+	/// Items are resolved within the item scheme of their class (e.g. a
+	/// `Code` within a `Codelist`), and components within their structure
+	/// (e.g. a `Dimension` within a `DataStructure`). Metadata attributes
+	/// are resolved through their item path, within their parents.
+	pub fn resolve(&self, urn: &Urn) -> Option<Resolved<'s>> {
+		let class = maintainable_class(urn.class);
+		let key = (class, urn.agency_id.as_str(), urn.id.as_str());
+		let versions = self.artefacts.get(&key)?;
+		let artefact = latest(versions, &urn.version)?;
+		match urn.item_path.split_first() {
+			None if class == urn.class => Some(artefact),
+			Some((id, path)) if class != urn.class => artefact.child(urn.class, id, path),
+			_ => None,
+		}
+	}
+
+	/// Parses and resolves a URN, as with [`Resolver::resolve`].
+	pub fn resolve_str(&self, urn: &str) -> Result<Resolved<'s>, ResolveError> {
+		let parsed: Urn = urn.parse().map_err(|error| ResolveError::InvalidUrn {
+			urn: urn.to_owned(),
+			error,
+		})?;
+		self.resolve(&parsed)
+			.ok_or(ResolveError::Unresolved(parsed))
+	}
+
+	/// Every reference between the artefacts of the structure data
+	/// which cannot be resolved: the structures of dataflows, the source
+	/// and target of categorisations, the concept identities and the
+	/// enumerations of components, the core representations and parents
+	/// of concept schemes, the parents of codelists, and the dataflows
+	/// and structures of data constraints.
+	pub fn unresolved(&self) -> Vec<ResolveError> {
+		let data = self.data;
+		let mut references: Vec<&str> = Vec::new();
+
+		for dataflow in data.dataflows.iter().flatten() {
+			references.extend(dataflow.structure.as_deref());
+		}
+		for categorisation in data.categorisations.iter().flatten() {
+			references.extend(categorisation.source.as_deref());
+			references.extend(categorisation.target.as_deref());
+		}
+		for structure in data.data_structures.iter().flatten() {
+			let Some(components) = &structure.data_structure_components else {
+				continue;
+			};
+			let dimension_list = &components.dimension_list;
+			for dimension in dimension_list.dimensions.iter().flatten() {
+				references.push(&dimension.concept_identity);
+				references.extend(enumeration(dimension.local_representation.as_ref()));
+			}
+			if let Some(dimension) = &dimension_list.time_dimensions {
+				references.push(&dimension.concept_identity);
+				references.extend(enumeration(Some(&dimension.local_representation)));
+			}
+			let attributes = components.attribute_list.iter();
+			for attribute in attributes.flat_map(|a| a.attributes.iter().flatten()) {
+				references.push(&attribute.concept_identity);
+				references.extend(enumeration(Some(&attribute.local_representation)));
+			}
+			let measures = components.measure_list.iter();
+			for measure in measures.flat_map(|m| m.measures.iter().flatten()) {
+				references.push(&measure.concept_identity);
+				references.extend(enumeration(Some(&measure.local_representation)));
+			}
+		}
+		for structure in data.metadata_structures.iter().flatten() {
+			let components = structure.metadata_structure_components.iter();
+			let lists = components.flat_map(|c| c.metadata_attribute_list.iter());
+			let mut attributes: Vec<_> = lists
+				.flat_map(|l| l.metadata_attributes.iter().flatten())
+				.collect();
+			while let Some(attribute) = attributes.pop() {
+				references.push(&attribute.concept_identity);
+				if let Some(MetadataAttributeRepresentation::Enumeration(e)) =
+					&attribute.local_representation
+				{
+					references.push(&e.enumeration);
+				}
+				attributes.extend(attribute.metadata_attributes.iter().flatten());
+			}
+		}
+		for scheme in data.concept_schemas.iter().flatten() {
+			let representation = scheme.core_representation.as_ref();
+			references.extend(representation.and_then(|r| r.enumeration.as_deref()));
+			references.extend(scheme.parent.as_deref());
+		}
+		let codelists = [
+			&data.concepts,
+			&data.geographic_codelists,
+			&data.geo_grid_codelists,
+		];
+		for codelist in codelists.into_iter().flatten().flatten() {
+			references.extend(codelist.parent.as_deref());
+		}
+		for constraint in data.data_constraints.iter().flatten() {
+			let attachment = constraint.constraint_attachment.as_ref();
+			let dataflows = attachment.and_then(|a| a.dataflows.as_ref());
+			let structures = attachment.and_then(|a| a.data_structures.as_ref());
+			references.extend(
+				dataflows
+					.into_iter()
+					.chain(structures)
+					.flatten()
+					.map(String::as_str),
+			);
+		}
+
+		references
+			.into_iter()
+			.filter_map(|urn| self.resolve_str(urn).err())
+			.collect()
+	}
+}
+
+impl<'s> Resolved<'s> {
+	/// The items of this artefact, if it is an item scheme.
+	pub fn items(&self) -> Option<&'s [Item]> {
+		let items = match self {
+			Self::CategoryScheme(s) => s.items(),
+			Self::ConceptScheme(s) => s.items(),
+			Self::Codelist(s) => s.items(),
+			Self::AgencyScheme(s) => s.items(),
+			Self::DataProviderScheme(s) => s.items(),
+			Self::DataConsumerScheme(s) => s.items(),
+			Self::MetadataProviderScheme(s) => s.items(),
+			Self::OrganizationUnitScheme(s) => s.items(),
+			Self::ReportingTaxonomy(s) => s.items(),
+			Self::CustomTypeScheme(s) => s.items(),
+			Self::VtlMappingScheme(s) => s.items(),
+			Self::NamePersonalizationScheme(s) => s.items(),
+			Self::RulesetScheme(s) => s.items(),
+			Self::TransformationScheme(s) => s.items(),
+			Self::UserDefinedOperatorsScheme(s) => s.items(),
+			_ => None,
+		};
+		items.map(Vec::as_slice)
+	}
+
+	/// The item or component of a class within this artefact,
+	/// given the first ID of its item path and the rest of it.
+	fn child(self, class: Class, id: &str, path: &[String]) -> Option<Self> {
+		if let (Self::MetadataStructure(structure), Class::MetadataAttribute) = (self, class) {
+			let components = structure.metadata_structure_components.as_ref()?;
+			let list = components.metadata_attribute_list.as_ref()?;
+			let mut attributes = list.metadata_attributes.as_deref()?;
+			let mut attribute = attributes.iter().find(|a| a.id == id)?;
+			for id in path {
+				attributes = attribute.metadata_attributes.as_deref()?;
+				attribute = attributes.iter().find(|a| a.id == *id)?;
+			}
+			return Some(Self::MetadataAttribute(attribute));
+		}
+		if !path.is_empty() {
+			return None;
+		}
+
+		let Self::DataStructure(structure) = self else {
+			let items = self.items()?;
+			return items.iter().find(|item| item.id == id).map(Self::Item);
+		};
+		let components = structure.data_structure_components.as_ref()?;
+		let dimension_list = &components.dimension_list;
+		match class {
+			Class::Dimension | Class::MeasureDimension => dimension_list
+				.dimensions
+				.iter()
+				.flatten()
+				.find(|dimension| dimension.id.as_deref() == Some(id))
+				.map(Self::Dimension),
+			Class::TimeDimension => dimension_list
+				.time_dimensions
+				.as_ref()
+				.filter(|dimension| dimension.id.as_deref() == Some(id))
+				.map(Self::TimeDimension),
+			Class::DataAttribute => components
+				.attribute_list
+				.as_ref()?
+				.attributes
+				.iter()
+				.flatten()
+				.find(|attribute| attribute.id == id)
+				.map(Self::Attribute),
+			Class::Measure | Class::PrimaryMeasure => components
+				.measure_list
+				.as_ref()?
+				.measures
+				.iter()
+				.flatten()
+				.find(|measure| measure.id == id)
+				.map(Self::Measure),
+			_ => None,
+		}
+	}
+}
+
+fn enumeration(representation: Option<&LocalRepresentation>) -> Option<&str> {
+	representation?.enumeration.as_deref()
+}
+
+/// The class of the maintainable artefact which contains
+/// the items or components of a class.
+fn maintainable_class(class: Class) -> Class {
+	match class {
+		Class::Agency => Class::AgencyScheme,
+		Class::DataConsumer => Class::DataConsumerScheme,
+		Class::DataProvider => Class::DataProviderScheme,
+		Class::MetadataProvider => Class::MetadataProviderScheme,
+		Class::OrganisationUnit => Class::OrganisationUnitScheme,
+		Class::Code => Class::Codelist,
+		Class::GeoFeatureSetCode => Class::GeographicCodelist,
+		Class::GeoGridCode => Class::GeoGridCodelist,
+		Class::Category => Class::CategoryScheme,
+		Class::ReportingCategory => Class::ReportingTaxonomy,
+		Class::Concept => Class::ConceptScheme,
+		Class::Dimension
+		| Class::MeasureDimension
+		| Class::TimeDimension
+		| Class::DataAttribute
+		| Class::Measure
+		| Class::PrimaryMeasure => Class::DataStructure,
+		Class::MetadataAttribute => Class::MetadataStructure,
+		Class::CustomType => Class::CustomTypeScheme,
+		Class::NamePersonalisation => Class::NamePersonalisationScheme,
+		Class::Ruleset => Class::RulesetScheme,
+		Class::Transformation => Class::TransformationScheme,
+		Class::UserDefinedOperator => Class::UserDefinedOperatorScheme,
+		Class::VtlCodelistMapping | Class::VtlConceptMapping | Class::VtlDataflowMapping => {
+			Class::VtlMappingScheme
+		}
+		class => class,
+	}
+}
+
+/// The artefact of the latest version which matches a version,
+/// which may contain wildcards.
+fn latest<'s>(versions: &[(&str, Resolved<'s>)], pattern: &str) -> Option<Resolved<'s>> {
+	if !pattern.contains(['+', '*']) {
+		let exact = versions.iter().find(|(version, _)| *version == pattern);
+		return exact.map(|(_, artefact)| *artefact);
+	}
+
+	versions
+		.iter()
+		.filter_map(|(version, artefact)| Some((parse_version(version)?, artefact)))
+		.filter(|(version, _)| matches_version(pattern, version))
+		.max_by(|(a, _), (b, _)| a.cmp(b))
+		.map(|(_, artefact)| *artefact)
+}
+
+/// The numeric parts of a stable version (i.e. without
+/// an extension such as `-draft`).
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+	version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Whether a stable version matches a version with wildcards.
+fn matches_version(pattern: &str, version: &[u64]) -> bool {
+	if pattern == "*" {
+		return true;
+	}
+	let parts: Option<Vec<(u64, bool)>> = pattern
+		.split('.')
+		.map(|part| match part.strip_suffix('+') {
+			Some(part) => Some((part.parse().ok()?, true)),
+			None => Some((part.parse().ok()?, false)),
+		})
+		.collect();
+	let Some(parts) = parts.filter(|parts| parts.len() == version.len()) else {
+		return false;
+	};
+	let numbers: Vec<u64> = parts.iter().map(|(number, _)| *number).collect();
+	let wildcard = parts.iter().position(|(_, plus)| *plus);
+	let wildcard = wildcard.unwrap_or(numbers.len());
+	version[..wildcard] == numbers[..wildcard] && version[wildcard..] >= numbers[wildcard..]
+}
@@ -0,0 +1,416 @@
+use crate::primitives::Location;
+use crate::structure::{
+	Categorization, ConstraintAttachment, CoreRepresentation, Dataflow, LocalRepresentation,
+	MetadataAttributeEnumeration,
+};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+const PREFIX: &str = "urn:sdmx:org.sdmx.infomodel.";
+
+/// A uniform resource name (URN) of an SDMX artefact, or of an item
+/// (or component) of an artefact.
+///
+/// A URN can be parsed from a string, and written back as the
+/// same string. It is also (de)serialized as a string.
+///
+/// # Example
+/// ```
+/// use sdmx_json::structure::{Class, Package, Urn};
+///
+/// let urn: Urn = "urn:sdmx:org.sdmx.infomodel.codelist.Code=ESTAT:CL_FREQ(1.0.0).A"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(urn.package(), Package::Codelist);
+/// assert_eq!(urn.class, Class::Code);
+/// assert_eq!(urn.agency_id, "ESTAT");
+/// assert_eq!(urn.id, "CL_FREQ");
+/// assert_eq!(urn.version, "1.0.0");
+/// assert_eq!(urn.item_path, ["A"]);
+/// assert_eq!(
+///     urn.to_string(),
+///     "urn:sdmx:org.sdmx.infomodel.codelist.Code=ESTAT:CL_FREQ(1.0.0).A"
+/// );
+///
+/// let unknown = "urn:sdmx:org.sdmx.infomodel.codelist.Concept=ESTAT:CS(1.0)";
+/// assert!(unknown.parse::<Urn>().is_err());
+/// ```
+#[derive(
+	Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, DeserializeFromStr, SerializeDisplay,
+)]
+pub struct Urn {
+	pub class: Class,
+	/// The ID of the agency maintaining the artefact, which may be
+	/// nested in other agencies (e.g. `SDMX.ESTAT`).
+	pub agency_id: String,
+	/// The ID of the maintainable artefact.
+	pub id: String,
+	/// The version of the maintainable artefact, which may
+	/// contain wildcards when used in a reference (e.g. `1.0+`).
+	pub version: String,
+	/// The IDs of the item (or component) within the maintainable
+	/// artefact, which are more than one for the items of hierarchical
+	/// item schemes. This is empty for a maintainable artefact.
+	pub item_path: Vec<String>,
+}
+
+impl Urn {
+	/// The package of the class of this URN.
+	pub fn package(&self) -> Package {
+		self.class.package()
+	}
+
+	/// Whether this URN refers to a maintainable artefact,
+	/// rather than to one of its items or components.
+	pub fn is_maintainable(&self) -> bool {
+		self.item_path.is_empty()
+	}
+}
+
+impl FromStr for Urn {
+	type Err = ParseUrnError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let error = || ParseUrnError::InvalidSyntax;
+		let (qualified, reference) = s
+			.strip_prefix(PREFIX)
+			.and_then(|s| s.split_once('='))
+			.ok_or_else(error)?;
+		let (package, class) = qualified.split_once('.').ok_or_else(error)?;
+		let package: Package = package.parse()?;
+		let class = Class::ALL
+			.iter()
+			.find(|c| c.package() == package && c.name() == class)
+			.ok_or_else(|| ParseUrnError::UnknownClass {
+				package,
+				class: class.to_owned(),
+			})?;
+
+		let (agency_id, rest) = reference.split_once(':').ok_or_else(error)?;
+		let (id, rest) = rest.split_once('(').ok_or_else(error)?;
+		let (version, path) = rest.split_once(')').ok_or_else(error)?;
+		if agency_id.is_empty() || id.is_empty() || version.is_empty() {
+			return Err(error());
+		}
+		let item_path = match path {
+			"" => Vec::new(),
+			path => path
+				.strip_prefix('.')
+				.ok_or_else(error)?
+				.split('.')
+				.map(|item| match item {
+					"" => Err(error()),
+					item => Ok(item.to_owned()),
+				})
+				.collect::<Result<_, _>>()?,
+		};
+
+		Ok(Self {
+			class: *class,
+			agency_id: agency_id.to_owned(),
+			id: id.to_owned(),
+			version: version.to_owned(),
+			item_path,
+		})
+	}
+}
+
+impl fmt::Display for Urn {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}{}.{}={}:{}({})",
+			PREFIX,
+			self.package(),
+			self.class,
+			self.agency_id,
+			self.id,
+			self.version
+		)?;
+		for item in &self.item_path {
+			write!(f, ".{}", item)?;
+		}
+		Ok(())
+	}
+}
+
+/// An error which can be returned when parsing a URN.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseUrnError {
+	/// The URN is not of the form
+	/// `urn:sdmx:org.sdmx.infomodel.{package}.{class}={agency}:{id}({version})`,
+	/// optionally followed by an item path.
+	InvalidSyntax,
+	/// The package is not a package of the SDMX information model.
+	UnknownPackage(String),
+	/// The class is not a class of its package in the SDMX information model.
+	UnknownClass { package: Package, class: String },
+}
+
+impl fmt::Display for ParseUrnError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidSyntax => f.write_str("invalid URN"),
+			Self::UnknownPackage(package) => write!(f, "unknown package {} in URN", package),
+			Self::UnknownClass { package, class } => {
+				write!(f, "unknown class {} of package {} in URN", class, package)
+			}
+		}
+	}
+}
+
+impl Error for ParseUrnError {}
+
+/// A package of the SDMX information model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Package {
+	Base,
+	Codelist,
+	CategoryScheme,
+	ConceptScheme,
+	DataStructure,
+	MetadataStructure,
+	Process,
+	Registry,
+	StructureMapping,
+	Transformation,
+}
+
+impl Package {
+	const ALL: [Self; 10] = [
+		Self::Base,
+		Self::Codelist,
+		Self::CategoryScheme,
+		Self::ConceptScheme,
+		Self::DataStructure,
+		Self::MetadataStructure,
+		Self::Process,
+		Self::Registry,
+		Self::StructureMapping,
+		Self::Transformation,
+	];
+
+	/// The name of this package, as it appears in a URN.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Base => "base",
+			Self::Codelist => "codelist",
+			Self::CategoryScheme => "categoryscheme",
+			Self::ConceptScheme => "conceptscheme",
+			Self::DataStructure => "datastructure",
+			Self::MetadataStructure => "metadatastructure",
+			Self::Process => "process",
+			Self::Registry => "registry",
+			Self::StructureMapping => "structuremapping",
+			Self::Transformation => "transformation",
+		}
+	}
+}
+
+impl FromStr for Package {
+	type Err = ParseUrnError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.into_iter()
+			.find(|package| package.name() == s)
+			.ok_or_else(|| ParseUrnError::UnknownPackage(s.to_owned()))
+	}
+}
+
+impl fmt::Display for Package {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+macro_rules! classes {
+	($($package:ident => [$($class:ident),+ $(,)?]),+ $(,)?) => {
+		/// A class of the SDMX information model, which can be referred to by a URN.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+		pub enum Class {
+			$($($class,)+)+
+		}
+
+		impl Class {
+			const ALL: &'static [Self] = &[$($(Self::$class,)+)+];
+
+			/// The package this class belongs to.
+			pub fn package(&self) -> Package {
+				match self {
+					$($(Self::$class)|+ => Package::$package,)+
+				}
+			}
+
+			/// The name of this class, as it appears in a URN.
+			pub fn name(&self) -> &'static str {
+				match self {
+					$($(Self::$class => stringify!($class),)+)+
+				}
+			}
+		}
+	};
+}
+
+classes! {
+	Base => [
+		Agency,
+		AgencyScheme,
+		DataConsumer,
+		DataConsumerScheme,
+		DataProvider,
+		DataProviderScheme,
+		MetadataProvider,
+		MetadataProviderScheme,
+		OrganisationUnit,
+		OrganisationUnitScheme,
+	],
+	Codelist => [
+		Code,
+		Codelist,
+		GeographicCodelist,
+		GeoFeatureSetCode,
+		GeoGridCode,
+		GeoGridCodelist,
+		HierarchicalCode,
+		HierarchicalCodelist,
+		Hierarchy,
+		HierarchyAssociation,
+		Level,
+		ValueItem,
+		Valuelist,
+	],
+	CategoryScheme => [
+		Categorisation,
+		Category,
+		CategoryScheme,
+		ReportingCategory,
+		ReportingTaxonomy,
+	],
+	ConceptScheme => [Concept, ConceptScheme],
+	DataStructure => [
+		AttributeDescriptor,
+		DataAttribute,
+		DataStructure,
+		Dataflow,
+		Dimension,
+		DimensionDescriptor,
+		GroupDimensionDescriptor,
+		Measure,
+		MeasureDescriptor,
+		MeasureDimension,
+		PrimaryMeasure,
+		TimeDimension,
+	],
+	MetadataStructure => [
+		MetadataAttribute,
+		MetadataAttributeDescriptor,
+		MetadataSet,
+		MetadataStructure,
+		MetadataTarget,
+		Metadataflow,
+		ReportStructure,
+	],
+	Process => [Process, ProcessStep, Transition],
+	Registry => [
+		AttachmentConstraint,
+		ContentConstraint,
+		DataConstraint,
+		MetadataConstraint,
+		MetadataProvisionAgreement,
+		ProvisionAgreement,
+		Subscription,
+	],
+	StructureMapping => [
+		CategorySchemeMap,
+		ComponentMap,
+		ConceptSchemeMap,
+		DatePatternMap,
+		EpochMap,
+		FrequencyFormatMapping,
+		OrganisationSchemeMap,
+		ReportingTaxonomyMap,
+		RepresentationMap,
+		StructureMap,
+		StructureSet,
+	],
+	Transformation => [
+		CustomType,
+		CustomTypeScheme,
+		NamePersonalisation,
+		NamePersonalisationScheme,
+		Ruleset,
+		RulesetScheme,
+		Transformation,
+		TransformationScheme,
+		UserDefinedOperator,
+		UserDefinedOperatorScheme,
+		VtlCodelistMapping,
+		VtlConceptMapping,
+		VtlDataflowMapping,
+		VtlMappingScheme,
+	],
+}
+
+impl fmt::Display for Class {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl Dataflow {
+	/// The URN of the data structure of this dataflow, if any.
+	pub fn structure_urn(&self) -> Option<Result<Urn, ParseUrnError>> {
+		self.structure.as_deref().map(str::parse)
+	}
+}
+
+impl Categorization {
+	/// The URN of the artefact which is categorized, if any.
+	pub fn source_urn(&self) -> Option<Result<Urn, ParseUrnError>> {
+		self.source.as_deref().map(str::parse)
+	}
+
+	/// The URN of the category of the source, if any.
+	pub fn target_urn(&self) -> Option<Result<Urn, ParseUrnError>> {
+		self.target.as_deref().map(str::parse)
+	}
+}
+
+impl LocalRepresentation {
+	/// The URN of the list of values of this representation, if any.
+	pub fn enumeration_urn(&self) -> Option<Result<Urn, ParseUrnError>> {
+		self.enumeration.as_deref().map(str::parse)
+	}
+}
+
+impl CoreRepresentation {
+	/// The URN of the list of values of this representation, if any.
+	pub fn enumeration_urn(&self) -> Option<Result<Urn, ParseUrnError>> {
+		self.enumeration.as_deref().map(str::parse)
+	}
+}
+
+impl MetadataAttributeEnumeration {
+	/// The URN of the list of values of this representation.
+	pub fn enumeration_urn(&self) -> Result<Urn, ParseUrnError> {
+		self.enumeration.parse()
+	}
+}
+
+impl ConstraintAttachment {
+	/// The URNs of the dataflows this constraint is attached to.
+	pub fn dataflow_urns(&self) -> impl Iterator<Item = Result<Urn, ParseUrnError>> + '_ {
+		self.dataflows.iter().flatten().map(|urn| urn.parse())
+	}
+}
+
+impl Location {
+	/// The URN of this location, unless it is a hyperlink reference.
+	pub fn urn(&self) -> Option<Result<Urn, ParseUrnError>> {
+		match self {
+			Self::Urn(urn) => Some(urn.parse()),
+			Self::Href(_) => None,
+		}
+	}
+}
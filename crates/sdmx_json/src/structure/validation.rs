@@ -0,0 +1,277 @@
+use crate::structure::{
+	AttributeRelationship, Data, DataStructure, ResolveError, Resolved, Resolver, StructureMessage,
+	Urn,
+};
+use std::collections::HashSet;
+use std::fmt;
+use std::ptr;
+
+/// An inconsistency within the artefacts of a structure message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+	/// A reference to an artefact, item, or component
+	/// which is not in the structure message.
+	DanglingReference(ResolveError),
+	/// Several artefacts of the same class have
+	/// the same agency, ID and version.
+	DuplicateArtefact(Urn),
+	/// Several items of an item scheme have the same ID. Only the items
+	/// at the top level of a scheme are compared, as nested items (e.g. the
+	/// subcategories of a category) are not part of the model; items of
+	/// a hierarchy defined by parent IDs (e.g. codes) are all at that level.
+	DuplicateItem { scheme: Urn, id: String },
+	/// Several dimensions of a data structure have the same position.
+	DuplicateDimensionPosition { structure: Urn, position: usize },
+	/// No dimension of a data structure has a position,
+	/// between the first and the last positions.
+	MissingDimensionPosition { structure: Urn, position: usize },
+	/// An attribute is related to a dimension
+	/// which is not in its data structure.
+	UnknownDimension {
+		structure: Urn,
+		attribute: String,
+		dimension: String,
+	},
+	/// An attribute is related to a group which is not in its data structure.
+	UnknownGroup {
+		structure: Urn,
+		attribute: String,
+		group: String,
+	},
+	/// A codelist or a concept scheme is its own parent,
+	/// directly or through the parents of its parent.
+	ParentCycle(Urn),
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::DanglingReference(error) => error.fmt(f),
+			Self::DuplicateArtefact(urn) => write!(f, "duplicate artefact: {}", urn),
+			Self::DuplicateItem { scheme, id } => {
+				write!(f, "duplicate item {} in {}", id, scheme)
+			}
+			Self::DuplicateDimensionPosition {
+				structure,
+				position,
+			} => write!(
+				f,
+				"duplicate dimension position {} in {}",
+				position, structure
+			),
+			Self::MissingDimensionPosition {
+				structure,
+				position,
+			} => write!(
+				f,
+				"missing dimension position {} in {}",
+				position, structure
+			),
+			Self::UnknownDimension {
+				structure,
+				attribute,
+				dimension,
+			} => write!(
+				f,
+				"attribute {} is related to unknown dimension {} in {}",
+				attribute, dimension, structure
+			),
+			Self::UnknownGroup {
+				structure,
+				attribute,
+				group,
+			} => write!(
+				f,
+				"attribute {} is related to unknown group {} in {}",
+				attribute, group, structure
+			),
+			Self::ParentCycle(urn) => write!(f, "cycle in the parents of {}", urn),
+		}
+	}
+}
+
+impl StructureMessage {
+	/// Checks that the artefacts of this message are consistent with
+	/// each other, as with [`Data::validate`].
+	pub fn validate(&self) -> Vec<Diagnostic> {
+		self.data.as_ref().map(Data::validate).unwrap_or_default()
+	}
+}
+
+impl Data {
+	/// Checks that the artefacts of this structure data are consistent
+	/// with each other, and returns every inconsistency found:
+	///
+	/// - references which cannot be resolved (see [`Resolver::unresolved`]),
+	/// - artefacts of the same class with the same agency, ID and version,
+	/// - items of an item scheme with the same ID (at the top level
+	///   of the scheme, see [`Diagnostic::DuplicateItem`]),
+	/// - dimensions of a data structure with the same position, or
+	///   gaps between their positions (which start at 0 or 1),
+	/// - attributes related to dimensions or groups which are not in
+	///   their data structure,
+	/// - codelists and concept schemes which are their own parent.
+	///
+	/// # Example
+	/// ```no_run
+	/// use std::str::FromStr;
+	/// use std::fs::read_to_string;
+	/// use sdmx_json::structure::StructureMessage;
+	///
+	/// fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
+	///     let file = read_to_string("sdmx-structure.json")?;
+	///     let message = StructureMessage::from_str(file.as_str())?;
+	///     for diagnostic in message.validate() {
+	///         println!("{}", diagnostic);
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn validate(&self) -> Vec<Diagnostic> {
+		let resolver = Resolver::new(self);
+		let mut diagnostics: Vec<_> = resolver
+			.unresolved()
+			.into_iter()
+			.map(Diagnostic::DanglingReference)
+			.collect();
+
+		let mut urns = HashSet::new();
+		let mut duplicates = HashSet::new();
+		for (urn, artefact) in resolver.artefacts() {
+			if !urns.insert(urn) && duplicates.insert(urn) {
+				diagnostics.push(Diagnostic::DuplicateArtefact(urn.clone()));
+			}
+
+			let mut ids = HashSet::new();
+			let mut duplicate_ids = HashSet::new();
+			for item in artefact.items().unwrap_or_default() {
+				if !ids.insert(&item.id) && duplicate_ids.insert(&item.id) {
+					diagnostics.push(Diagnostic::DuplicateItem {
+						scheme: urn.clone(),
+						id: item.id.clone(),
+					});
+				}
+			}
+
+			if let Resolved::DataStructure(structure) = artefact {
+				validate_data_structure(urn, structure, &mut diagnostics);
+			}
+			if is_in_parent_cycle(&resolver, *artefact) {
+				diagnostics.push(Diagnostic::ParentCycle(urn.clone()));
+			}
+		}
+		diagnostics
+	}
+}
+
+fn validate_data_structure(
+	urn: &Urn,
+	structure: &DataStructure,
+	diagnostics: &mut Vec<Diagnostic>,
+) {
+	let Some(components) = &structure.data_structure_components else {
+		return;
+	};
+	let dimension_list = &components.dimension_list;
+	let dimensions = dimension_list.dimensions.as_deref().unwrap_or_default();
+
+	let mut positions: Vec<usize> = dimensions.iter().map(|d| d.position).collect();
+	positions.sort_unstable();
+	for pair in positions.windows(2) {
+		match pair[1] - pair[0] {
+			0 => {
+				let duplicate = Diagnostic::DuplicateDimensionPosition {
+					structure: urn.clone(),
+					position: pair[0],
+				};
+				if diagnostics.last() != Some(&duplicate) {
+					diagnostics.push(duplicate);
+				}
+			}
+			_ => {
+				for position in pair[0] + 1..pair[1] {
+					diagnostics.push(Diagnostic::MissingDimensionPosition {
+						structure: urn.clone(),
+						position,
+					});
+				}
+			}
+		}
+	}
+	if let Some(first) = positions.first().filter(|first| **first > 1) {
+		for position in 1..*first {
+			diagnostics.push(Diagnostic::MissingDimensionPosition {
+				structure: urn.clone(),
+				position,
+			});
+		}
+	}
+
+	let time_dimension = dimension_list.time_dimensions.as_ref();
+	let dimension_ids: HashSet<&str> = dimensions
+		.iter()
+		.filter_map(|d| d.id.as_deref())
+		.chain(time_dimension.and_then(|d| d.id.as_deref()))
+		.collect();
+	let group_ids: HashSet<&str> = components
+		.groups
+		.iter()
+		.flatten()
+		.map(|g| g.id.as_str())
+		.collect();
+
+	let attributes = components.attribute_list.iter();
+	for attribute in attributes.flat_map(|a| a.attributes.iter().flatten()) {
+		match &attribute.attribute_relationship {
+			AttributeRelationship::Dimensions(relationship) => {
+				let related = relationship.dimensions.iter().flatten();
+				for dimension in related.filter(|d| !dimension_ids.contains(d.as_str())) {
+					diagnostics.push(Diagnostic::UnknownDimension {
+						structure: urn.clone(),
+						attribute: attribute.id.clone(),
+						dimension: dimension.clone(),
+					});
+				}
+			}
+			AttributeRelationship::Groups(relationship) => {
+				if !group_ids.contains(relationship.group.as_str()) {
+					diagnostics.push(Diagnostic::UnknownGroup {
+						structure: urn.clone(),
+						attribute: attribute.id.clone(),
+						group: relationship.group.clone(),
+					});
+				}
+			}
+			AttributeRelationship::DataFlow(_) | AttributeRelationship::Observations(_) => {}
+		}
+	}
+}
+
+/// Whether a codelist or a concept scheme is reached again
+/// by following the parents of its parent.
+fn is_in_parent_cycle(resolver: &Resolver<'_>, artefact: Resolved<'_>) -> bool {
+	let parent = |artefact: Resolved<'_>| match artefact {
+		Resolved::Codelist(codelist) => codelist.parent.clone(),
+		Resolved::ConceptScheme(scheme) => scheme.parent.clone(),
+		_ => None,
+	};
+	// artefacts are compared by identity, as distinct
+	// artefacts of a message may be equal
+	let is_same = |other: Resolved<'_>| match (artefact, other) {
+		(Resolved::Codelist(a), Resolved::Codelist(b)) => ptr::eq(a, b),
+		(Resolved::ConceptScheme(a), Resolved::ConceptScheme(b)) => ptr::eq(a, b),
+		_ => false,
+	};
+
+	let mut current = artefact;
+	for _ in 0..resolver.artefacts().len() {
+		let Some(next) = parent(current).and_then(|urn| resolver.resolve_str(&urn).ok()) else {
+			return false;
+		};
+		if is_same(next) {
+			return true;
+		}
+		current = next;
+	}
+	false
+}
@@ -0,0 +1,925 @@
+use crate::structure::TimeDataType;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A time period, as any of the formats allowed by a [`TimeDataType`].
+///
+/// A time period can be parsed from a string, and written back
+/// as the same string. It is also (de)serialized as a string.
+///
+/// Time periods are ordered by when they start, and then by the
+/// latest end first, so that e.g. a year comes before its first quarter.
+/// A date-time without a UTC offset is treated as being in UTC
+/// when compared to other time periods.
+///
+/// # Example
+/// ```
+/// use sdmx_json::time::{Date, TimePeriod};
+///
+/// let quarter: TimePeriod = "2020-Q2".parse().unwrap();
+/// assert_eq!(quarter.start().date(), Date::new(2020, 4, 1).unwrap());
+/// assert_eq!(quarter.end().date(), Date::new(2020, 7, 1).unwrap());
+///
+/// let mut periods: Vec<TimePeriod> = ["2020-Q2", "2020-03", "2020"]
+///     .into_iter()
+///     .map(|s| s.parse().unwrap())
+///     .collect();
+/// periods.sort();
+/// assert_eq!(periods[0].to_string(), "2020");
+/// assert_eq!(periods[1].to_string(), "2020-03");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub enum TimePeriod {
+	/// A gregorian year (e.g. `2020`).
+	GregorianYear(i32),
+	/// A gregorian month (e.g. `2020-01`).
+	GregorianYearMonth { year: i32, month: u8 },
+	/// A gregorian day (e.g. `2020-01-15`).
+	GregorianDay(Date),
+	/// A period of a reporting year (e.g. `2020-Q1`), where the
+	/// reporting year begins on a given day (January 1 by default).
+	/// The start day is not part of the string of a reporting period,
+	/// and is instead set with [`TimePeriod::with_start_day`].
+	Reporting {
+		year: i32,
+		period: ReportingPeriod,
+		start_day: MonthDay,
+	},
+	/// A distinct point in time (e.g. `2020-01-15T12:30:00Z`).
+	DateTime(DateTime),
+	/// A start and a duration (e.g. `2020-01-15T12:30:00/PT6H`).
+	TimeRange {
+		start: RangeStart,
+		duration: Duration,
+	},
+}
+
+impl TimePeriod {
+	/// The instant this time period starts on.
+	pub fn start(&self) -> DateTime {
+		match *self {
+			Self::GregorianYear(year) => midnight(Date::first_of(year, 1)),
+			Self::GregorianYearMonth { year, month } => midnight(Date::first_of(year, month)),
+			Self::GregorianDay(date) => midnight(date),
+			Self::Reporting {
+				year,
+				period,
+				start_day,
+			} => midnight(period.start(start_day.in_year(year))),
+			Self::DateTime(date_time) => date_time,
+			Self::TimeRange { start, .. } => start.date_time(),
+		}
+	}
+
+	/// The instant this time period ends on, which is exclusive (i.e. the
+	/// start of the next period). A date-time ends on the instant it starts.
+	pub fn end(&self) -> DateTime {
+		match *self {
+			Self::GregorianYear(year) => midnight(Date::first_of(year + 1, 1)),
+			Self::GregorianYearMonth { year, month } => {
+				midnight(Date::first_of(year, month).add_months(1))
+			}
+			Self::GregorianDay(date) => midnight(date.add_days(1)),
+			Self::Reporting {
+				year,
+				period,
+				start_day,
+			} => midnight(period.end(start_day.in_year(year))),
+			Self::DateTime(date_time) => date_time,
+			Self::TimeRange { start, duration } => duration.add_to(start.date_time()),
+		}
+	}
+
+	/// The most specific time data type of this time period.
+	pub const fn data_type(&self) -> TimeDataType {
+		match self {
+			Self::GregorianYear(_) => TimeDataType::GregorianYear,
+			Self::GregorianYearMonth { .. } => TimeDataType::GregorianYearMonth,
+			Self::GregorianDay(_) => TimeDataType::GregorianDay,
+			Self::Reporting { period, .. } => match period {
+				ReportingPeriod::Year => TimeDataType::ReportingYear,
+				ReportingPeriod::Semester(_) => TimeDataType::ReportingSemester,
+				ReportingPeriod::Trimester(_) => TimeDataType::ReportingTrimester,
+				ReportingPeriod::Quarter(_) => TimeDataType::ReportingQuarter,
+				ReportingPeriod::Month(_) => TimeDataType::ReportingMonth,
+				ReportingPeriod::Week(_) => TimeDataType::ReportingWeek,
+				ReportingPeriod::Day(_) => TimeDataType::ReportingDay,
+			},
+			Self::DateTime(_) => TimeDataType::DateTime,
+			Self::TimeRange { .. } => TimeDataType::TimeRange,
+		}
+	}
+
+	/// Whether this time period is valid for a time data type,
+	/// including the types which combine other types (e.g. a
+	/// `GregorianDay` is also a `BasicTimePeriod`).
+	pub const fn is_of_type(&self, data_type: TimeDataType) -> bool {
+		let own = self.data_type();
+		match data_type {
+			TimeDataType::ObservationalTimePeriod => true,
+			TimeDataType::StandardTimePeriod => !matches!(own, TimeDataType::TimeRange),
+			TimeDataType::BasicTimePeriod => {
+				own.is_gregorian() || matches!(own, TimeDataType::DateTime)
+			}
+			TimeDataType::GregorianTimePeriod => own.is_gregorian(),
+			TimeDataType::ReportingTimePeriod => own.is_reporting(),
+			_ => own as u8 == data_type as u8,
+		}
+	}
+
+	/// Sets the day a reporting year begins on, which only
+	/// applies to reporting periods.
+	///
+	/// Returns `None` if the week or the day of a reporting period does
+	/// not exist within the reporting year starting on that day (e.g.
+	/// `2020-W53` when the reporting year starts on July 1).
+	pub fn with_start_day(self, day: MonthDay) -> Option<Self> {
+		match self {
+			Self::Reporting { year, period, .. } => {
+				period
+					.is_valid(day.in_year(year))
+					.then_some(Self::Reporting {
+						year,
+						period,
+						start_day: day,
+					})
+			}
+			other => Some(other),
+		}
+	}
+
+	/// Orders the kinds of time periods, to break ties between
+	/// different kinds of time periods with the same start and end.
+	const fn rank(&self) -> u8 {
+		match self {
+			Self::GregorianYear(_) => 0,
+			Self::GregorianYearMonth { .. } => 1,
+			Self::GregorianDay(_) => 2,
+			Self::Reporting { .. } => 3,
+			Self::DateTime(_) => 4,
+			Self::TimeRange { .. } => 5,
+		}
+	}
+}
+
+impl Ord for TimePeriod {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.start()
+			.cmp(&other.start())
+			.then_with(|| other.end().cmp(&self.end()))
+			.then_with(|| self.rank().cmp(&other.rank()))
+			.then_with(|| match (self, other) {
+				(
+					Self::Reporting {
+						period, start_day, ..
+					},
+					Self::Reporting {
+						period: other_period,
+						start_day: other_start_day,
+						..
+					},
+				) => (period, start_day).cmp(&(other_period, other_start_day)),
+				(
+					Self::TimeRange { start, duration },
+					Self::TimeRange {
+						start: other_start,
+						duration: other_duration,
+					},
+				) => (start, duration).cmp(&(other_start, other_duration)),
+				// Any other kind of time period is fully
+				// determined by when it starts and ends.
+				_ => Ordering::Equal,
+			})
+	}
+}
+
+impl PartialOrd for TimePeriod {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl FromStr for TimePeriod {
+	type Err = ParseTimePeriodError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let error = ParseTimePeriodError;
+		if let Some((start, duration)) = s.split_once('/') {
+			return Ok(Self::TimeRange {
+				start: start.parse()?,
+				duration: duration.parse()?,
+			});
+		}
+		if s.len() > 10 {
+			return s.parse().map(Self::DateTime);
+		}
+
+		let (year, rest) = match (s.get(..4), s.get(4..)) {
+			(Some(year), Some("")) => (year, None),
+			(Some(year), Some(rest)) => (year, Some(rest.strip_prefix('-').ok_or(error)?)),
+			_ => return Err(error),
+		};
+		let year = parse_digits(year, 4)? as i32;
+		let Some(rest) = rest else {
+			return Ok(Self::GregorianYear(year));
+		};
+
+		if rest.starts_with(|c: char| c.is_ascii_digit()) {
+			return match rest.len() {
+				2 => Ok(Self::GregorianYearMonth {
+					year,
+					month: parse_month(rest)?,
+				}),
+				_ => s.parse().map(Self::GregorianDay),
+			};
+		}
+
+		let period = ReportingPeriod::parse(rest).ok_or(error)?;
+		let start_day = MonthDay::default();
+		if !period.is_valid(start_day.in_year(year)) {
+			return Err(error);
+		}
+		Ok(Self::Reporting {
+			year,
+			period,
+			start_day,
+		})
+	}
+}
+
+impl fmt::Display for TimePeriod {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::GregorianYear(year) => write!(f, "{:04}", year),
+			Self::GregorianYearMonth { year, month } => write!(f, "{:04}-{:02}", year, month),
+			Self::GregorianDay(date) => date.fmt(f),
+			Self::Reporting { year, period, .. } => write!(f, "{:04}-{}", year, period),
+			Self::DateTime(date_time) => date_time.fmt(f),
+			Self::TimeRange { start, duration } => write!(f, "{}/{}", start, duration),
+		}
+	}
+}
+
+/// A period within a reporting year, where the number of
+/// the period starts at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReportingPeriod {
+	/// The whole reporting year (`A1`).
+	Year,
+	/// A half of a reporting year (`S1` to `S2`).
+	Semester(u8),
+	/// A third of a reporting year (`T1` to `T3`).
+	Trimester(u8),
+	/// A quarter of a reporting year (`Q1` to `Q4`).
+	Quarter(u8),
+	/// A month of a reporting year (`M01` to `M12`).
+	Month(u8),
+	/// A week of a reporting year (`W01` to `W53`), where the first
+	/// week is the one with the first Thursday of the reporting year.
+	Week(u8),
+	/// A day of a reporting year (`D001` to `D366`).
+	Day(u16),
+}
+
+impl ReportingPeriod {
+	fn parse(s: &str) -> Option<Self> {
+		let (kind, number) = (s.get(..1)?, s.get(1..)?);
+		let number = |digits| parse_digits(number, digits).ok();
+		let in_range = |max, digits| number(digits).filter(|n| (1..=max).contains(n));
+		match kind {
+			"A" => in_range(1, 1).map(|_| Self::Year),
+			"S" => in_range(2, 1).map(|n| Self::Semester(n as u8)),
+			"T" => in_range(3, 1).map(|n| Self::Trimester(n as u8)),
+			"Q" => in_range(4, 1).map(|n| Self::Quarter(n as u8)),
+			"M" => in_range(12, 2).map(|n| Self::Month(n as u8)),
+			"W" => in_range(53, 2).map(|n| Self::Week(n as u8)),
+			"D" => in_range(366, 3).map(|n| Self::Day(n as u16)),
+			_ => None,
+		}
+	}
+
+	/// Whether a week or a day exists within the reporting year,
+	/// which may only have 52 weeks, or 365 days.
+	fn is_valid(&self, year_start: Date) -> bool {
+		let next_year_start = year_start.add_months(12);
+		match *self {
+			Self::Week(week) => {
+				let weeks =
+					(first_week(next_year_start).days() - first_week(year_start).days()) / 7;
+				i64::from(week) <= weeks
+			}
+			Self::Day(day) => i64::from(day) <= next_year_start.days() - year_start.days(),
+			_ => true,
+		}
+	}
+
+	fn start(&self, year_start: Date) -> Date {
+		match *self {
+			Self::Year => year_start,
+			Self::Semester(n) => year_start.add_months(6 * (u32::from(n) - 1)),
+			Self::Trimester(n) => year_start.add_months(4 * (u32::from(n) - 1)),
+			Self::Quarter(n) => year_start.add_months(3 * (u32::from(n) - 1)),
+			Self::Month(n) => year_start.add_months(u32::from(n) - 1),
+			Self::Week(n) => first_week(year_start).add_days(7 * (i64::from(n) - 1)),
+			Self::Day(n) => year_start.add_days(i64::from(n) - 1),
+		}
+	}
+
+	fn end(&self, year_start: Date) -> Date {
+		let start = self.start(year_start);
+		match self {
+			Self::Year => start.add_months(12),
+			Self::Semester(_) => start.add_months(6),
+			Self::Trimester(_) => start.add_months(4),
+			Self::Quarter(_) => start.add_months(3),
+			Self::Month(_) => start.add_months(1),
+			Self::Week(_) => start.add_days(7),
+			Self::Day(_) => start.add_days(1),
+		}
+	}
+}
+
+impl fmt::Display for ReportingPeriod {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Year => f.write_str("A1"),
+			Self::Semester(n) => write!(f, "S{}", n),
+			Self::Trimester(n) => write!(f, "T{}", n),
+			Self::Quarter(n) => write!(f, "Q{}", n),
+			Self::Month(n) => write!(f, "M{:02}", n),
+			Self::Week(n) => write!(f, "W{:02}", n),
+			Self::Day(n) => write!(f, "D{:03}", n),
+		}
+	}
+}
+
+/// The Monday of the first week of a reporting year,
+/// which is the week with its first Thursday.
+fn first_week(year_start: Date) -> Date {
+	match year_start.weekday() {
+		weekday @ 0..=3 => year_start.add_days(-i64::from(weekday)),
+		weekday => year_start.add_days(7 - i64::from(weekday)),
+	}
+}
+
+/// A day of a year which is not specific to any year, written
+/// as `--MM-DD` (e.g. the start day of a reporting year).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MonthDay {
+	month: u8,
+	day: u8,
+}
+
+impl MonthDay {
+	/// Returns `None` if the day does not exist in the month
+	/// of a leap year.
+	pub fn new(month: u8, day: u8) -> Option<Self> {
+		((1..=12).contains(&month) && (1..=days_in_month(2000, month)).contains(&day))
+			.then_some(Self { month, day })
+	}
+
+	pub const fn month(&self) -> u8 {
+		self.month
+	}
+
+	pub const fn day(&self) -> u8 {
+		self.day
+	}
+
+	/// The date of this day in a given year. February 29
+	/// falls on February 28 outside of leap years.
+	fn in_year(&self, year: i32) -> Date {
+		Date {
+			year,
+			month: self.month,
+			day: self.day.min(days_in_month(year, self.month)),
+		}
+	}
+}
+
+impl Default for MonthDay {
+	fn default() -> Self {
+		Self { month: 1, day: 1 }
+	}
+}
+
+impl FromStr for MonthDay {
+	type Err = ParseTimePeriodError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (month, day) = s
+			.strip_prefix("--")
+			.and_then(|s| s.split_once('-'))
+			.ok_or(ParseTimePeriodError)?;
+		Self::new(parse_month(month)?, parse_digits(day, 2)? as u8).ok_or(ParseTimePeriodError)
+	}
+}
+
+impl fmt::Display for MonthDay {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "--{:02}-{:02}", self.month, self.day)
+	}
+}
+
+/// A day of the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+	year: i32,
+	month: u8,
+	day: u8,
+}
+
+impl Date {
+	/// Returns `None` if the day does not exist in the month.
+	pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+		((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day))
+			.then_some(Self { year, month, day })
+	}
+
+	pub const fn year(&self) -> i32 {
+		self.year
+	}
+
+	pub const fn month(&self) -> u8 {
+		self.month
+	}
+
+	pub const fn day(&self) -> u8 {
+		self.day
+	}
+
+	/// The day of the week, from 0 (Monday) to 6 (Sunday).
+	pub fn weekday(&self) -> u8 {
+		// January 1, 1970 was a Thursday.
+		(self.days() + 3).rem_euclid(7) as u8
+	}
+
+	const fn first_of(year: i32, month: u8) -> Self {
+		Self {
+			year,
+			month,
+			day: 1,
+		}
+	}
+
+	/// Adds a number of months, keeping the day of the month
+	/// unless it does not exist in the resulting month.
+	fn add_months(&self, months: u32) -> Self {
+		let months = i64::from(self.month) - 1 + i64::from(months);
+		let year = self.year + months.div_euclid(12) as i32;
+		let month = months.rem_euclid(12) as u8 + 1;
+		Self {
+			year,
+			month,
+			day: self.day.min(days_in_month(year, month)),
+		}
+	}
+
+	fn add_days(&self, days: i64) -> Self {
+		Self::from_days(self.days() + days)
+	}
+
+	/// The number of days since January 1, 1970
+	/// (after Howard Hinnant's algorithm).
+	fn days(&self) -> i64 {
+		let month = i64::from(self.month);
+		let year = i64::from(self.year) - i64::from(month <= 2);
+		let era = year.div_euclid(400);
+		let year_of_era = year - era * 400;
+		let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+		let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(self.day) - 1;
+		let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+		era * 146_097 + day_of_era - 719_468
+	}
+
+	/// The inverse of [`Date::days`].
+	fn from_days(days: i64) -> Self {
+		let days = days + 719_468;
+		let era = days.div_euclid(146_097);
+		let day_of_era = days - era * 146_097;
+		let year_of_era =
+			(day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+		let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+		let shifted_month = (5 * day_of_year + 2) / 153;
+		let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+		let month = if shifted_month < 10 {
+			shifted_month + 3
+		} else {
+			shifted_month - 9
+		};
+		Self {
+			year: (year_of_era + era * 400 + i64::from(month <= 2)) as i32,
+			month: month as u8,
+			day: day as u8,
+		}
+	}
+}
+
+impl FromStr for Date {
+	type Err = ParseTimePeriodError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = s.split('-');
+		let (Some(year), Some(month), Some(day), None) =
+			(parts.next(), parts.next(), parts.next(), parts.next())
+		else {
+			return Err(ParseTimePeriodError);
+		};
+		let year = parse_digits(year, 4)? as i32;
+		let day = parse_digits(day, 2)? as u8;
+		Self::new(year, parse_month(month)?, day).ok_or(ParseTimePeriodError)
+	}
+}
+
+impl fmt::Display for Date {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+	}
+}
+
+/// A date and a time of day, with an optional offset from UTC.
+///
+/// Date-times are ordered by the instant they refer to (assuming UTC
+/// if they have no offset), and then by their offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateTime {
+	date: Date,
+	hour: u8,
+	minute: u8,
+	second: u8,
+	nanosecond: u32,
+	/// The offset from UTC, in minutes.
+	offset: Option<i16>,
+}
+
+impl DateTime {
+	/// Returns `None` if the time of day is out of range.
+	pub fn new(date: Date, hour: u8, minute: u8, second: u8) -> Option<Self> {
+		(hour < 24 && minute < 60 && second < 60).then_some(Self {
+			date,
+			hour,
+			minute,
+			second,
+			nanosecond: 0,
+			offset: None,
+		})
+	}
+
+	/// Sets the fraction of the second, which is
+	/// `None` if it is not less than a second.
+	pub fn with_nanosecond(self, nanosecond: u32) -> Option<Self> {
+		(nanosecond < 1_000_000_000).then_some(Self { nanosecond, ..self })
+	}
+
+	/// Sets the offset from UTC in minutes, which is `None`
+	/// if it is not less than a day.
+	pub fn with_offset(self, offset: i16) -> Option<Self> {
+		(offset.abs() < 24 * 60).then_some(Self {
+			offset: Some(offset),
+			..self
+		})
+	}
+
+	pub const fn date(&self) -> Date {
+		self.date
+	}
+
+	pub const fn hour(&self) -> u8 {
+		self.hour
+	}
+
+	pub const fn minute(&self) -> u8 {
+		self.minute
+	}
+
+	pub const fn second(&self) -> u8 {
+		self.second
+	}
+
+	pub const fn nanosecond(&self) -> u32 {
+		self.nanosecond
+	}
+
+	/// The offset from UTC in minutes, if any.
+	pub const fn offset(&self) -> Option<i16> {
+		self.offset
+	}
+
+	/// The number of seconds since January 1, 1970 at midnight UTC
+	/// (ignoring the nanoseconds), assuming UTC if there is no offset.
+	pub fn unix_timestamp(&self) -> i64 {
+		let seconds =
+			i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second)
+				- i64::from(self.offset.unwrap_or_default()) * 60;
+		self.date.days() * 86_400 + seconds
+	}
+
+	/// Adds a number of seconds, keeping the same offset.
+	fn add_seconds(&self, seconds: i64) -> Self {
+		let seconds =
+			self.unix_timestamp() + i64::from(self.offset.unwrap_or_default()) * 60 + seconds;
+		let time = seconds.rem_euclid(86_400);
+		Self {
+			date: Date::from_days(seconds.div_euclid(86_400)),
+			hour: (time / 3600) as u8,
+			minute: (time % 3600 / 60) as u8,
+			second: (time % 60) as u8,
+			..*self
+		}
+	}
+}
+
+impl Ord for DateTime {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(self.unix_timestamp(), self.nanosecond, self.offset).cmp(&(
+			other.unix_timestamp(),
+			other.nanosecond,
+			other.offset,
+		))
+	}
+}
+
+impl PartialOrd for DateTime {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl FromStr for DateTime {
+	type Err = ParseTimePeriodError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let error = ParseTimePeriodError;
+		let (date, time) = s.split_once('T').ok_or(error)?;
+
+		let (time, offset) = match time.find(['Z', '+', '-']) {
+			Some(position) => time.split_at(position),
+			None => (time, ""),
+		};
+		let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+		let mut parts = time.split(':');
+		let (Some(hour), Some(minute), Some(second), None) =
+			(parts.next(), parts.next(), parts.next(), parts.next())
+		else {
+			return Err(error);
+		};
+
+		let date_time = Self::new(
+			date.parse()?,
+			parse_digits(hour, 2)? as u8,
+			parse_digits(minute, 2)? as u8,
+			parse_digits(second, 2)? as u8,
+		)
+		.ok_or(error)?;
+		let date_time = match fraction {
+			"" if s.contains('.') => return Err(error),
+			"" => date_time,
+			fraction if fraction.len() <= 9 => {
+				let nanosecond =
+					parse_digits(fraction, fraction.len())? * 10u32.pow(9 - fraction.len() as u32);
+				date_time.with_nanosecond(nanosecond).ok_or(error)?
+			}
+			_ => return Err(error),
+		};
+		match offset {
+			"" => Ok(date_time),
+			"Z" => date_time.with_offset(0).ok_or(error),
+			offset => {
+				let (sign, offset) = offset.split_at(1);
+				let (hours, minutes) = offset.split_once(':').ok_or(error)?;
+				let minutes = (parse_digits(hours, 2)? * 60 + parse_digits(minutes, 2)?) as i16;
+				let minutes = match sign {
+					"+" => minutes,
+					"-" => -minutes,
+					_ => return Err(error),
+				};
+				date_time.with_offset(minutes).ok_or(error)
+			}
+		}
+	}
+}
+
+impl fmt::Display for DateTime {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{}T{:02}:{:02}:{:02}",
+			self.date, self.hour, self.minute, self.second
+		)?;
+		if self.nanosecond > 0 {
+			let fraction = format!("{:09}", self.nanosecond);
+			write!(f, ".{}", fraction.trim_end_matches('0'))?;
+		}
+		match self.offset {
+			None => Ok(()),
+			Some(0) => f.write_str("Z"),
+			Some(offset) => {
+				let sign = if offset < 0 { '-' } else { '+' };
+				let offset = offset.unsigned_abs();
+				write!(f, "{}{:02}:{:02}", sign, offset / 60, offset % 60)
+			}
+		}
+	}
+}
+
+/// The start of a time range, which is either a day
+/// (starting at midnight), or a date-time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RangeStart {
+	Date(Date),
+	DateTime(DateTime),
+}
+
+impl RangeStart {
+	pub fn date_time(&self) -> DateTime {
+		match *self {
+			Self::Date(date) => midnight(date),
+			Self::DateTime(date_time) => date_time,
+		}
+	}
+}
+
+impl FromStr for RangeStart {
+	type Err = ParseTimePeriodError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.contains('T') {
+			true => s.parse().map(Self::DateTime),
+			false => s.parse().map(Self::Date),
+		}
+	}
+}
+
+impl fmt::Display for RangeStart {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Date(date) => date.fmt(f),
+			Self::DateTime(date_time) => date_time.fmt(f),
+		}
+	}
+}
+
+/// An ISO 8601 duration (e.g. `P1Y2M`, `PT6H`, or `P2W`).
+///
+/// The years and months are added as calendar months (keeping the
+/// day of the month if possible), before the weeks, the days, and
+/// the time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Duration {
+	pub years: u32,
+	pub months: u32,
+	pub weeks: u32,
+	pub days: u32,
+	pub hours: u32,
+	pub minutes: u32,
+	pub seconds: u32,
+}
+
+impl Duration {
+	/// The instant after this duration from a given start.
+	pub fn add_to(&self, start: DateTime) -> DateTime {
+		let date = start
+			.date
+			.add_months(self.years * 12 + self.months)
+			.add_days(i64::from(self.weeks) * 7 + i64::from(self.days));
+		let seconds =
+			i64::from(self.hours) * 3600 + i64::from(self.minutes) * 60 + i64::from(self.seconds);
+		DateTime { date, ..start }.add_seconds(seconds)
+	}
+}
+
+impl FromStr for Duration {
+	type Err = ParseTimePeriodError;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let error = ParseTimePeriodError;
+		let rest = s.strip_prefix('P').ok_or(error)?;
+		let (date, time) = match rest.split_once('T') {
+			Some((_, "")) => return Err(error),
+			Some((date, time)) => (date, time),
+			None => (rest, ""),
+		};
+		if date.is_empty() && time.is_empty() {
+			return Err(error);
+		}
+
+		let mut duration = Self::default();
+		let date_units: [(char, &mut u32); 4] = [
+			('Y', &mut duration.years),
+			('M', &mut duration.months),
+			('W', &mut duration.weeks),
+			('D', &mut duration.days),
+		];
+		parse_units(date, date_units)?;
+		let time_units: [(char, &mut u32); 3] = [
+			('H', &mut duration.hours),
+			('M', &mut duration.minutes),
+			('S', &mut duration.seconds),
+		];
+		parse_units(time, time_units)?;
+		Ok(duration)
+	}
+}
+
+impl fmt::Display for Duration {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("P")?;
+		let date = [
+			(self.years, 'Y'),
+			(self.months, 'M'),
+			(self.weeks, 'W'),
+			(self.days, 'D'),
+		];
+		let time = [(self.hours, 'H'), (self.minutes, 'M'), (self.seconds, 'S')];
+		let has_time = time.iter().any(|(value, _)| *value > 0);
+		for (value, unit) in date {
+			if value > 0 {
+				write!(f, "{}{}", value, unit)?;
+			}
+		}
+		if has_time {
+			f.write_str("T")?;
+			for (value, unit) in time {
+				if value > 0 {
+					write!(f, "{}{}", value, unit)?;
+				}
+			}
+		} else if date.iter().all(|(value, _)| *value == 0) {
+			f.write_str("0D")?;
+		}
+		Ok(())
+	}
+}
+
+/// Parses the values of a part of a duration, where each unit
+/// may appear at most once, and in the given order.
+fn parse_units<const N: usize>(
+	s: &str,
+	units: [(char, &mut u32); N],
+) -> Result<(), ParseTimePeriodError> {
+	let mut rest = s;
+	let mut next_unit = 0;
+	while !rest.is_empty() {
+		let digits = rest
+			.find(|c: char| !c.is_ascii_digit())
+			.ok_or(ParseTimePeriodError)?;
+		let (value, tail) = rest.split_at(digits);
+		let unit = tail.chars().next().ok_or(ParseTimePeriodError)?;
+		let position = units[next_unit..]
+			.iter()
+			.position(|(u, _)| *u == unit)
+			.ok_or(ParseTimePeriodError)?;
+		*units[next_unit + position].1 = parse_digits(value, value.len())?;
+		next_unit += position + 1;
+		rest = &tail[1..];
+	}
+	Ok(())
+}
+
+/// An error which can be returned when parsing a time period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseTimePeriodError;
+
+impl fmt::Display for ParseTimePeriodError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("invalid time period")
+	}
+}
+
+impl Error for ParseTimePeriodError {}
+
+const fn midnight(date: Date) -> DateTime {
+	DateTime {
+		date,
+		hour: 0,
+		minute: 0,
+		second: 0,
+		nanosecond: 0,
+		offset: None,
+	}
+}
+
+/// Parses a number of exactly the given amount of ASCII digits.
+fn parse_digits(s: &str, digits: usize) -> Result<u32, ParseTimePeriodError> {
+	if s.is_empty() || s.len() != digits || !s.bytes().all(|b| b.is_ascii_digit()) {
+		return Err(ParseTimePeriodError);
+	}
+	s.parse().map_err(|_| ParseTimePeriodError)
+}
+
+fn parse_month(s: &str) -> Result<u8, ParseTimePeriodError> {
+	match parse_digits(s, 2)? {
+		month @ 1..=12 => Ok(month as u8),
+		_ => Err(ParseTimePeriodError),
+	}
+}
+
+const fn is_leap_year(year: i32) -> bool {
+	year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+const fn days_in_month(year: i32, month: u8) -> u8 {
+	match month {
+		2 if is_leap_year(year) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
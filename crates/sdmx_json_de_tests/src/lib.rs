@@ -86,7 +86,8 @@ mod tests_metadata_message {
 #[cfg(test)]
 mod tests_structure_message {
 	use super::*;
-	use sdmx_json::structure::StructureMessage;
+	use sdmx_json::structure::{CommonArtefactType, DataStructure, StructureMessage};
+	use serde_json::json;
 
 	#[test]
 	#[cfg_attr(miri, ignore)]
@@ -101,4 +102,1839 @@ mod tests_structure_message {
 		let file = read_json::<StructureMessage>(fixture!("structure/twg-generated-sample.json"));
 		assert!(file.is_ok(), "{:?}", file);
 	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_agency_id() {
+		let file = read_to_string(fixture!("structure/exr-data-structure.json")).unwrap();
+		let structure: DataStructure = serde_json::from_str(&file).unwrap();
+		assert_eq!(structure.common.agency_id.as_deref(), Some("ECB"));
+		let json = serde_json::to_value(&structure).unwrap();
+		assert_eq!(json["agencyID"], "ECB");
+		assert!(json.get("agencyId").is_none());
+
+		// The camel-cased spelling is still read.
+		let structure: CommonArtefactType =
+			serde_json::from_value(json!({ "id": "EXR", "agencyId": "ECB" })).unwrap();
+		assert_eq!(structure.agency_id.as_deref(), Some("ECB"));
+	}
+}
+
+#[cfg(test)]
+mod tests_time {
+	use sdmx_json::structure::TimeDataType;
+	use sdmx_json::time::{MonthDay, TimePeriod};
+
+	fn period(s: &str) -> TimePeriod {
+		s.parse()
+			.unwrap_or_else(|_| panic!("failed to parse {}", s))
+	}
+
+	fn bounds(s: &str) -> (String, String) {
+		let period = period(s);
+		(period.start().to_string(), period.end().to_string())
+	}
+
+	#[test]
+	fn test_parse_and_display() {
+		let periods = [
+			("2020", TimeDataType::GregorianYear),
+			("2020-02", TimeDataType::GregorianYearMonth),
+			("2020-02-29", TimeDataType::GregorianDay),
+			("2020-A1", TimeDataType::ReportingYear),
+			("2020-S2", TimeDataType::ReportingSemester),
+			("2020-T3", TimeDataType::ReportingTrimester),
+			("2020-Q4", TimeDataType::ReportingQuarter),
+			("2020-M07", TimeDataType::ReportingMonth),
+			("2020-W53", TimeDataType::ReportingWeek),
+			("2020-D366", TimeDataType::ReportingDay),
+			("2020-01-15T12:30:00", TimeDataType::DateTime),
+			("2020-01-15T12:30:00.25Z", TimeDataType::DateTime),
+			("2020-01-15T12:30:00-05:30", TimeDataType::DateTime),
+			("2020-01-15/P1M", TimeDataType::TimeRange),
+			(
+				"2020-01-15T12:30:00Z/P1Y2M3DT4H5M6S",
+				TimeDataType::TimeRange,
+			),
+			("2020-01-01/P2W", TimeDataType::TimeRange),
+		];
+		for (s, data_type) in periods {
+			let period = period(s);
+			assert_eq!(period.to_string(), s);
+			assert_eq!(period.data_type(), data_type, "{}", s);
+		}
+
+		let invalid = [
+			"",
+			"20",
+			"2020-",
+			"2020-13",
+			"2019-02-29",
+			"2020-Q5",
+			"2020-M1",
+			"2021-W53",
+			"2021-D366",
+			"2020-X1",
+			"2020-01-15T25:00:00",
+			"2020-01-15T12:30",
+			"2020-01-15T12:30:00.",
+			"2020-01-15T12:00:00Z05:00",
+			"2020-01-15T12:00:00*05:00",
+			"2020-01-15T12:00:00+05",
+			"2020-01-15/P",
+			"2020-01-15/PT",
+			"2020-01-15/P1D2Y",
+		];
+		for s in invalid {
+			assert!(s.parse::<TimePeriod>().is_err(), "{}", s);
+		}
+	}
+
+	#[test]
+	fn test_start_and_end() {
+		let cases = [
+			("2020", "2020-01-01T00:00:00", "2021-01-01T00:00:00"),
+			("2020-02", "2020-02-01T00:00:00", "2020-03-01T00:00:00"),
+			("2020-S2", "2020-07-01T00:00:00", "2021-01-01T00:00:00"),
+			("2020-T2", "2020-05-01T00:00:00", "2020-09-01T00:00:00"),
+			("2020-W01", "2019-12-30T00:00:00", "2020-01-06T00:00:00"),
+			("2020-D060", "2020-02-29T00:00:00", "2020-03-01T00:00:00"),
+			(
+				"2020-01-31/P1M",
+				"2020-01-31T00:00:00",
+				"2020-02-29T00:00:00",
+			),
+			(
+				"2020-12-31T22:00:00Z/PT3H",
+				"2020-12-31T22:00:00Z",
+				"2021-01-01T01:00:00Z",
+			),
+		];
+		for (s, start, end) in cases {
+			assert_eq!(bounds(s), (start.to_owned(), end.to_owned()), "{}", s);
+		}
+
+		let start_day: MonthDay = "--07-01".parse().unwrap();
+		let quarter = period("2020-Q1").with_start_day(start_day).unwrap();
+		assert_eq!(quarter.start().to_string(), "2020-07-01T00:00:00");
+		assert_eq!(quarter.end().to_string(), "2020-10-01T00:00:00");
+		// The first week has the first Thursday of the reporting year.
+		let week = period("2020-W01").with_start_day(start_day).unwrap();
+		assert_eq!(week.start().to_string(), "2020-06-29T00:00:00");
+		// The reporting year starting on July 1, 2020 has neither
+		// a 53rd week nor a 366th day.
+		assert_eq!(period("2020-W53").with_start_day(start_day), None);
+		assert_eq!(period("2020-D366").with_start_day(start_day), None);
+		assert_eq!(
+			period("2020").with_start_day(start_day),
+			Some(period("2020"))
+		);
+	}
+
+	#[test]
+	fn test_ordering_and_types() {
+		let mut periods: Vec<TimePeriod> = [
+			"2020-02",
+			"2020-Q1",
+			"2019-12-31T23:00:00-02:00",
+			"2020",
+			"2020-01-01",
+			"2020-A1",
+		]
+		.into_iter()
+		.map(period)
+		.collect();
+		periods.sort();
+		let sorted: Vec<String> = periods.iter().map(ToString::to_string).collect();
+		assert_eq!(
+			sorted,
+			[
+				"2020",
+				"2020-A1",
+				"2020-Q1",
+				"2020-01-01",
+				"2019-12-31T23:00:00-02:00",
+				"2020-02"
+			]
+		);
+		assert_ne!(period("2020"), period("2020-A1"));
+
+		let day = period("2020-01-01");
+		assert!(day.is_of_type(TimeDataType::BasicTimePeriod));
+		assert!(day.is_of_type(TimeDataType::StandardTimePeriod));
+		assert!(!day.is_of_type(TimeDataType::ReportingTimePeriod));
+		let range = period("2020-01-01/P1D");
+		assert!(range.is_of_type(TimeDataType::ObservationalTimePeriod));
+		assert!(!range.is_of_type(TimeDataType::StandardTimePeriod));
+
+		let json = serde_json::to_string(&period("2020-W05")).unwrap();
+		assert_eq!(json, "\"2020-W05\"");
+		let parsed: TimePeriod = serde_json::from_str(&json).unwrap();
+		assert_eq!(parsed, period("2020-W05"));
+	}
+}
+
+#[cfg(test)]
+mod tests_data_observations {
+	use super::*;
+	use sdmx_json::data::{AttributeValue, Data, DataMessage, DecodeError, Observation};
+	use sdmx_json::primitives::SdmxValue;
+
+	fn sample() -> Data {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		file.unwrap().data.unwrap()
+	}
+
+	fn find<'d>(observations: &[Observation<'d>], currency: &str, time: &str) -> Observation<'d> {
+		let observation = observations.iter().find(|o| {
+			o.dimensions["CURRENCY"].id == currency && o.dimensions["TIME_PERIOD"].id == time
+		});
+		observation.unwrap().clone()
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_series_observations() {
+		let data = sample();
+		let data_set = &data.data_sets.as_ref().unwrap()[0];
+		let observations: Vec<Observation> = data
+			.observations(data_set)
+			.unwrap()
+			.collect::<Result<_, _>>()
+			.unwrap();
+		assert_eq!(observations.len(), 4);
+
+		let observation = find(&observations, "RUB", "2013-01-21");
+		assert_eq!(observation.series_key, Some("1"));
+		assert_eq!(observation.key, "1");
+		assert_eq!(observation.dimensions.len(), 6);
+		assert_eq!(observation.dimensions["FREQ"].id, "D");
+		assert_eq!(observation.measures["OBS_VALUE"], &SdmxValue::Number(40.3));
+		assert!(matches!(
+			observation.attributes["EMBARGO_TIME"],
+			AttributeValue::Literal(SdmxValue::String(s)) if s == "2013-03-21T11:00:00"
+		));
+		// The first value of the status is null.
+		assert!(!observation.attributes.contains_key("OBS_STATUS"));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_flat_observations() {
+		let data = sample();
+		let data_set = &data.data_sets.as_ref().unwrap()[1];
+		let observations: Vec<Observation> = data
+			.observations(data_set)
+			.unwrap()
+			.collect::<Result<_, _>>()
+			.unwrap();
+		assert_eq!(observations.len(), 4);
+
+		let observation = find(&observations, "NZD", "2013-01-18");
+		assert_eq!(observation.series_key, None);
+		assert_eq!(observation.key, "0:0");
+		assert_eq!(
+			observation.measures["OBS_VALUE"],
+			&SdmxValue::Number(1.5931)
+		);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_invalid_indices() {
+		let mut data = sample();
+		let data_sets = data.data_sets.as_mut().unwrap();
+		let observations = data_sets[1].observations.as_mut().unwrap();
+		let values = observations.remove("1:1").unwrap();
+		observations.insert("1:2".to_owned(), values.clone());
+		data_sets[2].observations = Some([("0:0:0".to_owned(), values)].into());
+		data_sets[3].structure = Some(1);
+
+		let data_sets = data.data_sets.as_ref().unwrap();
+		let errors: Vec<DecodeError> = data
+			.observations(&data_sets[1])
+			.unwrap()
+			.filter_map(Result::err)
+			.collect();
+		assert_eq!(
+			errors,
+			[DecodeError::InvalidValueIndex {
+				component: "TIME_PERIOD".to_owned(),
+				index: 2,
+			}]
+		);
+
+		let mut observations = data.observations(&data_sets[2]).unwrap();
+		let error = observations.next().unwrap().unwrap_err();
+		assert_eq!(error, DecodeError::InvalidKey("0:0:0".to_owned()));
+
+		let error = data.observations(&data_sets[3]).unwrap_err();
+		assert_eq!(error, DecodeError::MissingStructure(1));
+	}
+}
+
+#[cfg(test)]
+mod tests_data_attributes {
+	use super::*;
+	use sdmx_json::data::{AttributeValue, DataMessage, DecodeError, Level};
+	use sdmx_json::primitives::SdmxValue;
+
+	fn coded_id<'d>(value: &AttributeValue<'d>) -> Option<&'d str> {
+		match value {
+			AttributeValue::Coded(code) => Some(&code.id),
+			AttributeValue::Literal(_) => None,
+		}
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_resolve_every_level() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let data = file.unwrap().data.unwrap();
+		let structure = &data.structures.as_ref().unwrap()[0];
+		let data_set = &data.data_sets.as_ref().unwrap()[0];
+
+		let values = data_set.attributes.as_deref().unwrap();
+		let attributes: Vec<_> = structure
+			.resolve_attributes(Level::DataSet, values)
+			.collect::<Result<_, _>>()
+			.unwrap();
+		assert_eq!(attributes.len(), 2);
+		assert_eq!(attributes[0].0, "TIME_FORMAT");
+		assert_eq!(coded_id(&attributes[0].1), Some("P1D"));
+		assert_eq!(attributes[1].0, "DESCRIPTION");
+		assert!(matches!(
+			attributes[1].1,
+			AttributeValue::Literal(SdmxValue::Array(values)) if values.len() == 2
+		));
+
+		let groups = data_set.dimension_group_attributes.as_ref().unwrap();
+		let mut attributes =
+			structure.resolve_attributes(Level::DimensionGroup, &groups["0::0::1:"]);
+		let (id, value) = attributes.next().unwrap().unwrap();
+		assert_eq!((id, coded_id(&value)), ("UNIT_MEAS", Some("NC")));
+
+		let series = &data_set.series.as_ref().unwrap()["1"];
+		let values = series.attributes.as_deref().unwrap();
+		let mut attributes = structure.resolve_attributes(Level::Series, values);
+		let (id, value) = attributes.next().unwrap().unwrap();
+		assert_eq!((id, coded_id(&value)), ("ID", Some("ID2")));
+
+		// The measure comes first, and the status is an index to a null value.
+		let values = &series.observations.as_ref().unwrap()["0"];
+		let attributes: Vec<_> = structure
+			.resolve_attributes(Level::Observation, values)
+			.collect::<Result<_, _>>()
+			.unwrap();
+		assert_eq!(attributes.len(), 1);
+		assert_eq!(attributes[0].0, "EMBARGO_TIME");
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_resolve_invalid_index() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let data = file.unwrap().data.unwrap();
+		let structure = &data.structures.as_ref().unwrap()[0];
+
+		let values = [SdmxValue::Null, SdmxValue::Integer(2)];
+		let errors: Vec<_> = structure
+			.resolve_attributes(Level::Series, &values[1..])
+			.chain(structure.resolve_attributes(Level::Series, &values[..1]))
+			.collect();
+		assert_eq!(
+			errors,
+			[Err(DecodeError::InvalidValueIndex {
+				component: "ID".to_owned(),
+				index: 2,
+			})]
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests_data_annotations {
+	use super::*;
+	use sdmx_json::data::{DataMessage, DecodeError};
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_annotations_of() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let data = file.unwrap().data.unwrap();
+		let structure = &data.structures.as_ref().unwrap()[0];
+		let data_set = &data.data_sets.as_ref().unwrap()[0];
+
+		let annotations: Vec<_> = structure
+			.annotations_of(data_set)
+			.collect::<Result<_, _>>()
+			.unwrap();
+		assert_eq!(annotations.len(), 1);
+		assert_eq!(
+			annotations[0].title.as_deref(),
+			Some("A title for the annotation.")
+		);
+
+		let series = data_set.series.as_ref().unwrap();
+		assert_eq!(structure.annotations_of(&series["0"]).count(), 0);
+		let annotation = structure.annotations_of(&series["1"]).next();
+		assert!(std::ptr::eq(annotation.unwrap().unwrap(), annotations[0]));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_missing_annotation() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let data = file.unwrap().data.unwrap();
+		let structure = &data.structures.as_ref().unwrap()[0];
+		let mut data_set = data.data_sets.as_ref().unwrap()[0].clone();
+		data_set.annotations = Some(vec![0, 3]);
+
+		let mut annotations = structure.annotations_of(&data_set);
+		assert!(annotations.next().unwrap().is_ok());
+		assert_eq!(
+			annotations.next().unwrap(),
+			Err(DecodeError::MissingAnnotation(3))
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests_data_rows {
+	use super::*;
+	use sdmx_json::data::{CellValue, DataMessage, DecodeError, Labels};
+	use sdmx_json::primitives::SdmxValue;
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_rows_by_id() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let message = file.unwrap();
+		let rows: Vec<_> = message.rows(Labels::Id).collect::<Result<_, _>>().unwrap();
+		// Every dataset has 4 observations, and all of them share the same structure.
+		assert_eq!(rows.len(), 20);
+		assert!(rows.iter().all(|row| row.cells.len() == 13));
+		let rows: Vec<_> = rows.into_iter().filter(|row| row.data_set == 0).collect();
+		assert_eq!(rows.len(), 4);
+
+		let value = SdmxValue::Number(40.3426);
+		let row = rows
+			.iter()
+			.find(|row| row.get("OBS_VALUE") == Some(&CellValue::Value(&value)))
+			.unwrap();
+		assert_eq!(row.get("CURRENCY"), Some(&CellValue::Text("RUB")));
+		assert_eq!(row.get("TIME_FORMAT"), Some(&CellValue::Text("P1D")));
+		assert_eq!(row.get("UNIT_MEAS"), Some(&CellValue::Text("NC")));
+		assert_eq!(row.get("ID"), Some(&CellValue::Text("ID2")));
+		assert_eq!(row.get("OBS_STATUS"), None);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_rows_by_name() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let message = file.unwrap();
+		let rows: Vec<_> = message
+			.rows(Labels::Name("en"))
+			.collect::<Result<_, _>>()
+			.unwrap();
+		let currency = Some(&CellValue::Text("Russian rouble"));
+		let rows: Vec<_> = rows.into_iter().filter(|row| row.data_set == 0).collect();
+		assert_eq!(
+			rows.iter()
+				.filter(|row| row.get("Currency") == currency)
+				.count(),
+			2
+		);
+		assert!(rows
+			.iter()
+			.all(|row| row.get("Time Format") == Some(&CellValue::Text("Daily"))));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_rows_missing_structure() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let mut message = file.unwrap();
+		let data = message.data.as_mut().unwrap();
+		data.data_sets.as_mut().unwrap()[0].structure = Some(5);
+
+		let mut rows = message.rows(Labels::Id);
+		assert_eq!(rows.next(), Some(Err(DecodeError::MissingStructure(5))));
+		// The other datasets are still decoded.
+		assert_eq!(rows.next().unwrap().unwrap().data_set, 1);
+	}
+}
+
+#[cfg(all(test, feature = "arrow"))]
+mod tests_arrow {
+	use super::*;
+	use arrow_array::cast::AsArray;
+	use arrow_array::types::{Float64Type, Int32Type, Int64Type};
+	use arrow_array::{Array, ArrayAccessor, StringArray};
+	use arrow_schema::DataType;
+	use sdmx_json::arrow::ToArrowError;
+	use sdmx_json::data::{Data, DataMessage};
+	use sdmx_json::primitives::{DataType as PrimitiveType, NumberOrString, SdmxValue};
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_to_record_batches() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let data = file.unwrap().data.unwrap();
+		let data_sets = data.data_sets.as_ref().unwrap();
+		let batches: Vec<_> = data_sets[..4]
+			.iter()
+			.map(|data_set| data.to_record_batch(data_set))
+			.collect::<Result<_, _>>()
+			.unwrap();
+		assert!(batches.iter().all(|batch| batch.num_rows() == 4));
+
+		let batch = &batches[0];
+		let schema = batch.schema();
+		assert_eq!(batch.num_columns(), 13);
+		let dictionary = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+		assert_eq!(schema.field(0).name(), "FREQ");
+		assert_eq!(schema.field(0).data_type(), &dictionary);
+		assert_eq!(schema.field(6).name(), "OBS_VALUE");
+		assert_eq!(schema.field(6).data_type(), &DataType::Float64);
+		assert_eq!(schema.field(8).name(), "DESCRIPTION");
+		assert_eq!(schema.field(8).data_type(), &DataType::Utf8);
+
+		let currencies = batch.column_by_name("CURRENCY").unwrap();
+		let currencies = currencies.as_dictionary::<Int32Type>();
+		let values = batch.column_by_name("OBS_VALUE").unwrap();
+		let values = values.as_primitive::<Float64Type>();
+		let row = (0..batch.num_rows())
+			.find(|row| values.value(*row) == 40.3426)
+			.unwrap();
+		// Keys are the indices of the values of the dimension.
+		assert_eq!(currencies.keys().value(row), 1);
+
+		let ids = batch.column_by_name("ID").unwrap();
+		let ids = ids.as_dictionary::<Int32Type>();
+		let ids = ids.downcast_dict::<StringArray>().unwrap();
+		assert_eq!(ids.value(row), "ID2");
+
+		let statuses = batch.column_by_name("OBS_STATUS").unwrap();
+		assert_eq!(statuses.null_count(), 4);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_invalid_measure_value() {
+		// The observations of the last dataset only have attributes,
+		// so their first value is not a value of the measure.
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let error = file.unwrap().to_record_batches().unwrap_err();
+		assert!(matches!(
+			error,
+			ToArrowError::InvalidValue { component, value: SdmxValue::String(_) }
+				if component == "OBS_VALUE"
+		));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_integral_measure_values() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let mut data = file.unwrap().data.unwrap();
+		let structure = &mut data.structures.as_mut().unwrap()[0];
+		let measures = structure.measures.as_mut().unwrap();
+		let measure = &mut measures.observation.as_mut().unwrap()[0];
+		measure.format.as_mut().unwrap().data_type = Some(PrimitiveType::Integer);
+
+		let set_values = |data: &mut Data, value: f64| {
+			let data_set = &mut data.data_sets.as_mut().unwrap()[0];
+			let series = data_set.series.iter_mut().flat_map(|s| s.values_mut());
+			let observations = series
+				.flat_map(|series| series.observations.iter_mut().flatten())
+				.chain(data_set.observations.iter_mut().flatten());
+			for (_, values) in observations {
+				values[0] = SdmxValue::Number(value);
+			}
+		};
+
+		set_values(&mut data, 3.0);
+		let batch = data.to_record_batch(&data.data_sets.as_ref().unwrap()[0]);
+		let batch = batch.unwrap();
+		let values = batch.column_by_name("OBS_VALUE").unwrap();
+		let values = values.as_primitive::<Int64Type>();
+		assert!(values.iter().all(|value| value == Some(3)));
+
+		set_values(&mut data, 3.5);
+		let error = data
+			.to_record_batch(&data.data_sets.as_ref().unwrap()[0])
+			.unwrap_err();
+		assert!(matches!(
+			error,
+			ToArrowError::InvalidValue {
+				value: SdmxValue::Number(_),
+				..
+			}
+		));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_sentinel_measure_values() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let mut data = file.unwrap().data.unwrap();
+		let structure = &mut data.structures.as_mut().unwrap()[0];
+		let measures = structure.measures.as_mut().unwrap();
+		let measure = &mut measures.observation.as_mut().unwrap()[0];
+		let format = measure.format.as_mut().unwrap();
+		format.data_type = Some(PrimitiveType::Integer);
+		format.sentinel_values = Some(vec![NumberOrString::String("NaN".to_owned())]);
+
+		let data_set = &mut data.data_sets.as_mut().unwrap()[0];
+		let series = data_set.series.iter_mut().flat_map(|s| s.values_mut());
+		let observations = series
+			.flat_map(|series| series.observations.iter_mut().flatten())
+			.chain(data_set.observations.iter_mut().flatten());
+		for (i, (_, values)) in observations.enumerate() {
+			values[0] = match i {
+				0 => SdmxValue::String("NaN".to_owned()),
+				_ => SdmxValue::Integer(3),
+			};
+		}
+
+		let batch = data.to_record_batch(&data.data_sets.as_ref().unwrap()[0]);
+		let batch = batch.unwrap();
+		let values = batch.column_by_name("OBS_VALUE").unwrap();
+		let values = values.as_primitive::<Int64Type>();
+		assert_eq!(values.null_count(), 1);
+		assert_eq!(values.iter().flatten().sum::<i64>(), 9);
+	}
+}
+
+#[cfg(test)]
+mod tests_data_builder {
+	use super::*;
+	use sdmx_json::data::{
+		BuildError, CellValue, DataMessageBuilder, Labels, NewObservation, ObservationLayout,
+	};
+	use sdmx_json::primitives::SdmxValue;
+	use sdmx_json::structure::DataStructure;
+
+	fn data_structure() -> DataStructure {
+		let file = read_to_string(fixture!("structure/exr-data-structure.json")).unwrap();
+		serde_json::from_str(&file).unwrap()
+	}
+
+	fn observation(freq: &str, currency: &str, time: &str, value: f64) -> NewObservation {
+		NewObservation::default()
+			.dimension("FREQ", freq)
+			.dimension("CURRENCY", currency)
+			.dimension("TIME_PERIOD", time)
+			.measure("OBS_VALUE", SdmxValue::Number(value))
+			.attribute("OBS_STATUS", SdmxValue::String("A".to_owned()))
+			.attribute("DECIMALS", SdmxValue::String("4".to_owned()))
+			.attribute("UNIT_MULT", SdmxValue::Integer(0))
+			.attribute("TITLE", SdmxValue::String("Exchange rates".to_owned()))
+	}
+
+	fn build(layout: ObservationLayout) -> sdmx_json::data::DataMessage {
+		let structure = data_structure();
+		let mut builder = DataMessageBuilder::new(&structure, layout);
+		builder
+			.add_observation(&observation("A", "USD", "2020", 1.1))
+			.unwrap();
+		builder
+			.add_observation(&observation("A", "USD", "2021", 1.2))
+			.unwrap();
+		builder
+			.add_observation(&observation("M", "JPY", "2020-01", 130.0))
+			.unwrap();
+		builder.build()
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_build_series() {
+		let message = build(ObservationLayout::Series);
+		let data = message.data.as_ref().unwrap();
+		let structure = &data.structures.as_ref().unwrap()[0];
+		let ids = |components: &Option<Vec<sdmx_json::data::Component>>| -> Vec<String> {
+			let components = components.iter().flatten();
+			components.map(|c| c.id.clone()).collect()
+		};
+		assert_eq!(ids(&structure.dimensions.series), ["FREQ", "CURRENCY"]);
+		assert_eq!(ids(&structure.dimensions.observation), ["TIME_PERIOD"]);
+		assert_eq!(ids(&structure.attributes.data_set), ["TITLE"]);
+		assert_eq!(ids(&structure.attributes.dimension_group), ["UNIT_MULT"]);
+		assert_eq!(ids(&structure.attributes.series), ["DECIMALS"]);
+		assert_eq!(ids(&structure.attributes.observation), ["OBS_STATUS"]);
+
+		// Coded values are deduplicated.
+		let status = &structure.attributes.observation.as_ref().unwrap()[0];
+		assert_eq!(status.values.as_ref().unwrap().len(), 1);
+
+		let data_set = &data.data_sets.as_ref().unwrap()[0];
+		let series = data_set.series.as_ref().unwrap();
+		let mut keys: Vec<_> = series.keys().collect();
+		keys.sort();
+		assert_eq!(keys, ["0:0", "1:1"]);
+		assert_eq!(series["0:0"].observations.as_ref().unwrap().len(), 2);
+		assert_eq!(series["0:0"].attributes, Some(vec![SdmxValue::Integer(0)]));
+		let observation = &series["1:1"].observations.as_ref().unwrap()["2"];
+		assert_eq!(
+			observation,
+			&[SdmxValue::Number(130.0), SdmxValue::Integer(0)]
+		);
+		let groups = data_set.dimension_group_attributes.as_ref().unwrap();
+		assert_eq!(groups.len(), 2);
+		assert!(groups.contains_key("0::"));
+
+		// The message can be decoded back into the same observations.
+		let rows: Vec<_> = message.rows(Labels::Id).collect::<Result<_, _>>().unwrap();
+		assert_eq!(rows.len(), 3);
+		for row in rows {
+			assert_eq!(row.get("DECIMALS"), Some(&CellValue::Text("4")));
+			assert_eq!(row.get("OBS_STATUS"), Some(&CellValue::Text("A")));
+			assert_eq!(
+				row.get("UNIT_MULT"),
+				Some(&CellValue::Value(&SdmxValue::Integer(0)))
+			);
+		}
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_build_flat() {
+		let message = build(ObservationLayout::Flat);
+		let data = message.data.as_ref().unwrap();
+		let structure = &data.structures.as_ref().unwrap()[0];
+		assert!(structure.dimensions.series.is_none());
+		assert_eq!(structure.dimensions.observation.as_ref().unwrap().len(), 3);
+		assert!(structure.attributes.series.is_none());
+
+		let data_set = &data.data_sets.as_ref().unwrap()[0];
+		assert!(data_set.series.is_none());
+		let mut keys: Vec<_> = data_set.observations.as_ref().unwrap().keys().collect();
+		keys.sort();
+		assert_eq!(keys, ["0:0:0", "0:0:1", "1:1:2"]);
+		let groups = data_set.dimension_group_attributes.as_ref().unwrap();
+		assert!(groups.contains_key("0:0:"));
+		assert_eq!(message.rows(Labels::Id).count(), 3);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_build_invalid_observation() {
+		let structure = data_structure();
+		let mut builder = DataMessageBuilder::new(&structure, ObservationLayout::Series);
+
+		let mut missing = observation("A", "USD", "2020", 1.0);
+		missing.dimensions.remove("CURRENCY");
+		assert_eq!(
+			builder.add_observation(&missing),
+			Err(BuildError::MissingDimension("CURRENCY".to_owned()))
+		);
+
+		let unknown = observation("A", "USD", "2020", 1.0).measure("OTHER", SdmxValue::Null);
+		assert_eq!(
+			builder.add_observation(&unknown),
+			Err(BuildError::UnknownComponent("OTHER".to_owned()))
+		);
+
+		let invalid =
+			observation("A", "USD", "2020", 1.0).attribute("OBS_STATUS", SdmxValue::Boolean(true));
+		assert_eq!(
+			builder.add_observation(&invalid),
+			Err(BuildError::InvalidCode("OBS_STATUS".to_owned()))
+		);
+
+		let message = builder.build();
+		let data_set = &message.data.unwrap().data_sets.unwrap()[0];
+		assert!(data_set.series.is_none());
+	}
+}
+
+#[cfg(test)]
+mod tests_data_stream {
+	use super::*;
+	use sdmx_json::data::{DataHandler, DataMessage, Series, Structure};
+	use sdmx_json::primitives::SdmxValue;
+	use std::collections::HashMap;
+	use std::fs::File;
+	use std::io::BufReader;
+
+	/// Collects everything which is streamed, to rebuild the message.
+	#[derive(Default)]
+	struct Collector {
+		structures: usize,
+		series: HashMap<(usize, String), Series>,
+		observations: Vec<(usize, Option<String>, String, Vec<SdmxValue>)>,
+		limit: Option<usize>,
+	}
+
+	impl DataHandler for Collector {
+		type Error = String;
+
+		fn structures(&mut self, structures: &[Structure]) -> Result<(), Self::Error> {
+			self.structures = structures.len();
+			Ok(())
+		}
+
+		fn series(
+			&mut self,
+			data_set: usize,
+			key: &str,
+			series: Series,
+		) -> Result<(), Self::Error> {
+			self.series.insert((data_set, key.to_owned()), series);
+			Ok(())
+		}
+
+		fn observation(
+			&mut self,
+			data_set: usize,
+			series_key: Option<&str>,
+			key: &str,
+			values: Vec<SdmxValue>,
+		) -> Result<(), Self::Error> {
+			if self.limit == Some(self.observations.len()) {
+				return Err("too many observations".to_owned());
+			}
+			let series_key = series_key.map(str::to_owned);
+			self.observations
+				.push((data_set, series_key, key.to_owned(), values));
+			Ok(())
+		}
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_stream_data_message() {
+		let path = fixture!("data/twg-constructed-sample-full.json");
+		let reader = BufReader::new(File::open(path).unwrap());
+		let mut collector = Collector::default();
+		let mut message = DataMessage::stream_from_reader(reader, &mut collector).unwrap();
+		assert_eq!(collector.structures, 1);
+		assert_eq!(collector.series.len(), 2);
+		assert_eq!(collector.observations.len(), 20);
+
+		let data = message.data.as_mut().unwrap();
+		let data_sets = data.data_sets.as_mut().unwrap();
+		assert!(data_sets.iter().all(|d| d.series.is_none()));
+		assert!(data_sets.iter().all(|d| d.observations.is_none()));
+		for ((data_set, key), series) in collector.series {
+			let all_series = data_sets[data_set].series.get_or_insert_with(HashMap::new);
+			all_series.insert(key, series);
+		}
+		for (data_set, series_key, key, values) in collector.observations {
+			let data_set = &mut data_sets[data_set];
+			let observations = match series_key {
+				Some(series_key) => {
+					let series = data_set.series.as_mut().unwrap();
+					let series = series.get_mut(&series_key).unwrap();
+					series.observations.get_or_insert_with(HashMap::new)
+				}
+				None => data_set.observations.get_or_insert_with(HashMap::new),
+			};
+			observations.insert(key, values);
+		}
+
+		let expected = read_json::<DataMessage>(path).unwrap();
+		assert_eq!(message, expected);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_stream_handler_error() {
+		let path = fixture!("data/twg-constructed-sample-full.json");
+		let reader = BufReader::new(File::open(path).unwrap());
+		let mut collector = Collector {
+			limit: Some(3),
+			..Default::default()
+		};
+		let error = DataMessage::stream_from_reader(reader, &mut collector).unwrap_err();
+		assert!(error.to_string().starts_with("too many observations"));
+		assert_eq!(collector.observations.len(), 3);
+	}
+}
+
+#[cfg(test)]
+mod tests_data_typed {
+	use super::*;
+	use sdmx_json::data::{Component, DataMessage, Format, TypeError, TypedValue};
+	use sdmx_json::primitives::{DataType, NumberOrString, SdmxValue};
+	use sdmx_json::time::TimePeriod;
+
+	fn component(data_type: DataType) -> Component {
+		Component {
+			id: "OBS_VALUE".to_owned(),
+			format: Some(Format {
+				data_type: Some(data_type),
+				..Default::default()
+			}),
+			..Default::default()
+		}
+	}
+
+	fn string(value: &str) -> SdmxValue {
+		SdmxValue::String(value.to_owned())
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_typed_measures() {
+		let file = read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json"));
+		let data = file.unwrap().data.unwrap();
+		let structure = &data.structures.as_ref().unwrap()[0];
+		let data_set = &data.data_sets.as_ref().unwrap()[0];
+		let series = &data_set.series.as_ref().unwrap()["0"];
+		let values = &series.observations.as_ref().unwrap()["0"];
+
+		let measures: Vec<_> = structure
+			.typed_measures(values)
+			.collect::<Result<_, _>>()
+			.unwrap();
+		assert_eq!(measures, [("OBS_VALUE", TypedValue::Decimal(1.5931))]);
+	}
+
+	#[test]
+	fn test_typed_value() {
+		let short = component(DataType::Short);
+		assert_eq!(
+			short.typed_value(&string("42")),
+			Ok(TypedValue::Integer(42))
+		);
+		assert!(short.typed_value(&SdmxValue::Integer(40_000)).is_err());
+		assert!(short.typed_value(&SdmxValue::Number(1.5)).is_err());
+
+		let boolean = component(DataType::Boolean);
+		assert_eq!(
+			boolean.typed_value(&string("true")),
+			Ok(TypedValue::Boolean(true))
+		);
+
+		let numeric = component(DataType::Numeric);
+		assert!(numeric.typed_value(&string("0042")).is_ok());
+		assert!(numeric.typed_value(&string("42a")).is_err());
+
+		let quarter = component(DataType::ReportingQuarter);
+		let period: TimePeriod = "2020-Q1".parse().unwrap();
+		assert_eq!(
+			quarter.typed_value(&string("2020-Q1")),
+			Ok(TypedValue::Period(period))
+		);
+		assert_eq!(
+			quarter.typed_value(&string("2020-01")),
+			Err(TypeError::InvalidValue {
+				component: "OBS_VALUE".to_owned(),
+				data_type: Some(DataType::ReportingQuarter),
+			})
+		);
+
+		let values = SdmxValue::Array(Box::new(vec![string("A"), SdmxValue::Null]));
+		assert_eq!(
+			component(DataType::String).typed_value(&values),
+			Ok(TypedValue::Multiple(vec![
+				TypedValue::String("A".into()),
+				TypedValue::Missing
+			]))
+		);
+		assert_eq!(
+			Component::default().typed_value(&SdmxValue::Integer(1)),
+			Ok(TypedValue::Integer(1))
+		);
+	}
+
+	#[test]
+	fn test_typed_value_format() {
+		let mut double = component(DataType::Double);
+		let format = double.format.as_mut().unwrap();
+		format.decimals = Some(1);
+		format.sentinel_values = Some(vec![
+			NumberOrString::Number(-1),
+			NumberOrString::String("NaN".to_owned()),
+		]);
+
+		assert_eq!(
+			double.typed_value(&SdmxValue::Number(2.5)),
+			Ok(TypedValue::Decimal(2.5))
+		);
+		assert_eq!(
+			double.typed_value(&string("2.50")),
+			Err(TypeError::TooManyDecimals {
+				component: "OBS_VALUE".to_owned(),
+				decimals: 1,
+			})
+		);
+		assert_eq!(
+			double.typed_value(&SdmxValue::Integer(-1)),
+			Ok(TypedValue::Sentinel(&NumberOrString::Number(-1)))
+		);
+		assert!(matches!(
+			double.typed_value(&string("NaN")),
+			Ok(TypedValue::Sentinel(NumberOrString::String(_)))
+		));
+	}
+}
+
+#[cfg(test)]
+mod tests_data_store {
+	use super::*;
+	use sdmx_json::data::{
+		DataMessage, DataMessageBuilder, DataStore, NewObservation, ObservationLayout, SeriesKey,
+	};
+	use sdmx_json::primitives::{Action, SdmxValue};
+	use sdmx_json::structure::DataStructure;
+	use std::collections::HashMap;
+
+	fn message(action: Action, observations: &[NewObservation]) -> DataMessage {
+		let file = read_to_string(fixture!("structure/exr-data-structure.json")).unwrap();
+		let structure: DataStructure = serde_json::from_str(&file).unwrap();
+		let mut builder =
+			DataMessageBuilder::new(&structure, ObservationLayout::Series).action(action);
+		for observation in observations {
+			builder.add_observation(observation).unwrap();
+		}
+		builder.build()
+	}
+
+	fn observation(freq: &str, currency: &str, time: &str) -> NewObservation {
+		NewObservation::default()
+			.dimension("FREQ", freq)
+			.dimension("CURRENCY", currency)
+			.dimension("TIME_PERIOD", time)
+	}
+
+	fn value(freq: &str, currency: &str, time: &str, value: f64) -> NewObservation {
+		observation(freq, currency, time)
+			.measure("OBS_VALUE", SdmxValue::Number(value))
+			.attribute("OBS_STATUS", SdmxValue::String("A".to_owned()))
+	}
+
+	fn key(freq: &str, currency: &str) -> SeriesKey {
+		let key = [("FREQ", freq), ("CURRENCY", currency)];
+		key.iter()
+			.map(|(id, value)| (id.to_string(), value.to_string()))
+			.collect()
+	}
+
+	fn obs_value(store: &DataStore, key: &SeriesKey, time: &str) -> Option<SdmxValue> {
+		let observation = store.observation(key, time)?;
+		observation.measures.get("OBS_VALUE").cloned()
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_apply_actions() {
+		let mut store = DataStore::new();
+		let initial = message(
+			Action::Information,
+			&[
+				value("A", "USD", "2020", 1.1)
+					.attribute("DECIMALS", SdmxValue::String("4".to_owned()))
+					.attribute("UNIT_MULT", SdmxValue::Integer(0))
+					.attribute("TITLE", SdmxValue::String("Exchange rates".to_owned())),
+				value("A", "USD", "2021", 1.2),
+				value("M", "JPY", "2020-01", 130.0),
+			],
+		);
+		store.apply(&initial).unwrap();
+		let usd = key("A", "USD");
+		assert_eq!(store.series().len(), 2);
+		assert_eq!(store.series()[&usd].observations.len(), 2);
+		assert_eq!(
+			store.series()[&usd].attributes["DECIMALS"],
+			SdmxValue::String("4".to_owned())
+		);
+		assert!(store.attributes().contains_key("TITLE"));
+		let group: SeriesKey = [("FREQ".to_owned(), "A".to_owned())].into();
+		assert_eq!(store.groups()[&group]["UNIT_MULT"], SdmxValue::Integer(0));
+		let stored = store.observation(&usd, "2020").unwrap();
+		assert_eq!(
+			stored.attributes["OBS_STATUS"],
+			SdmxValue::String("A".to_owned())
+		);
+
+		// Appending does not replace existing values.
+		let append = message(
+			Action::Append,
+			&[
+				value("A", "USD", "2020", 9.9),
+				value("A", "USD", "2022", 1.3),
+			],
+		);
+		store.apply(&append).unwrap();
+		assert_eq!(
+			obs_value(&store, &usd, "2020"),
+			Some(SdmxValue::Number(1.1))
+		);
+		assert_eq!(
+			obs_value(&store, &usd, "2022"),
+			Some(SdmxValue::Number(1.3))
+		);
+
+		let replace = message(Action::Replace, &[value("A", "USD", "2020", 2.0)]);
+		store.apply(&replace).unwrap();
+		assert_eq!(
+			obs_value(&store, &usd, "2020"),
+			Some(SdmxValue::Number(2.0))
+		);
+
+		// Deleting an attribute of an observation keeps its other values.
+		let status = SdmxValue::String("A".to_owned());
+		let delete = message(
+			Action::Delete,
+			&[observation("A", "USD", "2021").attribute("OBS_STATUS", status.clone())],
+		);
+		store.apply(&delete).unwrap();
+		let stored = store.observation(&usd, "2021").unwrap();
+		assert!(stored.attributes.is_empty());
+		assert_eq!(
+			obs_value(&store, &usd, "2021"),
+			Some(SdmxValue::Number(1.2))
+		);
+
+		// Deleting an observation without values deletes it as a whole.
+		let delete = message(Action::Delete, &[observation("A", "USD", "2022")]);
+		store.apply(&delete).unwrap();
+		assert_eq!(store.observation(&usd, "2022"), None);
+		assert_eq!(store.series()[&usd].observations.len(), 2);
+
+		// Deleting an observation with a measure value deletes it as a whole.
+		let delete = message(Action::Delete, &[value("A", "USD", "2020", 1.0)]);
+		store.apply(&delete).unwrap();
+		assert_eq!(store.observation(&usd, "2020"), None);
+		assert_eq!(store.series()[&usd].observations.len(), 1);
+
+		// An observation left without values is deleted.
+		let replace = message(
+			Action::Replace,
+			&[observation("A", "USD", "2020").attribute("OBS_STATUS", status.clone())],
+		);
+		store.apply(&replace).unwrap();
+		assert!(store.observation(&usd, "2020").is_some());
+		let delete = message(
+			Action::Delete,
+			&[observation("A", "USD", "2020").attribute("OBS_STATUS", status)],
+		);
+		store.apply(&delete).unwrap();
+		assert_eq!(store.observation(&usd, "2020"), None);
+
+		// Deleting an empty dataset deletes nothing.
+		let mut delete = message(Action::Delete, &[]);
+		let data = delete.data.as_mut().unwrap();
+		for data_set in data.data_sets.iter_mut().flatten() {
+			data_set.attributes = None;
+			data_set.dimension_group_attributes = None;
+			data_set.series = None;
+		}
+		let expected = store.clone();
+		store.apply(&delete).unwrap();
+		assert_eq!(store, expected);
+
+		// Deleting a series key without observations deletes the whole series.
+		let mut delete = message(Action::Delete, &[observation("M", "JPY", "2020-01")]);
+		let data = delete.data.as_mut().unwrap();
+		for data_set in data.data_sets.iter_mut().flatten() {
+			for series in data_set.series.iter_mut().flat_map(|s| s.values_mut()) {
+				series.observations = Some(HashMap::new());
+			}
+		}
+		store.apply(&delete).unwrap();
+		assert_eq!(store.series().len(), 1);
+		assert!(store.series().contains_key(&usd));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_apply_invalid_message() {
+		let mut store = DataStore::new();
+		let initial = message(Action::Replace, &[value("A", "USD", "2020", 1.1)]);
+		store.apply(&initial).unwrap();
+
+		let mut update = message(
+			Action::Replace,
+			&[
+				value("A", "USD", "2020", 2.0),
+				value("M", "JPY", "2020-01", 130.0),
+			],
+		);
+		let data = update.data.as_mut().unwrap();
+		let data_set = &mut data.data_sets.as_mut().unwrap()[0];
+		let series = data_set.series.as_mut().unwrap();
+		let jpy = series.remove("1:1").unwrap();
+		series.insert("1:9".to_owned(), jpy);
+
+		// The message is not applied at all.
+		let expected = store.clone();
+		assert!(store.apply(&update).is_err());
+		assert_eq!(store, expected);
+		let usd = key("A", "USD");
+		assert_eq!(
+			obs_value(&store, &usd, "2020"),
+			Some(SdmxValue::Number(1.1))
+		);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_apply_fixture() {
+		let message =
+			read_json::<DataMessage>(fixture!("data/twg-constructed-sample-full.json")).unwrap();
+		let mut store = DataStore::new();
+		store.apply(&message).unwrap();
+		assert_eq!(
+			store.attributes()["TIME_FORMAT"],
+			SdmxValue::String("P1D".to_owned())
+		);
+		assert!(!store.groups().is_empty());
+
+		// Every dataset gives the same observations, which replace each other.
+		assert_eq!(store.series().len(), 2);
+		let observations = store.series().values().map(|s| s.observations.len());
+		assert_eq!(observations.sum::<usize>(), 4);
+	}
+}
+
+#[cfg(test)]
+mod tests_structure_urn {
+	use super::*;
+	use sdmx_json::structure::{
+		Class, DataStructure, Package, ParseUrnError, StructureMessage, Urn,
+	};
+	use serde_json::Value;
+
+	fn collect_urns<'a>(value: &'a Value, urns: &mut Vec<&'a str>) {
+		match value {
+			Value::String(s) if s.starts_with("urn:sdmx:") => urns.push(s),
+			Value::Array(values) => values.iter().for_each(|v| collect_urns(v, urns)),
+			Value::Object(map) => map.values().for_each(|v| collect_urns(v, urns)),
+			_ => {}
+		}
+	}
+
+	#[test]
+	fn test_parse_and_display() {
+		let s = "urn:sdmx:org.sdmx.infomodel.categoryscheme.Category=SDMX.ESTAT:STAT_SUBJECT(2.0+).DEMO.POP";
+		let urn: Urn = s.parse().unwrap();
+		assert_eq!(urn.package(), Package::CategoryScheme);
+		assert_eq!(urn.class, Class::Category);
+		assert_eq!(urn.agency_id, "SDMX.ESTAT");
+		assert_eq!(urn.id, "STAT_SUBJECT");
+		assert_eq!(urn.version, "2.0+");
+		assert_eq!(urn.item_path, ["DEMO", "POP"]);
+		assert!(!urn.is_maintainable());
+		assert_eq!(urn.to_string(), s);
+
+		let s = "urn:sdmx:org.sdmx.infomodel.datastructure.Dataflow=ECB:EXR(1.0)";
+		let urn: Urn = s.parse().unwrap();
+		assert!(urn.is_maintainable());
+		assert_eq!(serde_json::to_string(&urn).unwrap(), format!("\"{}\"", s));
+
+		let invalid = [
+			"urn:sdmx:org.sdmx.infomodel.codelist.Codelist=ESTAT:CL_FREQ",
+			"urn:sdmx:org.sdmx.infomodel.codelist.Codelist=:CL_FREQ(1.0)",
+			"urn:sdmx:org.sdmx.infomodel.codelist.Code=ESTAT:CL_FREQ(1.0)A",
+			"urn:sdmx:org.sdmx.infomodel.codelist.Code=ESTAT:CL_FREQ(1.0).A..B",
+			"urn:sdmx:org.example.codelist.Codelist=ESTAT:CL_FREQ(1.0)",
+		];
+		for s in invalid {
+			assert_eq!(s.parse::<Urn>(), Err(ParseUrnError::InvalidSyntax), "{}", s);
+		}
+		assert_eq!(
+			"urn:sdmx:org.sdmx.infomodel.codes.Code=ESTAT:CL_FREQ(1.0).A".parse::<Urn>(),
+			Err(ParseUrnError::UnknownPackage("codes".to_owned()))
+		);
+		assert_eq!(
+			"urn:sdmx:org.sdmx.infomodel.codelist.Concept=ESTAT:CS(1.0).A".parse::<Urn>(),
+			Err(ParseUrnError::UnknownClass {
+				package: Package::Codelist,
+				class: "Concept".to_owned(),
+			})
+		);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_fixture_urns() {
+		for path in [
+			fixture!("structure/twg-constructed-sample.json"),
+			fixture!("structure/twg-generated-sample.json"),
+		] {
+			let value: Value = serde_json::from_str(&read_to_string(path).unwrap()).unwrap();
+			let mut urns = Vec::new();
+			collect_urns(&value, &mut urns);
+			assert!(!urns.is_empty());
+			for s in urns {
+				let urn: Urn = s.parse().unwrap_or_else(|e| panic!("{}: {}", s, e));
+				assert_eq!(urn.to_string(), s);
+			}
+		}
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_typed_accessors() {
+		let message =
+			read_json::<StructureMessage>(fixture!("structure/twg-constructed-sample.json"))
+				.unwrap();
+		let data = message.data.unwrap();
+
+		let dataflows = data.dataflows.iter().flatten();
+		let structures: Vec<_> = dataflows.filter_map(|d| d.structure_urn()).collect();
+		assert!(!structures.is_empty());
+		for urn in structures {
+			assert_eq!(urn.unwrap().class, Class::DataStructure);
+		}
+
+		let file = read_to_string(fixture!("structure/exr-data-structure.json")).unwrap();
+		let structure: DataStructure = serde_json::from_str(&file).unwrap();
+		let components = structure.data_structure_components.unwrap();
+		let dimensions = components.dimension_list.dimensions.unwrap();
+		let urn = dimensions[0].concept_identity_urn().unwrap();
+		assert_eq!(urn.class, Class::Concept);
+		assert_eq!(urn.item_path, ["CURRENCY"]);
+		assert_eq!(dimensions[0].concept_id(), "CURRENCY");
+	}
+}
+
+#[cfg(test)]
+mod tests_structure_resolver {
+	use super::*;
+	use sdmx_json::structure::{Data, DataStructure, ResolveError, Resolved, Resolver, Urn};
+	use serde_json::json;
+
+	fn data() -> Data {
+		let file = read_to_string(fixture!("structure/exr-data-structure.json")).unwrap();
+		let structure: DataStructure = serde_json::from_str(&file).unwrap();
+		let mut newer = structure.clone();
+		newer.common.version = Some("1.2.0".to_owned());
+
+		let codelist = |id: &str, version: &str, codes: &[&str]| {
+			let codes: Vec<_> = codes.iter().map(|code| json!({ "id": code })).collect();
+			let codelist =
+				json!({ "id": id, "agencyID": "ECB", "version": version, "codes": codes });
+			serde_json::from_value(codelist).unwrap()
+		};
+		let concepts = [
+			"CURRENCY",
+			"FREQ",
+			"TIME_PERIOD",
+			"OBS_VALUE",
+			"TITLE",
+			"DECIMALS",
+			"UNIT_MULT",
+			"OBS_STATUS",
+		];
+		let concepts: Vec<_> = concepts.iter().map(|id| json!({ "id": id })).collect();
+		let scheme = json!({ "id": "ECB_CONCEPTS", "agencyID": "ECB", "version": "1.0", "concepts": concepts });
+		let dataflow = json!({
+			"id": "EXR",
+			"agencyID": "ECB",
+			"version": "1.0",
+			"structure": "urn:sdmx:org.sdmx.infomodel.datastructure.DataStructure=ECB:EXR(1.0+.0)",
+		});
+
+		Data {
+			data_structures: Some(vec![structure, newer]),
+			concept_schemas: Some(vec![serde_json::from_value(scheme).unwrap()]),
+			concepts: Some(vec![
+				codelist("CL_FREQ", "1.0", &["A", "M"]),
+				codelist("CL_CURRENCY", "1.0", &["USD"]),
+				codelist("CL_CURRENCY", "1.1", &["USD", "JPY"]),
+				codelist("CL_CURRENCY", "2.0", &["EUR"]),
+				codelist("CL_CURRENCY", "2.1.0-draft", &[]),
+			]),
+			dataflows: Some(vec![serde_json::from_value(dataflow).unwrap()]),
+			..Default::default()
+		}
+	}
+
+	fn urn(s: &str) -> Urn {
+		format!("urn:sdmx:org.sdmx.infomodel.{}", s)
+			.parse()
+			.unwrap()
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_resolve() {
+		let data = data();
+		let resolver = Resolver::new(&data);
+
+		let dataflow = &data.dataflows.as_ref().unwrap()[0];
+		let structure = resolver.resolve_str(dataflow.structure.as_ref().unwrap());
+		assert!(matches!(
+			structure,
+			Ok(Resolved::DataStructure(s)) if s.common.version.as_deref() == Some("1.2.0")
+		));
+		let exact = resolver.resolve(&urn("datastructure.DataStructure=ECB:EXR(1.0.0)"));
+		assert!(matches!(
+			exact,
+			Some(Resolved::DataStructure(s)) if s.common.version.as_deref() == Some("1.0.0")
+		));
+
+		let dimension = resolver.resolve(&urn("datastructure.Dimension=ECB:EXR(1.0.0).FREQ"));
+		let Some(Resolved::Dimension(dimension)) = dimension else {
+			panic!("{:?}", dimension);
+		};
+		let concept = resolver.resolve_str(&dimension.concept_identity);
+		assert!(matches!(concept, Ok(Resolved::Item(item)) if item.id == "FREQ"));
+		let time = resolver.resolve(&urn(
+			"datastructure.TimeDimension=ECB:EXR(1.0.0).TIME_PERIOD",
+		));
+		assert!(matches!(time, Some(Resolved::TimeDimension(_))));
+		let attribute = resolver.resolve(&urn(
+			"datastructure.DataAttribute=ECB:EXR(1.0.0).OBS_STATUS",
+		));
+		assert!(matches!(attribute, Some(Resolved::Attribute(a)) if a.id == "OBS_STATUS"));
+
+		// Version wildcards resolve to the latest stable version they match.
+		let codes = |version: &str| {
+			let urn = urn(&format!("codelist.Codelist=ECB:CL_CURRENCY({})", version));
+			let resolved = resolver.resolve(&urn)?;
+			let items = resolved.items()?.iter().map(|item| item.id.as_str());
+			Some(items.collect::<Vec<_>>())
+		};
+		assert_eq!(codes("1.0"), Some(vec!["USD"]));
+		assert_eq!(codes("1.0+"), Some(vec!["USD", "JPY"]));
+		assert_eq!(codes("1+.0"), Some(vec!["EUR"]));
+		assert_eq!(codes("*"), Some(vec!["EUR"]));
+		assert_eq!(codes("2.1.0-draft"), Some(vec![]));
+		assert_eq!(codes("3.0+"), None);
+
+		let code = resolver.resolve(&urn("codelist.Code=ECB:CL_FREQ(1.0).M"));
+		assert!(matches!(code, Some(Resolved::Item(item)) if item.id == "M"));
+		assert_eq!(
+			resolver.resolve(&urn("codelist.Code=ECB:CL_FREQ(1.0).Q")),
+			None
+		);
+		assert_eq!(
+			resolver.resolve(&urn("codelist.Codelist=ECB:CL_FREQ(1.0).A")),
+			None
+		);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_unresolved() {
+		let mut data = data();
+		let resolver = Resolver::new(&data);
+		let mut unresolved: Vec<_> = resolver
+			.unresolved()
+			.into_iter()
+			.map(|error| match error {
+				ResolveError::Unresolved(urn) => urn.id,
+				error => panic!("{}", error),
+			})
+			.collect();
+		unresolved.sort();
+		unresolved.dedup();
+		assert_eq!(unresolved, ["CL_DECIMALS", "CL_OBS_STATUS"]);
+
+		let dataflow = &mut data.dataflows.as_mut().unwrap()[0];
+		dataflow.structure = Some("ECB:EXR(1.0)".to_owned());
+		let resolver = Resolver::new(&data);
+		let invalid = resolver.unresolved().into_iter().find(
+			|error| matches!(error, ResolveError::InvalidUrn { urn, .. } if urn == "ECB:EXR(1.0)"),
+		);
+		assert!(invalid.is_some());
+	}
+}
+
+#[cfg(test)]
+mod tests_structure_validation {
+	use super::*;
+	use sdmx_json::structure::{Data, Diagnostic, ResolveError};
+	use serde_json::{json, Value};
+
+	fn data(structure: Value) -> Data {
+		let codelist = |id: &str, codes: &[&str], parent: Option<&str>| {
+			let codes: Vec<_> = codes.iter().map(|code| json!({ "id": code })).collect();
+			let parent =
+				parent.map(|p| format!("urn:sdmx:org.sdmx.infomodel.codelist.Codelist={}", p));
+			let codelist = json!({
+				"id": id,
+				"agencyID": "ECB",
+				"version": "1.0",
+				"codes": codes,
+				"parent": parent,
+			});
+			serde_json::from_value(codelist).unwrap()
+		};
+		let concepts = [
+			"CURRENCY",
+			"FREQ",
+			"TIME_PERIOD",
+			"OBS_VALUE",
+			"TITLE",
+			"DECIMALS",
+			"UNIT_MULT",
+			"OBS_STATUS",
+		];
+		let concepts: Vec<_> = concepts.iter().map(|id| json!({ "id": id })).collect();
+		let scheme = json!({ "id": "ECB_CONCEPTS", "agencyID": "ECB", "version": "1.0", "concepts": concepts });
+
+		Data {
+			data_structures: Some(vec![serde_json::from_value(structure).unwrap()]),
+			concept_schemas: Some(vec![serde_json::from_value(scheme).unwrap()]),
+			concepts: Some(vec![
+				codelist("CL_FREQ", &["A", "M"], None),
+				codelist("CL_CURRENCY", &["USD", "JPY"], Some("ECB:CL_FREQ(1.0)")),
+				codelist("CL_DECIMALS", &["0", "1", "2"], None),
+				codelist("CL_OBS_STATUS", &["A", "E"], None),
+			]),
+			..Default::default()
+		}
+	}
+
+	fn structure() -> Value {
+		let file = read_to_string(fixture!("structure/exr-data-structure.json")).unwrap();
+		serde_json::from_str(&file).unwrap()
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_valid_structure() {
+		assert_eq!(data(structure()).validate(), []);
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_invalid_structure() {
+		let mut structure = structure();
+		let components = structure.pointer_mut("/dataStructureComponents").unwrap();
+		components["dimensionList"]["dimensions"][0]["position"] = json!(4);
+		let attributes = &mut components["attributeList"]["attributes"];
+		attributes[1]["attributeRelationship"]["dimensions"]["dimensions"][1] = json!("REF_AREA");
+		attributes[2]["attributeRelationship"] = json!({ "groups": { "group": "SIBLING" } });
+
+		let mut data = data(structure);
+		let codelists = data.concepts.as_mut().unwrap();
+		codelists[0].parent =
+			Some("urn:sdmx:org.sdmx.infomodel.codelist.Codelist=ECB:CL_CURRENCY(1.0)".to_owned());
+		codelists[2].codes.as_mut().unwrap()[2].id = "0".to_owned();
+		codelists.push(codelists[3].clone());
+		codelists[3].parent =
+			Some("urn:sdmx:org.sdmx.infomodel.codelist.Codelist=ECB:CL_UNKNOWN(1.0)".to_owned());
+
+		let diagnostics: Vec<_> = data.validate().iter().map(|d| d.to_string()).collect();
+		let structure = "urn:sdmx:org.sdmx.infomodel.datastructure.DataStructure=ECB:EXR(1.0.0)";
+		let codelist = |id: &str| {
+			format!(
+				"urn:sdmx:org.sdmx.infomodel.codelist.Codelist=ECB:{}(1.0)",
+				id
+			)
+		};
+		assert_eq!(
+			diagnostics,
+			[
+				ResolveError::Unresolved(codelist("CL_UNKNOWN").parse().unwrap()).to_string(),
+				format!("missing dimension position 2 in {}", structure),
+				format!("missing dimension position 3 in {}", structure),
+				format!(
+					"attribute DECIMALS is related to unknown dimension REF_AREA in {}",
+					structure
+				),
+				format!(
+					"attribute UNIT_MULT is related to unknown group SIBLING in {}",
+					structure
+				),
+				format!("cycle in the parents of {}", codelist("CL_FREQ")),
+				format!("cycle in the parents of {}", codelist("CL_CURRENCY")),
+				format!("duplicate item 0 in {}", codelist("CL_DECIMALS")),
+				format!("duplicate artefact: {}", codelist("CL_OBS_STATUS")),
+			]
+		);
+		assert!(matches!(
+			data.validate().last(),
+			Some(Diagnostic::DuplicateArtefact(urn)) if urn.id == "CL_OBS_STATUS"
+		));
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_parent_cycle_of_equal_codelists() {
+		let mut data = data(structure());
+		let codelists = data.concepts.as_mut().unwrap();
+		codelists[0].parent =
+			Some("urn:sdmx:org.sdmx.infomodel.codelist.Codelist=ECB:CL_CURRENCY(1.0)".to_owned());
+		// an equal copy of CL_CURRENCY leads to the cycle, but is not part of it
+		codelists.push(codelists[1].clone());
+
+		let cycles: Vec<_> = data
+			.validate()
+			.into_iter()
+			.filter_map(|diagnostic| match diagnostic {
+				Diagnostic::ParentCycle(urn) => Some(urn.id),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(cycles, ["CL_FREQ", "CL_CURRENCY"]);
+	}
+}
+
+#[cfg(test)]
+mod tests_data_validation {
+	use super::*;
+	use sdmx_json::data::{DataMessageBuilder, NewObservation, ObservationLayout, ViolationKind};
+	use sdmx_json::primitives::SdmxValue;
+	use sdmx_json::structure::{Data, DataStructure, Resolver};
+	use serde_json::{json, Value};
+
+	fn data_structure() -> DataStructure {
+		let file = read_to_string(fixture!("structure/exr-data-structure.json")).unwrap();
+		let mut structure: Value = serde_json::from_str(&file).unwrap();
+		let components = &mut structure["dataStructureComponents"];
+		components["dimensionList"]["timeDimensions"]["localRepresentation"] = json!({
+			"format": { "dataType": "GregorianYear", "isMultilingual": false }
+		});
+		components["measureList"]["measures"][0]["localRepresentation"] = json!({
+			"format": {
+				"dataType": "Double",
+				"maxValue": 200,
+				"decimals": 1,
+				"pattern": "[0-9.]+",
+				"isMultilingual": false,
+				"sentinelValue": [{ "value": { "String": "NaN" } }]
+			}
+		});
+		serde_json::from_value(structure).unwrap()
+	}
+
+	fn codelists() -> Data {
+		let codelist = |id: &str, codes: &[&str]| {
+			let codes: Vec<_> = codes.iter().map(|code| json!({ "id": code })).collect();
+			let codelist = json!({ "id": id, "agencyID": "ECB", "version": "1.0", "codes": codes });
+			serde_json::from_value(codelist).unwrap()
+		};
+		Data {
+			concepts: Some(vec![
+				codelist("CL_FREQ", &["A", "M"]),
+				codelist("CL_CURRENCY", &["USD"]),
+				codelist("CL_DECIMALS", &["4"]),
+				codelist("CL_OBS_STATUS", &["A", "E"]),
+			]),
+			..Default::default()
+		}
+	}
+
+	fn observation(currency: &str, time: &str, value: SdmxValue) -> NewObservation {
+		NewObservation::default()
+			.dimension("FREQ", "A")
+			.dimension("CURRENCY", currency)
+			.dimension("TIME_PERIOD", time)
+			.measure("OBS_VALUE", value)
+			.attribute("DECIMALS", SdmxValue::String("4".to_owned()))
+	}
+
+	#[test]
+	#[cfg_attr(miri, ignore)]
+	fn test_validate_data() {
+		let structure = data_structure();
+		let codelists = codelists();
+		let resolver = Resolver::new(&codelists);
+
+		let status = |status: &str| SdmxValue::String(status.to_owned());
+		let observations = [
+			observation("USD", "2020", SdmxValue::Number(1.5)).attribute("OBS_STATUS", status("A")),
+			observation("USD", "2021", SdmxValue::String("NaN".to_owned()))
+				.attribute("OBS_STATUS", status("E")),
+			observation("USD", "2022", SdmxValue::Number(1.25))
+				.attribute("OBS_STATUS", status("X")),
+			observation("USD", "2023", SdmxValue::Number(-1.0))
+				.attribute("OBS_STATUS", status("A")),
+			observation("JPY", "2020-01", SdmxValue::Number(230.0)),
+		];
+		let mut builder = DataMessageBuilder::new(&structure, ObservationLayout::Series);
+		for observation in &observations {
+			builder.add_observation(observation).unwrap();
+		}
+		let message = builder.build();
+
+		let mut violations: Vec<_> = message
+			.validate(&structure, &resolver)
+			.unwrap()
+			.iter()
+			.map(|violation| violation.to_string())
+			.collect();
+		violations.sort();
+		assert_eq!(
+			violations,
+			[
+				"dataset 0, key A.JPY.2020-01: missing mandatory attribute OBS_STATUS",
+				"dataset 0, key A.JPY.2020-01: time period 2020-01 is not of type GregorianYear",
+				"dataset 0, key A.JPY.2020-01: unknown code JPY for component CURRENCY",
+				"dataset 0, key A.JPY.2020-01: value of measure OBS_VALUE does not respect MaxValue(200)",
+				"dataset 0, key A.USD.2022: unknown code X for component OBS_STATUS",
+				"dataset 0, key A.USD.2022: value of measure OBS_VALUE does not respect Decimals(1)",
+				"dataset 0, key A.USD.2023: value of measure OBS_VALUE does not respect Pattern(\"[0-9.]+\")",
+			]
+		);
+
+		// Every observation misses a dimension which is not in the message.
+		let mut other = structure.clone();
+		let components = other.data_structure_components.as_mut().unwrap();
+		let dimensions = components.dimension_list.dimensions.as_mut().unwrap();
+		let mut area = dimensions[0].clone();
+		area.id = Some("REF_AREA".to_owned());
+		area.position = 3;
+		area.local_representation = None;
+		dimensions.push(area);
+		let violations = message.validate(&other, &resolver).unwrap();
+		let missing = violations
+			.iter()
+			.filter(|v| v.kind == ViolationKind::MissingDimension("REF_AREA".to_owned()));
+		assert_eq!(missing.count(), observations.len());
+
+		// A dimension without an ID is identified by its concept.
+		let components = other.data_structure_components.as_mut().unwrap();
+		let dimensions = components.dimension_list.dimensions.as_mut().unwrap();
+		let area = dimensions.last_mut().unwrap();
+		area.id = None;
+		area.concept_identity =
+			"urn:sdmx:org.sdmx.infomodel.conceptscheme.Concept=ECB:ECB_CONCEPTS(1.0).REF_AREA"
+				.to_owned();
+		let violations = message.validate(&other, &resolver).unwrap();
+		let missing = violations
+			.iter()
+			.filter(|v| v.kind == ViolationKind::MissingDimension("REF_AREA".to_owned()));
+		assert_eq!(missing.count(), observations.len());
+	}
+}
+
+#[cfg(test)]
+mod tests_metadata_validation {
+	use sdmx_json::metadata::{MetadataMessage, ViolationKind};
+	use sdmx_json::structure::{Data, MetadataStructure, Resolver};
+	use serde_json::json;
+
+	fn metadata_structure() -> MetadataStructure {
+		let concept = |id: &str| {
+			format!(
+				"urn:sdmx:org.sdmx.infomodel.conceptscheme.Concept=ECB:ECB_CONCEPTS(1.0).{}",
+				id
+			)
+		};
+		let structure = json!({
+			"id": "MSD",
+			"agencyID": "ECB",
+			"version": "1.0",
+			"metadataStructureComponents": {
+				"metadataAttributeList": {
+					"id": "MetadataAttributeDescriptor",
+					"metadataAttributes": [
+						{
+							"id": "CONTACT",
+							"conceptIdentity": concept("CONTACT"),
+							"minOccurs": 1,
+							"maxOccurs": "Unbounded",
+							"isPresentational": true,
+							"metadataAttributes": [
+								{
+									"id": "EMAIL",
+									"conceptIdentity": concept("EMAIL"),
+									"minOccurs": 1,
+									"localRepresentation": {
+										"Format": {
+											"format": {
+												"dataType": "String",
+												"maxLength": 20,
+												"pattern": ".+@.+",
+												"isMultilingual": false
+											}
+										}
+									}
+								}
+							]
+						},
+						{
+							"id": "FREQ",
+							"conceptIdentity": concept("FREQ"),
+							"minOccurs": 0,
+							"maxOccurs": { "Signed": 2 },
+							"localRepresentation": {
+								"Enumeration": {
+									"enumeration": "urn:sdmx:org.sdmx.infomodel.codelist.Codelist=ECB:CL_FREQ(1.0)"
+								}
+							}
+						}
+					]
+				}
+			}
+		});
+		serde_json::from_value(structure).unwrap()
+	}
+
+	fn message(attributes: serde_json::Value) -> MetadataMessage {
+		let message = json!({
+			"data": {
+				"metadataSets": [{
+					"id": "REPORT",
+					"agencyID": "ECB",
+					"name": "Report",
+					"metadataflow": null,
+					"metadataProvisionAgreement": null,
+					"targets": [],
+					"attributes": attributes,
+				}]
+			}
+		});
+		serde_json::from_value(message).unwrap()
+	}
+
+	#[test]
+	fn test_validate_metadata() {
+		let structure = metadata_structure();
+		let codelist = json!({ "id": "CL_FREQ", "agencyID": "ECB", "version": "1.0", "codes": [{ "id": "A" }, { "id": "M" }] });
+		let codelists = Data {
+			concepts: Some(vec![serde_json::from_value(codelist).unwrap()]),
+			..Default::default()
+		};
+		let resolver = Resolver::new(&codelists);
+
+		let valid = message(json!([
+			{
+				"id": "CONTACT",
+				"value": null,
+				"attributes": [{ "id": "EMAIL", "value": "info@ecb.eu", "attributes": null }]
+			},
+			{ "id": "FREQ", "value": ["A", "M"], "attributes": null },
+		]));
+		assert_eq!(valid.validate(&structure, &resolver), []);
+
+		let invalid = message(json!([
+			{
+				"id": "CONTACT",
+				"value": "Contact",
+				"attributes": [
+					{ "id": "EMAIL", "value": "statistics.information@ecb.eu", "attributes": null },
+					{ "id": "PHONE", "value": "+49", "attributes": null },
+				]
+			},
+			{ "id": "CONTACT", "value": null, "attributes": [] },
+			{ "id": "FREQ", "value": "Q", "attributes": null },
+			{ "id": "FREQ", "value": "A", "attributes": null },
+			{ "id": "FREQ", "value": "M", "attributes": null },
+		]));
+		let violations: Vec<_> = invalid
+			.validate(&structure, &resolver)
+			.iter()
+			.map(|violation| violation.to_string())
+			.collect();
+		assert_eq!(
+			violations,
+			[
+				"metadata set 0, attribute FREQ: 3 occurrences instead of at most 2",
+				"metadata set 0, attribute CONTACT: presentational attribute has a value",
+				"metadata set 0, attribute CONTACT.EMAIL: value does not respect MaxLength(20)",
+				"metadata set 0, attribute CONTACT.PHONE: unknown attribute",
+				"metadata set 0, attribute CONTACT.EMAIL: 0 occurrences instead of at least 1",
+				"metadata set 0, attribute FREQ: unknown code Q",
+			]
+		);
+		let kinds: Vec<_> = invalid
+			.validate(&structure, &resolver)
+			.into_iter()
+			.map(|violation| violation.kind)
+			.collect();
+		assert!(kinds.contains(&ViolationKind::UnknownCode("Q".to_owned())));
+	}
 }